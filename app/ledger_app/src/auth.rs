@@ -0,0 +1,280 @@
+pub(crate) mod share;
+
+use std::io::BufRead;
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{
+        rand_core::{
+            OsRng,
+            RngCore,
+        },
+        PasswordHash,
+        PasswordHasher,
+        PasswordVerifier,
+        SaltString,
+    },
+    Argon2,
+};
+use axum::{
+    extract::{
+        Request,
+        State,
+    },
+    http::{
+        header,
+        HeaderMap,
+        Method,
+        StatusCode,
+    },
+    middleware::Next,
+    response::{
+        IntoResponse,
+        Response,
+    },
+    Json,
+};
+use base64::{
+    engine::general_purpose::STANDARD,
+    Engine,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use ledger_db::{
+    Db,
+    Transaction,
+    User,
+    UserRole,
+};
+
+use crate::{
+    AppState,
+    Error,
+};
+
+// the user a request is made by, None while auth.enabled is off
+#[derive(Debug, Clone)]
+pub(crate) struct CurrentUser(pub Option<User>);
+
+impl CurrentUser {
+
+    pub fn user_name(&self) -> Option<String> {
+        self.0.as_ref().map(|u| u.user_name.clone())
+    }
+
+    // everyone is the owner while auth.enabled is off
+    pub fn role(&self) -> UserRole {
+        self.0.as_ref().map(|u| u.user_role).unwrap_or(UserRole::Owner)
+    }
+
+    // the transaction as posted by the user
+    pub fn stamp(
+        &self,
+        transaction: Transaction,
+    ) -> Result<Transaction, Error> {
+        transaction.stamp(self.0.as_ref()).map_err(Error::from_posting)
+    }
+
+}
+
+// None is open to anyone, the rest needs the role or one above it
+fn required_role(method: &Method, path: &str) -> Option<UserRole> {
+    let read_only = matches!(*method, Method::GET | Method::HEAD)
+        || path == "/compare"
+        || path == "/import/rule/test"
+        || path.ends_with("/preview");
+    match path {
+        "/" => None,
+        p if p.starts_with("/admin") => Some(UserRole::Owner),
+        p if p == "/user/me" || p.starts_with("/user/me/")
+            => Some(UserRole::Accountant),
+        p if p == "/user" || p.starts_with("/user/") => Some(UserRole::Owner),
+        p if p == "/share" || p.starts_with("/share/") => Some(UserRole::Owner),
+        // the accountant asks the questions and marks the answers
+        p if p.starts_with("/review/") => Some(UserRole::Accountant),
+        // closing a reconciliation settles the bank account for good
+        p if p.starts_with("/reconcile/") && p.ends_with("/close")
+            => Some(UserRole::Owner),
+        _ if read_only => Some(UserRole::Accountant),
+        _ => Some(UserRole::Bookkeeper),
+    }
+}
+
+pub(crate) fn hash_password(password: &str) -> Result<String, Error> {
+    if password.is_empty() {
+        return Err(Error::UserError("the password is empty".to_string()));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default().hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| Error::UserError(e.to_string()))
+}
+
+fn verify_password(password_hash: &str, password: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default().verify_password(password.as_bytes(), &hash).is_ok()
+        })
+        .unwrap_or(false)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 32 random bytes, which need no slow hash to be kept safe
+pub(crate) fn new_token() -> String {
+    let mut bytes = [0_u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("ledger_{}", hex(&bytes))
+}
+
+pub(crate) fn token_hash(token: &str) -> String {
+    hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn unauthorized(message: &str) -> Error {
+    Error::Unauthorized(message.to_string())
+}
+
+async fn basic_user(db: &Db, credentials: &str) -> Result<User, Error> {
+    let decoded = STANDARD.decode(credentials.trim()).ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(unauthorized("malformed basic credentials"))?;
+    let (user_name, password) = decoded.split_once(':')
+        .ok_or(unauthorized("malformed basic credentials"))?;
+    let user = match User::by_name(db, user_name).await {
+        Ok(user) => user,
+        Err(ledger_db::Error::RowNotFound)
+            => return Err(unauthorized("wrong user name or password")),
+        Err(e) => return Err(e.into()),
+    };
+    // argon2 takes a while, which is better spent off the runtime
+    let (password_hash, password) = (user.password_hash.clone(), password.to_string());
+    let verified = tokio::task::spawn_blocking(move || {
+        verify_password(&password_hash, &password)
+    }).await.unwrap_or(false);
+    match verified {
+        true => Ok(user),
+        false => Err(unauthorized("wrong user name or password")),
+    }
+}
+
+async fn bearer_user(db: &Db, token: &str) -> Result<User, Error> {
+    match User::by_token(db, &token_hash(token.trim())).await {
+        Ok(user) => Ok(user),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(unauthorized("unknown token")),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// a password by Basic, so that a browser asks for it, or a token by
+// Bearer for the cli and scripts
+async fn user_of(db: &Db, headers: &HeaderMap) -> Result<User, Error> {
+    let authorization = headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(unauthorized("no credentials"))?;
+    match authorization.split_once(' ') {
+        Some((scheme, credentials)) if scheme.eq_ignore_ascii_case("basic")
+            => basic_user(db, credentials).await,
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer")
+            => bearer_user(db, token).await,
+        _ => Err(unauthorized("only basic and bearer are supported")),
+    }
+}
+
+fn error_response(status: StatusCode, e: Error) -> Response {
+    let challenge = match status {
+        StatusCode::UNAUTHORIZED => Some(
+            [(header::WWW_AUTHENTICATE, r#"Basic realm="ledger", charset="UTF-8""#)]
+        ),
+        _ => None,
+    };
+    (status, challenge, Json(e.into_api_response::<()>())).into_response()
+}
+
+fn status_of(e: &Error) -> StatusCode {
+    match e {
+        Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        Error::Forbidden(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// Finds the user of the request and checks the role against the route.
+// Handlers take the user as Extension<CurrentUser>. A request by a share
+// link has no user and reaches only the reports of the link.
+pub(crate) async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let Some(required) = required_role(request.method(), &path) else {
+        return next.run(request).await;
+    };
+    let shared = match state.config.auth.enabled {
+        true => share::token_of(request.uri(), request.headers()),
+        false => None,
+    };
+    if let Some(token) = shared {
+        return match share::check(&state, &token, request.method(), request.uri()).await {
+            Ok(_) => next.run(request).await,
+            Err(e) => error_response(status_of(&e), e),
+        };
+    }
+    let user = match state.config.auth.enabled {
+        true => match user_of(&state.db, request.headers()).await {
+            Ok(user) => CurrentUser(Some(user)),
+            Err(e) => return error_response(status_of(&e), e),
+        },
+        false => CurrentUser(None),
+    };
+    if user.role() < required {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            Error::Forbidden(format!("{} {}", request.method(), path)),
+        );
+    }
+    request.extensions_mut().insert(user);
+    next.run(request).await
+}
+
+// `ledger_app add-user NAME ROLE` with the password in LEDGER_PASSWORD
+// or on the first line of the standard input, for the first owner
+pub(crate) async fn add_user(db: &Db, args: &[String]) -> Result<(), Error> {
+    let [user_name, role] = args else {
+        return Err(Error::UserError(
+            "usage: ledger_app add-user NAME owner|bookkeeper|accountant"
+                .to_string()
+        ));
+    };
+    let user_role = parse_role(role)?;
+    let password = match std::env::var("LEDGER_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => {
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)
+                .map_err(|e| Error::UserError(e.to_string()))?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        },
+    };
+    User {
+        user_id: 0,
+        user_name: user_name.clone(),
+        password_hash: hash_password(&password)?,
+        user_role,
+    }.insert(db).await?;
+    println!("added {} as {}", user_name, user_role);
+    Ok(())
+}
+
+// owner, bookkeeper and accountant in any case, or in japanese
+pub(crate) fn parse_role(role: &str) -> Result<UserRole, Error> {
+    UserRole::parse(role.trim())
+        .ok_or(Error::UserError(format!("'{}' is not a role", role)))
+}
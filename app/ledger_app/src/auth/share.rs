@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::Query,
+    http::{
+        header,
+        HeaderMap,
+        Method,
+        Uri,
+    },
+};
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use chrono::NaiveDate;
+use hmac::{
+    Hmac,
+    Mac,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sha2::Sha256;
+
+use ledger_db::{
+    ShareLink,
+    ShareScope,
+};
+
+use crate::{
+    AppState,
+    Error,
+};
+
+use crate::handler::summary::{
+    get_period_month,
+    get_period_year,
+};
+
+use super::unauthorized;
+
+// what a share token is good for, signed so that it needs no lookup
+// but the one for the revocation
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    id: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+    scopes: Vec<String>,
+    exp: NaiveDate,
+}
+
+const TOKEN_PREFIX: &str = "share.";
+
+fn mac(secret: &str) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("hmac takes a key of any length")
+}
+
+// share.<claims>.<signature>, both in base64url
+pub(crate) fn sign(secret: &str, link: &ShareLink) -> Result<String, Error> {
+    if secret.is_empty() {
+        return Err(Error::ShareError("auth.share_secret is not set".to_string()));
+    }
+    let claims = Claims {
+        id: link.share_link_id,
+        from: link.period_start,
+        to: link.period_end,
+        scopes: link.scopes.iter().map(|s| s.to_string()).collect(),
+        exp: link.expires_on,
+    };
+    let json = serde_json::to_vec(&claims)
+        .map_err(|e| Error::ShareError(e.to_string()))?;
+    let payload = URL_SAFE_NO_PAD.encode(json);
+    let mut mac = mac(secret);
+    mac.update(payload.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+    Ok(format!("{}{}.{}", TOKEN_PREFIX, payload, signature))
+}
+
+fn verify(secret: &str, token: &str) -> Result<Claims, Error> {
+    let (payload, signature) = token.strip_prefix(TOKEN_PREFIX)
+        .and_then(|t| t.split_once('.'))
+        .ok_or(unauthorized("malformed share link"))?;
+    let signature = URL_SAFE_NO_PAD.decode(signature)
+        .map_err(|_| unauthorized("malformed share link"))?;
+    let mut mac = mac(secret);
+    mac.update(payload.as_bytes());
+    mac.verify_slice(&signature)
+        .map_err(|_| unauthorized("the share link is not ours"))?;
+    URL_SAFE_NO_PAD.decode(payload).ok()
+        .and_then(|json| serde_json::from_slice::<Claims>(&json).ok())
+        .ok_or(unauthorized("malformed share link"))
+}
+
+// ?share=... for a link opened in a browser, or Bearer share....
+pub(super) fn token_of(uri: &Uri, headers: &HeaderMap) -> Option<String> {
+    let from_query = Query::<HashMap<String, String>>::try_from_uri(uri).ok()
+        .and_then(|Query(query)| query.get("share").cloned());
+    from_query.or_else(|| {
+        headers.get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim())
+            .filter(|token| token.starts_with(TOKEN_PREFIX))
+            .map(|token| token.to_string())
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportRange {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+fn scope_of_stage(stage: &str) -> Option<ShareScope> {
+    match stage {
+        "from_prev" | "in_term" | "kessan" => Some(ShareScope::TrialBalance),
+        "soneki" | "to_next" => Some(ShareScope::FinancialStatements),
+        _ => None,
+    }
+}
+
+// the report of the route and the period it covers
+fn scope_of(
+    uri: &Uri,
+    start_month: u32,
+) -> Option<(ShareScope, (NaiveDate, NaiveDate))> {
+    let year = |y: &str| get_period_year(y.parse().ok()?, start_month);
+    let month = |y: &str, m: &str| get_period_month(y.parse().ok()?, m.parse().ok()?);
+    let segments = uri.path().trim_matches('/').split('/').collect::<Vec<&str>>();
+    match segments.as_slice() {
+        ["journal", y, m] => Some((ShareScope::Journals, month(y, m)?)),
+        ["export", _] => {
+            let Query(range) = Query::<ExportRange>::try_from_uri(uri).ok()?;
+            Some((ShareScope::Journals, (range.from, range.to)))
+        },
+        ["general_ledger", y, _] => Some((ShareScope::GeneralLedger, year(y)?)),
+        ["summary", y] => Some((ShareScope::TrialBalance, year(y)?)),
+        ["summary", y, m] if m.parse::<u32>().is_ok()
+            => Some((ShareScope::TrialBalance, month(y, m)?)),
+        ["summary", y, stage] => Some((scope_of_stage(stage)?, year(y)?)),
+        ["summary", y, m, stage] => Some((scope_of_stage(stage)?, month(y, m)?)),
+        ["cash_flow", y] => Some((ShareScope::FinancialStatements, year(y)?)),
+        _ => None,
+    }
+}
+
+// A share link reads the reports of its scopes within its period
+// until it expires or is revoked, and nothing else.
+pub(super) async fn check(
+    state: &AppState,
+    token: &str,
+    method: &Method,
+    uri: &Uri,
+) -> Result<(), Error> {
+    let secret = &state.config.auth.share_secret;
+    if secret.is_empty() {
+        return Err(unauthorized("share links are not enabled"));
+    }
+    let claims = verify(secret, token)?;
+    if claims.exp < state.today() {
+        return Err(unauthorized("the share link has expired"));
+    }
+    match ShareLink::by_id(&state.db, claims.id).await {
+        Ok(link) if link.revoked_at.is_none() => (),
+        Ok(_) => return Err(unauthorized("the share link is revoked")),
+        Err(ledger_db::Error::RowNotFound)
+            => return Err(unauthorized("unknown share link")),
+        Err(e) => return Err(e.into()),
+    };
+    let forbidden = || Error::Forbidden(format!("{} {}", method, uri.path()));
+    if !matches!(*method, Method::GET | Method::HEAD) {
+        return Err(forbidden());
+    }
+    let (scope, (start, end)) = scope_of(uri, state.config.fiscal_year.start_month)
+        .ok_or_else(forbidden)?;
+    let granted = claims.scopes.iter().any(|s| s == &scope.to_string());
+    match granted && claims.from <= start && end <= claims.to {
+        true => Ok(()),
+        false => Err(forbidden()),
+    }
+}
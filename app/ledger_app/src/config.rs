@@ -0,0 +1,299 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono_tz::Tz;
+use log::LevelFilter;
+use serde::Deserialize;
+use serde_json::{
+    Map,
+    Value,
+};
+use toml_edit::{
+    DocumentMut,
+    Item,
+    Value as TomlValue,
+};
+
+use crate::Error;
+
+// read when LEDGER_CONFIG does not name another file
+const DEFAULT_CONFIG_FILE: &str = "ledger.toml";
+
+// LEDGER_DATABASE_URL overrides database.url and so on
+const ENV_PREFIX: &str = "LEDGER_";
+const ENV_KEYS: &[(&str, &str)] = &[
+    ("database", "url"),
+    ("database", "max_connections"),
+    ("database", "acquire_timeout_secs"),
+    ("database", "migrate_on_startup"),
+    ("server", "listen"),
+    ("locale", "timezone"),
+    ("fiscal_year", "start_month"),
+    ("log", "level"),
+    ("auth", "enabled"),
+    ("auth", "share_secret"),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    pub acquire_timeout_secs: u64,
+    // off leaves the migrations to `ledger_app migrate`
+    pub migrate_on_startup: bool,
+}
+
+impl Default for DatabaseConfig {
+
+    fn default() -> Self {
+        DatabaseConfig {
+            url: "postgres://postgres:postgres@db:5432/ledger".to_string(),
+            max_connections: 5,
+            acquire_timeout_secs: 30,
+            migrate_on_startup: true,
+        }
+    }
+
+}
+
+impl DatabaseConfig {
+
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_secs(self.acquire_timeout_secs)
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ServerConfig {
+    pub listen: SocketAddr,
+}
+
+impl Default for ServerConfig {
+
+    fn default() -> Self {
+        ServerConfig { listen: SocketAddr::from(([0, 0, 0, 0], 2480)) }
+    }
+
+}
+
+// "today" of the journals and of the recurring entries
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct LocaleConfig {
+    pub timezone: Tz,
+}
+
+impl Default for LocaleConfig {
+
+    fn default() -> Self {
+        LocaleConfig { timezone: chrono_tz::Japan }
+    }
+
+}
+
+// The year of /summary/{y}, /budget/{fy} and the like begins on the
+// first day of start_month of y. 4 makes 2026 run through 2027-03-31.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct FiscalYearConfig {
+    pub start_month: u32,
+}
+
+impl Default for FiscalYearConfig {
+
+    fn default() -> Self {
+        FiscalYearConfig { start_month: 1 }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct LogConfig {
+    pub level: String,
+}
+
+impl Default for LogConfig {
+
+    fn default() -> Self {
+        LogConfig { level: "info".to_string() }
+    }
+
+}
+
+impl LogConfig {
+
+    pub fn level_filter(&self) -> LevelFilter {
+        LevelFilter::from_str(&self.level).unwrap_or(LevelFilter::Info)
+    }
+
+}
+
+// Off opens every route to anyone who reaches the port, as before
+// there were users. Only for a machine of one's own.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AuthConfig {
+    pub enabled: bool,
+    // signs the share links, which are not made while it is empty
+    pub share_secret: String,
+}
+
+impl Default for AuthConfig {
+
+    fn default() -> Self {
+        AuthConfig { enabled: true, share_secret: String::new() }
+    }
+
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    pub database: DatabaseConfig,
+    pub server: ServerConfig,
+    pub locale: LocaleConfig,
+    pub fiscal_year: FiscalYearConfig,
+    pub log: LogConfig,
+    pub auth: AuthConfig,
+}
+
+fn config_error(message: impl Into<String>) -> Error {
+    Error::ConfigError(message.into())
+}
+
+fn json_of_toml(value: &TomlValue) -> Value {
+    match value {
+        TomlValue::String(s) => Value::from(s.value().clone()),
+        TomlValue::Integer(i) => Value::from(*i.value()),
+        TomlValue::Float(f) => Value::from(*f.value()),
+        TomlValue::Boolean(b) => Value::from(*b.value()),
+        TomlValue::Datetime(d) => Value::from(d.value().to_string()),
+        TomlValue::Array(a) => Value::Array(a.iter().map(json_of_toml).collect()),
+        TomlValue::InlineTable(t) => Value::Object(
+            t.iter().map(|(k, v)| (k.to_string(), json_of_toml(v))).collect()
+        ),
+    }
+}
+
+fn json_of_item(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => json_of_toml(value),
+        Item::Table(table) => Value::Object(
+            table.iter().map(|(k, v)| (k.to_string(), json_of_item(v))).collect()
+        ),
+        Item::ArrayOfTables(tables) => Value::Array(
+            tables.iter()
+                .map(|t| Value::Object(
+                    t.iter()
+                        .map(|(k, v)| (k.to_string(), json_of_item(v)))
+                        .collect()
+                ))
+                .collect()
+        ),
+    }
+}
+
+// the file as JSON, so that the settings are read by serde
+fn read_file(path: &Path) -> Result<Value, Error> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        config_error(format!("{}: {}", path.display(), e))
+    })?;
+    let document = text.parse::<DocumentMut>().map_err(|e| {
+        config_error(format!("{}: {}", path.display(), e))
+    })?;
+    Ok(json_of_item(document.as_item()))
+}
+
+// numbers and booleans of the environment are taken as such
+fn json_of_env(value: &str) -> Value {
+    if let Ok(i) = value.parse::<i64>() { return Value::from(i); }
+    if let Ok(b) = value.parse::<bool>() { return Value::from(b); }
+    Value::from(value)
+}
+
+fn apply_env(
+    root: &mut Value,
+    vars: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), Error> {
+    if root.is_null() { *root = Value::Object(Map::new()); }
+    for (section, key) in ENV_KEYS {
+        let name = format!(
+            "{}{}_{}", ENV_PREFIX, section.to_uppercase(), key.to_uppercase(),
+        );
+        let Some(value) = vars(&name) else { continue };
+        let table = root.as_object_mut()
+            .and_then(|r| {
+                r.entry(section.to_string())
+                    .or_insert(Value::Object(Map::new()))
+                    .as_object_mut()
+            })
+            .ok_or(config_error(format!("'{}' is not a table", section)))?;
+        table.insert(key.to_string(), json_of_env(&value));
+    }
+    Ok(())
+}
+
+impl Config {
+
+    // The defaults, then the file, then LEDGER_* of the environment.
+    // A file named by LEDGER_CONFIG must exist, ledger.toml need not.
+    pub fn load() -> Result<Self, Error> {
+        let (path, required) = match std::env::var("LEDGER_CONFIG") {
+            Ok(path) => (path, true),
+            Err(_) => (DEFAULT_CONFIG_FILE.to_string(), false),
+        };
+        let path = Path::new(&path);
+        let mut root = match path.exists() || required {
+            true => read_file(path)?,
+            false => Value::Object(Map::new()),
+        };
+        apply_env(&mut root, &|name| std::env::var(name).ok())?;
+
+        let config: Config = serde_path_to_error::deserialize(root)
+            .map_err(|e| config_error(format!("{}: {}", e.path(), e.inner())))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let url = &self.database.url;
+        if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+            return Err(config_error(
+                "database.url: a postgres:// url is expected"
+            ));
+        }
+        if self.database.max_connections == 0 {
+            return Err(config_error(
+                "database.max_connections: at least 1 is expected"
+            ));
+        }
+        if !(1..=12).contains(&self.fiscal_year.start_month) {
+            return Err(config_error(format!(
+                "fiscal_year.start_month: {} is not a month",
+                self.fiscal_year.start_month,
+            )));
+        }
+        if LevelFilter::from_str(&self.log.level).is_err() {
+            return Err(config_error(format!(
+                "log.level: '{}' is not one of off, error, warn, info, \
+                 debug and trace",
+                self.log.level,
+            )));
+        }
+        let secret = &self.auth.share_secret;
+        if !secret.is_empty() && secret.len() < 32 {
+            return Err(config_error(
+                "auth.share_secret: at least 32 characters are expected"
+            ));
+        }
+        Ok(())
+    }
+
+}
@@ -1,4 +1,21 @@
 pub mod account;
 pub mod journal;
+pub mod review;
 pub mod summary;
+pub mod general_ledger;
+pub mod compare;
+pub mod budget;
+pub mod cash_flow;
+pub mod currency;
+pub mod import;
+pub mod partner;
 
+pub mod reconcile;
+pub mod payment;
+pub mod recurring;
+pub mod interop;
+pub mod plain_text;
+pub mod admin;
+pub mod ui;
+pub mod user;
+pub mod share;
@@ -45,6 +45,7 @@ struct Account {
 
 impl Account {
 
+    #[allow(clippy::wrong_self_convention)]
     fn into_db_account(&self) -> ledger_db::Account {
         ledger_db::Account {
             account_id: 0,
@@ -81,7 +82,7 @@ async fn show_account(
         ),
     };
     let acc = db_acc.iter()
-        .map(|a| Account::from_db_account(a))
+        .map(Account::from_db_account)
         .collect::<Vec<Account>>();
     (StatusCode::OK, Json(AccountOutput::ok(acc)))
 }
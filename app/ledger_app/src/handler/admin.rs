@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        DefaultBodyLimit,
+        Query,
+        State,
+    },
+    http::{
+        header,
+        StatusCode,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::{
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AppliedMigration,
+    Backup,
+    Db,
+    Migration,
+    TableRows,
+    UserRole,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+// a backup is one JSON document of every table, however large
+const RESTORE_BODY_LIMIT: usize = 1024 * 1024 * 1024;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/schema", get(show_schema))
+    .route("/backup", get(backup))
+    .route("/restore", post(restore))
+    .route("/restore/validate", post(validate))
+    .layer(DefaultBodyLimit::max(RESTORE_BODY_LIMIT))
+}
+
+#[derive(Debug, Serialize)]
+struct TableCount {
+    table: String,
+    rows: u64,
+}
+
+// only into a database without any journal when empty is set
+#[derive(Debug, Deserialize)]
+struct RestoreQuery {
+    #[serde(default)]
+    empty: bool,
+}
+
+type RestoreOutput = ApiResponse<Vec<TableCount>>;
+
+#[derive(Debug, Serialize)]
+struct SchemaMigration {
+    version: i64,
+    name: String,
+    applied_at: String,
+    baseline: bool,
+}
+
+impl SchemaMigration {
+
+    fn from_db_migration(migration: &AppliedMigration) -> Self {
+        SchemaMigration {
+            version: migration.version,
+            name: migration.name.clone(),
+            applied_at: migration.applied_at.to_string(),
+            baseline: migration.baseline,
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct Schema {
+    version: i64,
+    latest_version: i64,
+    migrations: Vec<SchemaMigration>,
+}
+
+type SchemaOutput = ApiResponse<Schema>;
+
+async fn schema_of(db: &Db) -> Result<Schema, Error> {
+    let applied = Migration::applied(db).await?;
+    Ok(Schema {
+        version: applied.last().map(|a| a.version).unwrap_or(0),
+        latest_version: Migration::latest_version(),
+        migrations: applied.iter()
+            .map(SchemaMigration::from_db_migration)
+            .collect(),
+    })
+}
+
+async fn show_schema(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<SchemaOutput>) {
+    match schema_of(&state.db).await {
+        Ok(schema) => (StatusCode::OK, Json(SchemaOutput::ok(schema))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn backup_file(state: &AppState) -> Result<Backup, Error> {
+    Ok(Backup::dump(&state.db, state.now().fixed_offset()).await?)
+}
+
+async fn backup(
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    match backup_file(&state).await {
+        Ok(backup) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"ledger_backup_{}.json\"",
+                    backup.created_at.format("%Y%m%d%H%M%S"),
+                ),
+            )],
+            Json(backup),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json::<ApiResponseWithoutBody>(e.into_api_response()),
+        ).into_response(),
+    }
+}
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::DatabaseNotEmpty => StatusCode::CONFLICT,
+        // the backup broke a constraint of the schema
+        Error::DataBaseError(ledger_db::Error::SqlError(_))
+            => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+// A restore replaces the users too when the backup has them, so that
+// one through the app must bring an owner with it. Older backups go
+// through `ledger restore`.
+fn holds_owner(tables: &[TableRows]) -> bool {
+    let Some(users) = tables.iter().find(|t| t.table_name == "users") else {
+        return true;
+    };
+    serde_json::from_str::<Vec<UserRow>>(&users.rows)
+        .unwrap_or_default()
+        .iter()
+        .any(|u| UserRole::parse(&u.user_role) == Some(UserRole::Owner))
+}
+
+#[derive(Debug, Deserialize)]
+struct UserRow {
+    user_role: String,
+}
+
+// recurring entries are not posted while the rows are replaced
+async fn restore_backup(
+    state: &AppState,
+    query: &RestoreQuery,
+    backup: &Backup,
+    dry_run: bool,
+) -> Result<Vec<TableCount>, Error> {
+    let tables = backup.table_rows(
+        Migration::current_version(&state.db).await?,
+    )?;
+    if state.config.auth.enabled && !holds_owner(&tables) {
+        return Err(Error::BackupError(
+            "the backup holds no owner to sign in with".to_string()
+        ));
+    }
+    let _running = state.scheduler.lock().await;
+    if query.empty && TableRows::holds_journals(&state.db).await? {
+        return Err(Error::DatabaseNotEmpty);
+    }
+    let counts = TableRows::restore(&state.db, &tables, dry_run).await?;
+    Ok(counts.into_iter()
+        .map(|c| TableCount { table: c.table_name, rows: c.row_count })
+        .collect())
+}
+
+async fn restore(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RestoreQuery>,
+    Json(input): Json<Backup>,
+) -> (StatusCode, Json<RestoreOutput>) {
+    match restore_backup(&state, &query, &input, false).await {
+        Ok(counts) => (
+            StatusCode::OK,
+            Json(RestoreOutput::ok_with(
+                format!("restored the backup of {}", input.created_at),
+                counts,
+            )),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// the rows a restore would put in, without keeping them
+async fn validate(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RestoreQuery>,
+    Json(input): Json<Backup>,
+) -> (StatusCode, Json<RestoreOutput>) {
+    match restore_backup(&state, &query, &input, true).await {
+        Ok(counts) => (StatusCode::OK, Json(RestoreOutput::ok(counts))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
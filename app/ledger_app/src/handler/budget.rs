@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AccountType,
+    Budget as DbBudget,
+    Db,
+    TransactionType,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use super::summary::{
+    balance_of,
+    db_summary_by_stage,
+    get_period_fiscal_month,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{fy}", get(show_budget).post(set_budget))
+    .route("/{fy}/report", get(show_year_report))
+    .route("/{fy}/copy/{src}", post(copy_actual))
+    .route("/{fy}/{m}/report", get(show_month_report))
+    .route("/{fy}/{m}/{account}", delete(delete_budget))
+}
+
+#[derive(Debug, Serialize)]
+struct Budget {
+    account_name: String,
+    month: u32,
+    amount: f32,
+}
+
+impl Budget {
+
+    fn from_db_budget(db_budget: &DbBudget) -> Self {
+        Budget {
+            account_name: db_budget.account_name.clone(),
+            month: db_budget.month,
+            amount: db_budget.amount,
+        }
+    }
+
+}
+
+// month omitted sets the same amount for all twelve months.
+#[derive(Debug, Deserialize)]
+struct BudgetInput {
+    account: String,
+    month: Option<u32>,
+    amount: f32,
+}
+
+impl BudgetInput {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_budgets(&self, fiscal_year: i32) -> Vec<DbBudget> {
+        let months = match self.month {
+            Some(m) => vec![m],
+            None => (1..=12).collect(),
+        };
+        months.into_iter().map(|month| DbBudget {
+            budget_id: 0,
+            account_id: 0,
+            account_name: self.account.clone(),
+            account_type: AccountType::Expense,
+            fiscal_year,
+            month,
+            amount: self.amount,
+        }).collect()
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct BudgetReport {
+    account_name: String,
+    account_type: String,
+    budget: f32,
+    actual: f32,
+    variance: f32,
+    consumption: Option<f32>,
+}
+
+type BudgetOutput = ApiResponse<Vec<Budget>>;
+type BudgetReportOutput = ApiResponse<Vec<BudgetReport>>;
+
+fn is_profit_and_loss(account_type: &AccountType) -> bool {
+    matches!(account_type, AccountType::Income | AccountType::Expense)
+}
+
+async fn budget_report(
+    db: &Db,
+    budgets: &[DbBudget],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<BudgetReport>, Error> {
+    // (budget, actual) keyed like the summary ordering
+    let mut rows
+        = BTreeMap::<(AccountType, i32), (String, f32, f32)>::new();
+    for b in budgets {
+        let row = rows.entry((b.account_type.clone(), b.account_id))
+            .or_insert_with(|| (b.account_name.clone(), 0_f32, 0_f32));
+        row.1 += b.amount;
+    }
+    let actual
+        = db_summary_by_stage(db, &TransactionType::InTerm, start, end)
+        .await?;
+    for s in actual.iter().filter(|s| is_profit_and_loss(&s.account_type)) {
+        let row = rows.entry((s.account_type.clone(), s.account_id))
+            .or_insert_with(|| (s.account_name.clone(), 0_f32, 0_f32));
+        row.2 += balance_of(s);
+    }
+
+    Ok(rows.into_iter()
+        .map(|((account_type, _), (account_name, budget, actual))| {
+            let consumption = if budget == 0_f32 {
+                None
+            } else {
+                Some(actual / budget * 100_f32)
+            };
+            BudgetReport {
+                account_name,
+                account_type: account_type.into_japanese(),
+                budget,
+                actual,
+                variance: actual - budget,
+                consumption,
+            }
+        })
+        .collect::<Vec<BudgetReport>>())
+}
+
+async fn show_budget(
+    Path(fy): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<BudgetOutput>) {
+    let db_budget = match DbBudget::by_year(&state.db, fy).await {
+        Ok(b) => b,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let budgets = db_budget.iter()
+        .map(Budget::from_db_budget)
+        .collect::<Vec<Budget>>();
+    (StatusCode::OK, Json(BudgetOutput::ok(budgets)))
+}
+
+async fn set_budget(
+    Path(fy): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<Vec<BudgetInput>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    let mut budgets = Vec::new();
+    for bi in &input {
+        if bi.month.is_some_and(|m| !(1..=12).contains(&m)) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(Error::DateTimeError(
+                    format!("{}-{}", fy, bi.month.unwrap_or(0))
+                ).into_api_response()),
+            );
+        }
+        budgets.extend(bi.into_db_budgets(fy));
+    }
+    if let Err(e) = DbBudget::upsert(&state.db, &budgets).await {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::from(e).into_api_response()),
+        );
+    }
+    (StatusCode::CREATED, Json(ApiResponse::ok_only()))
+}
+
+async fn delete_budget(
+    Path(fyma): Path<(i32, u32, String)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbBudget::delete(&state.db, &fyma.2, fyma.0, fyma.1).await {
+        Ok(0) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::BudgetNotFound(
+                format!("{} {}-{}", fyma.2, fyma.0, fyma.1)
+            ).into_api_response()),
+        ),
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+// the months of the source year are read first and stored in one go,
+// so that a failure leaves the budget as it was
+async fn copy_actual_months(
+    db: &Db,
+    fiscal_year: i32,
+    source_year: i32,
+    start_month: u32,
+) -> Result<u64, Error> {
+    let mut budgets = Vec::new();
+    for month in 1..=12 {
+        let (start, end)
+            = get_period_fiscal_month(source_year, month, start_month)
+            .ok_or(Error::DateTimeError(
+                format!("{}-{}-1", source_year, month)
+            ))?;
+        let actual
+            = db_summary_by_stage(db, &TransactionType::InTerm, start, end)
+            .await?;
+        budgets.extend(actual.iter()
+            .filter(|s| is_profit_and_loss(&s.account_type))
+            .map(|s| DbBudget {
+                budget_id: 0,
+                account_id: s.account_id,
+                account_name: s.account_name.clone(),
+                account_type: s.account_type.clone(),
+                fiscal_year,
+                month,
+                amount: balance_of(s),
+            }));
+    }
+    Ok(DbBudget::upsert(db, &budgets).await?)
+}
+
+async fn copy_actual(
+    Path(fys): Path<(i32, i32)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match copy_actual_months(
+        &state.db,
+        fys.0,
+        fys.1,
+        state.config.fiscal_year.start_month,
+    ).await {
+        Ok(count) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::ok_only_with(
+                format!("{} budgets copied from {}", count, fys.1)
+            )),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn show_year_report(
+    Path(fy): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<BudgetReportOutput>) {
+    // from the first day of the first month to the last day of the last
+    let start_month = state.config.fiscal_year.start_month;
+    let first = get_period_fiscal_month(fy, start_month, start_month);
+    let last = get_period_fiscal_month(fy, (start_month + 10) % 12 + 1, start_month);
+    let (start, end) = match first.zip(last) {
+        Some(((start, _), (_, end))) => (start, end),
+        None => return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::DateTimeError(format!("year {}", fy))
+                .into_api_response()),
+        ),
+    };
+    let budgets = match DbBudget::by_year(&state.db, fy).await {
+        Ok(b) => b,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    match budget_report(&state.db, &budgets, start, end).await {
+        Ok(r) => (StatusCode::OK, Json(BudgetReportOutput::ok(r))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn show_month_report(
+    Path(fym): Path<(i32, u32)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<BudgetReportOutput>) {
+    let start_month = state.config.fiscal_year.start_month;
+    let (start, end) = match get_period_fiscal_month(fym.0, fym.1, start_month) {
+        Some(p) => p,
+        None => return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::DateTimeError(format!("{}-{}-1", fym.0, fym.1))
+                .into_api_response()),
+        ),
+    };
+    let budgets = match DbBudget::by_month(&state.db, fym.0, fym.1).await {
+        Ok(b) => b,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    match budget_report(&state.db, &budgets, start, end).await {
+        Ok(r) => (StatusCode::OK, Json(BudgetReportOutput::ok(r))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::get,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AccountCashFlow,
+    AccountType,
+    CashFlowType,
+    Db,
+    TransactionType,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use super::summary::{
+    balance_of,
+    db_summary_by_stage,
+    get_period_year,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/class", get(show_class).post(set_class))
+    .route("/{y}", get(show_year))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CashFlowClass {
+    account_name: String,
+    cash_flow_type: String,
+}
+
+impl CashFlowClass {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_cash_flow(&self) -> Result<AccountCashFlow, Error> {
+        // a typo is not taken as 対象外, which would drop the account
+        let cash_flow_type = CashFlowType::parse(&self.cash_flow_type)
+            .ok_or(Error::CashFlowClassError(self.cash_flow_type.clone()))?;
+        Ok(AccountCashFlow {
+            account_id: 0,
+            account_name: self.account_name.clone(),
+            account_type: AccountType::Asset,
+            cash_flow_type,
+        })
+    }
+
+    fn from_db_cash_flow(cash_flow: &AccountCashFlow) -> Self {
+        CashFlowClass {
+            account_name: cash_flow.account_name.clone(),
+            cash_flow_type: cash_flow.cash_flow_type.into_japanese(),
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct CashFlowItem {
+    account_name: String,
+    amount: f32,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct CashFlowSection {
+    items: Vec<CashFlowItem>,
+    total: f32,
+}
+
+impl CashFlowSection {
+
+    fn push(&mut self, account_name: &str, amount: f32) {
+        if amount == 0_f32 { return; }
+        self.items.push(CashFlowItem {
+            account_name: account_name.to_string(),
+            amount,
+        });
+        self.total += amount;
+    }
+
+}
+
+#[derive(Debug, Default, Serialize)]
+struct CashFlowStatement {
+    net_income: f32,
+    operating: CashFlowSection,
+    investing: CashFlowSection,
+    financing: CashFlowSection,
+    net_change: f32,
+    opening_cash: f32,
+    closing_cash: f32,
+    difference: f32,
+}
+
+type CashFlowClassInput = CashFlowClass;
+type CashFlowClassOutput = ApiResponse<Vec<CashFlowClass>>;
+type CashFlowOutput = ApiResponse<CashFlowStatement>;
+
+// Indirect method: net income adjusted by the change of each balance
+// sheet account between the opening (from_prev) and the closing
+// position. The closing position is taken at the kessan stage, since
+// the to_next entries move every balance into the carry-forward
+// accounts and would leave nothing to compare.
+async fn statement(
+    db: &Db,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<CashFlowStatement, Error> {
+    let classes = AccountCashFlow::all(db).await?.into_iter()
+        .map(|cf| (cf.account_id, cf.cash_flow_type))
+        .collect::<HashMap<i32, CashFlowType>>();
+    let opening
+        = db_summary_by_stage(db, &TransactionType::FromPrev, start, end)
+        .await?.iter()
+        .map(|s| (s.account_id, balance_of(s)))
+        .collect::<HashMap<i32, f32>>();
+    let closing
+        = db_summary_by_stage(db, &TransactionType::Kessan, start, end)
+        .await?;
+
+    let mut cfs = CashFlowStatement::default();
+    let mut seen = Vec::new();
+    for s in &closing {
+        seen.push(s.account_id);
+        let balance = balance_of(s);
+        match s.account_type {
+            AccountType::Income => { cfs.net_income += balance; continue; },
+            AccountType::Expense => { cfs.net_income -= balance; continue; },
+            AccountType::UtilDebit | AccountType::UtilCredit => continue,
+            _ => (),
+        }
+        let class = classes.get(&s.account_id).cloned()
+            .unwrap_or(CashFlowType::default_for(&s.account_type));
+        let open = opening.get(&s.account_id).cloned().unwrap_or(0_f32);
+        let change = balance - open;
+        // an increase of an asset consumes cash,
+        // an increase of a liability or equity provides it
+        let effect = match s.account_type {
+            AccountType::Asset => -change,
+            _ => change,
+        };
+        match class {
+            CashFlowType::Cash => {
+                cfs.opening_cash += open;
+                cfs.closing_cash += balance;
+            },
+            CashFlowType::Operating
+                => cfs.operating.push(&s.account_name, effect),
+            CashFlowType::Investing
+                => cfs.investing.push(&s.account_name, effect),
+            CashFlowType::Financing
+                => cfs.financing.push(&s.account_name, effect),
+            CashFlowType::Excluded => (),
+        }
+    }
+
+    // cash accounts only present at the opening were emptied in term
+    for (account_id, open) in &opening {
+        if seen.contains(account_id) { continue; }
+        if classes.get(account_id) == Some(&CashFlowType::Cash) {
+            cfs.opening_cash += open;
+        }
+    }
+
+    cfs.operating.total += cfs.net_income;
+    cfs.net_change
+        = cfs.operating.total + cfs.investing.total + cfs.financing.total;
+    cfs.difference
+        = cfs.closing_cash - cfs.opening_cash - cfs.net_change;
+    Ok(cfs)
+}
+
+async fn show_year(
+    Path(y): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CashFlowOutput>) {
+    let (start, end) = match get_period_year(y, state.config.fiscal_year.start_month) {
+        Some(p) => p,
+        None => return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::DateTimeError(format!("year {}", y))
+                .into_api_response()),
+        ),
+    };
+    match statement(&state.db, start, end).await {
+        Ok(cfs) => (StatusCode::OK, Json(CashFlowOutput::ok(cfs))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn show_class(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CashFlowClassOutput>) {
+    let db_cf = match AccountCashFlow::all(&state.db).await {
+        Ok(cf) => cf,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let classes = db_cf.iter()
+        .filter(|cf| cf.cash_flow_type != CashFlowType::Excluded)
+        .map(CashFlowClass::from_db_cash_flow)
+        .collect::<Vec<CashFlowClass>>();
+    (StatusCode::OK, Json(CashFlowClassOutput::ok(classes)))
+}
+
+async fn set_class(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<CashFlowClassInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    let db_cf = match input.into_db_cash_flow() {
+        Ok(db_cf) => db_cf,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    };
+    match db_cf.upsert(&state.db).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
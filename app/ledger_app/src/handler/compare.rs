@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AccountType,
+    Db,
+    TransactionType,
+};
+
+use crate::{
+    ApiResponse,
+    AppState,
+    Error,
+};
+
+use super::summary::{
+    balance_of,
+    db_summary_by_stage,
+    get_period_month,
+    get_period_year,
+    stage_from_str,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", post(compare_periods))
+    .route("/{y1}/{y2}", get(compare_year_in_term))
+    .route("/{y1}/{y2}/{stage}", get(compare_year))
+}
+
+#[derive(Debug, Deserialize)]
+struct PeriodInput {
+    year: Option<i32>,
+    month: Option<u32>,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+}
+
+impl PeriodInput {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_period(
+        &self,
+        start_month: u32,
+    ) -> Option<(NaiveDate, NaiveDate)> {
+        match (self.start, self.end, self.year, self.month) {
+            (Some(s), Some(e), _, _) if s <= e => Some((s, e)),
+            (None, None, Some(y), Some(m)) => get_period_month(y, m),
+            (None, None, Some(y), None) => get_period_year(y, start_month),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> String {
+        match (self.start, self.end, self.year, self.month) {
+            (Some(s), Some(e), _, _) => format!("{}..{}", s, e),
+            (_, _, Some(y), Some(m)) => format!("{}-{:02}", y, m),
+            (_, _, Some(y), None) => format!("{}", y),
+            _ => "(unknown period)".to_string(),
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareInput {
+    stage: Option<String>,
+    periods: Vec<PeriodInput>,
+}
+
+#[derive(Debug, Serialize)]
+struct CompareAmount {
+    amount: f32,
+    difference: Option<f32>,
+    percentage: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+struct CompareRow {
+    account_name: String,
+    account_type: String,
+    amount_side: String,
+    amounts: Vec<CompareAmount>,
+}
+
+#[derive(Debug, Serialize)]
+struct Compare {
+    stage: String,
+    periods: Vec<String>,
+    rows: Vec<CompareRow>,
+}
+
+type CompareOutput = ApiResponse<Compare>;
+
+// difference and percentage are against the preceding period,
+// the first period only carries its amount.
+fn compare_amounts(amounts: &[f32]) -> Vec<CompareAmount> {
+    let mut result = Vec::new();
+    let mut prev: Option<f32> = None;
+    for amount in amounts {
+        let (difference, percentage) = match prev {
+            Some(p) => {
+                let diff = amount - p;
+                let pct = if p == 0_f32 {
+                    None
+                } else {
+                    Some(diff / p.abs() * 100_f32)
+                };
+                (Some(diff), pct)
+            },
+            None => (None, None),
+        };
+        result.push(CompareAmount {
+            amount: *amount,
+            difference,
+            percentage,
+        });
+        prev = Some(*amount);
+    }
+    result
+}
+
+async fn compare(
+    db: &Db,
+    stage: &TransactionType,
+    periods: &[(String, NaiveDate, NaiveDate)],
+) -> Result<Compare, Error> {
+    // accounts keyed like the summary ordering, amounts per period
+    let mut accounts
+        = BTreeMap::<(AccountType, i32), (String, Vec<f32>)>::new();
+    for (i, (_, start, end)) in periods.iter().enumerate() {
+        let db_summary = db_summary_by_stage(db, stage, *start, *end).await?;
+        for s in &db_summary {
+            let entry = accounts
+                .entry((s.account_type.clone(), s.account_id))
+                .or_insert_with(|| (
+                    s.account_name.clone(),
+                    vec![0_f32; periods.len()],
+                ));
+            entry.1[i] = balance_of(s);
+        }
+    }
+
+    let rows = accounts.into_iter()
+        .map(|((account_type, _), (account_name, amounts))| CompareRow {
+            account_name,
+            account_type: account_type.into_japanese(),
+            amount_side: account_type.amount_side().into_japanese(),
+            amounts: compare_amounts(&amounts),
+        })
+        .collect::<Vec<CompareRow>>();
+    Ok(Compare {
+        stage: stage.into_japanese(),
+        periods: periods.iter().map(|p| p.0.clone()).collect(),
+        rows,
+    })
+}
+
+async fn compare_periods(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<CompareInput>,
+) -> (StatusCode, Json<CompareOutput>) {
+    let stage_str = input.stage.clone().unwrap_or("in_term".to_string());
+    let stage = match stage_from_str(&stage_str) {
+        Some(s) => s,
+        None => return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::StageError(stage_str).into_api_response()),
+        ),
+    };
+    if input.periods.len() < 2 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::PeriodCountError.into_api_response()),
+        );
+    }
+    let mut periods = Vec::new();
+    for p in &input.periods {
+        match p.into_period(state.config.fiscal_year.start_month) {
+            Some((start, end)) => periods.push((p.label(), start, end)),
+            None => return (
+                StatusCode::BAD_REQUEST,
+                Json(Error::DateTimeError(p.label()).into_api_response()),
+            ),
+        }
+    }
+    match compare(&state.db, &stage, &periods).await {
+        Ok(c) => (StatusCode::OK, Json(CompareOutput::ok(c))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn compare_year_in_term(
+    Path(yy): Path<(i32, i32)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CompareOutput>) {
+    compare_year(
+        Path((yy.0, yy.1, "in_term".to_string())),
+        State(state),
+    ).await
+}
+
+async fn compare_year(
+    Path(yys): Path<(i32, i32, String)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CompareOutput>) {
+    let stage = match stage_from_str(&yys.2) {
+        Some(s) => s,
+        None => return (
+            StatusCode::BAD_REQUEST,
+            Json(Error::StageError(yys.2).into_api_response()),
+        ),
+    };
+    let mut periods = Vec::new();
+    for y in [yys.0, yys.1] {
+        match get_period_year(y, state.config.fiscal_year.start_month) {
+            Some((start, end)) => periods.push((y.to_string(), start, end)),
+            None => return (
+                StatusCode::BAD_REQUEST,
+                Json(Error::DateTimeError(format!("year {}", y))
+                    .into_api_response()),
+            ),
+        }
+    }
+    match compare(&state.db, &stage, &periods).await {
+        Ok(c) => (StatusCode::OK, Json(CompareOutput::ok(c))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Account,
+    Db,
+    ExchangeRate,
+    ForeignAmount,
+    ForeignBalance,
+    Transaction,
+    TransactionDetail,
+    TransactionType,
+};
+
+use crate::{
+    auth::CurrentUser,
+    text_codec,
+    ApiResponse,
+    AppState,
+    Error,
+};
+
+use crate::handler::interop::parse_date;
+use crate::handler::review::check_closable;
+use crate::handler::summary::get_period_year;
+
+// where the gains and the losses of the revaluation go
+const EXCHANGE_ACCOUNT: &str = "為替差損益";
+
+// the TTM table and the year-end revaluation of the foreign currency
+// receivables and payables
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/rate", post(insert_rate))
+    .route("/rate/import", post(import_rate))
+    .route("/rate/{currency}", get(show_rate))
+    .route("/revalue/{y}", get(show_revaluation).post(revalue))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Rate {
+    currency: String,
+    date: NaiveDate,
+    rate: f32,
+}
+
+impl Rate {
+
+    fn from_db_rate(rate: &ExchangeRate) -> Self {
+        Rate {
+            currency: rate.currency.clone(),
+            date: rate.rate_date,
+            rate: rate.rate,
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_rate(&self) -> Result<ExchangeRate, Error> {
+        let currency = self.currency.trim().to_uppercase();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::CurrencyError(
+                format!("'{}' is not a currency code", self.currency)
+            ));
+        }
+        if self.rate <= 0_f32 {
+            return Err(Error::CurrencyError(format!("{} is not a rate", self.rate)));
+        }
+        Ok(ExchangeRate { currency, rate_date: self.date, rate: self.rate })
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct RateQuery {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportQuery {
+    encoding: Option<String>,
+}
+
+// Debit positive, so that a gain is positive on the assets and on the
+// liabilities alike.
+#[derive(Debug, Serialize)]
+struct RevaluationLine {
+    account: String,
+    currency: String,
+    foreign_amount: f32,
+    rate: f32,
+    booked_amount: f32,
+    revalued_amount: f32,
+    gain: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct Revaluation {
+    date: NaiveDate,
+    lines: Vec<RevaluationLine>,
+    gain: f32,
+    // None when there was nothing to post
+    #[serde(skip_serializing_if = "Option::is_none")]
+    journal_id: Option<i32>,
+}
+
+type RateOutput = ApiResponse<Vec<Rate>>;
+type RateCountOutput = ApiResponse<u64>;
+type RevaluationOutput = ApiResponse<Revaluation>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::Forbidden(_) => StatusCode::FORBIDDEN,
+        Error::ReviewOpen(..) => StatusCode::CONFLICT,
+        Error::RateNotFound(..) | Error::AccountNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn show_rate(
+    Path(currency): Path<String>,
+    Query(query): Query<RateQuery>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<RateOutput>) {
+    let currency = currency.to_uppercase();
+    match ExchangeRate::by_currency(&state.db, &currency, query.from, query.to).await {
+        Ok(rates) => (
+            StatusCode::OK,
+            Json(RateOutput::ok(rates.iter().map(Rate::from_db_rate).collect())),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn insert_db_rate(db: &Db, input: &[Rate]) -> Result<u64, Error> {
+    let rates = input.iter()
+        .map(Rate::into_db_rate)
+        .collect::<Result<Vec<ExchangeRate>, Error>>()?;
+    Ok(ExchangeRate::upsert(db, &rates).await?)
+}
+
+async fn insert_rate(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<Vec<Rate>>,
+) -> (StatusCode, Json<RateCountOutput>) {
+    match insert_db_rate(&state.db, &input).await {
+        Ok(count) => (StatusCode::CREATED, Json(RateCountOutput::ok(count))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// date,currency,rate a line, after a header line or not
+fn parse_rate_csv(text: &str) -> Result<Vec<Rate>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let mut rates = Vec::new();
+    for (n, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| Error::CsvError(e.to_string()))?;
+        let column = |i: usize| record.get(i).unwrap_or("");
+        if column(0).is_empty() { continue; }
+        let Some(date) = parse_date(column(0)) else {
+            if n == 0 { continue; }
+            return Err(Error::CsvError(
+                format!("line {}: '{}' is not a date", n + 1, column(0))
+            ));
+        };
+        let rate = column(2).replace(',', "").parse::<f32>().map_err(|_| {
+            Error::CsvError(format!("line {}: '{}' is not a rate", n + 1, column(2)))
+        })?;
+        rates.push(Rate { currency: column(1).to_string(), date, rate });
+    }
+    Ok(rates)
+}
+
+async fn import_rate(
+    Query(query): Query<ImportQuery>,
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> (StatusCode, Json<RateCountOutput>) {
+    let rates = text_codec::decode(&body, query.encoding.as_deref().unwrap_or("UTF-8"))
+        .and_then(|text| parse_rate_csv(&text));
+    let result = match rates {
+        Ok(rates) => insert_db_rate(&state.db, &rates).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(count) => (StatusCode::CREATED, Json(RateCountOutput::ok(count))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// Every foreign balance at the TTM of the last day of the year. What
+// differs from the yen booked goes to 為替差損益, and the balances in
+// the currencies stay as they are.
+async fn revaluation_of(
+    state: &AppState,
+    y: i32,
+) -> Result<(Revaluation, Option<Transaction>), Error> {
+    let (start, end) = get_period_year(y, state.config.fiscal_year.start_month)
+        .ok_or(Error::DateTimeError(format!("year {}", y)))?;
+    let balances = ForeignBalance::upto_kessan(&state.db, start, end).await?;
+    let mut lines = Vec::new();
+    let mut details = Vec::new();
+    for balance in &balances {
+        let rate = match ExchangeRate::on(&state.db, &balance.currency, end).await {
+            Ok(r) => r.rate,
+            Err(ledger_db::Error::RowNotFound)
+                => return Err(Error::RateNotFound(balance.currency.clone(), end)),
+            Err(e) => return Err(e.into()),
+        };
+        let revalued = (balance.foreign_amount * rate).round();
+        let gain = revalued - balance.booked_amount;
+        lines.push(RevaluationLine {
+            account: balance.account_name.clone(),
+            currency: balance.currency.clone(),
+            foreign_amount: balance.foreign_amount,
+            rate,
+            booked_amount: balance.booked_amount,
+            revalued_amount: revalued,
+            gain,
+        });
+        if gain == 0_f32 { continue; }
+        details.push(TransactionDetail {
+            account_name: balance.account_name.clone(),
+            account_type: balance.account_type.clone(),
+            debit_amount: gain.max(0_f32),
+            credit_amount: (-gain).max(0_f32),
+            foreign: Some(ForeignAmount {
+                currency: balance.currency.clone(),
+                amount: 0_f32,
+                rate,
+            }),
+        });
+    }
+    let gain = lines.iter().map(|l| l.gain).sum::<f32>();
+    if gain != 0_f32 {
+        let account = match Account::by_name(&state.db, EXCHANGE_ACCOUNT).await {
+            Ok(account) => account,
+            Err(ledger_db::Error::RowNotFound)
+                => return Err(Error::AccountNotFound(EXCHANGE_ACCOUNT.to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        details.push(TransactionDetail {
+            account_name: account.account_name,
+            account_type: account.account_type,
+            debit_amount: (-gain).max(0_f32),
+            credit_amount: gain.max(0_f32),
+            foreign: None,
+        });
+    }
+    let transaction = match details.is_empty() {
+        true => None,
+        false => Some(Transaction {
+            transaction_id: 0,
+            transaction_date: end,
+            transaction_type: TransactionType::Kessan,
+            description: format!("外貨建債権債務の換算 {}", y),
+            partner_name: None,
+            created_by: None,
+            tags: Vec::new(),
+            review_status: None,
+            open_comments: 0,
+            details,
+        }),
+    };
+    Ok((Revaluation { date: end, lines, gain, journal_id: None }, transaction))
+}
+
+async fn show_revaluation(
+    Path(y): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<RevaluationOutput>) {
+    match revaluation_of(&state, y).await {
+        Ok((revaluation, _)) => (StatusCode::OK, Json(RevaluationOutput::ok(revaluation))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn post_revaluation(
+    state: &AppState,
+    user: &CurrentUser,
+    y: i32,
+) -> Result<Revaluation, Error> {
+    let (mut revaluation, transaction) = revaluation_of(state, y).await?;
+    if let Some(transaction) = transaction {
+        let transaction = user.stamp(transaction)?;
+        check_closable(state, &transaction).await?;
+        revaluation.journal_id = Some(transaction.insert(&state.db).await?);
+    }
+    Ok(revaluation)
+}
+
+// a second run finds the balances revalued already and posts nothing
+async fn revalue(
+    Path(y): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<RevaluationOutput>) {
+    match post_revaluation(&state, &user, y).await {
+        Ok(revaluation) if revaluation.journal_id.is_none()
+            => (StatusCode::OK, Json(RevaluationOutput::ok(revaluation))),
+        Ok(revaluation) => (StatusCode::CREATED, Json(RevaluationOutput::ok(revaluation))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
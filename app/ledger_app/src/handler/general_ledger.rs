@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::get,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use ledger_db::{
+    Account,
+    AmountSide,
+    Db,
+    Transaction,
+};
+
+use crate::{
+    ApiResponse,
+    AppState,
+    Error,
+};
+
+use crate::handler::summary::get_period_year;
+
+// 総勘定元帳 of one account through the fiscal year
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{y}/{account}", get(show_year))
+}
+
+#[derive(Debug, Serialize)]
+struct LedgerLine {
+    date: NaiveDate,
+    transaction_type: String,
+    description: String,
+    partner: Option<String>,
+    // the other side, 諸口 when there are several
+    counter_account: String,
+    debit: f32,
+    credit: f32,
+    balance: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct GeneralLedger {
+    account_name: String,
+    account_type: String,
+    lines: Vec<LedgerLine>,
+    balance: f32,
+}
+
+type GeneralLedgerOutput = ApiResponse<GeneralLedger>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::AccountNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DateTimeError(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn counter_account(transaction: &Transaction, debit_side: bool) -> String {
+    let counters = transaction.details.iter()
+        .filter(|d| match debit_side {
+            true => d.credit_amount != 0_f32,
+            false => d.debit_amount != 0_f32,
+        })
+        .map(|d| d.account_name.clone())
+        .collect::<Vec<String>>();
+    match counters.as_slice() {
+        [one] => one.clone(),
+        _ => "諸口".to_string(),
+    }
+}
+
+// The 前期繰越 journals come first in the year and open the balance,
+// so the lines begin at zero.
+async fn general_ledger(
+    db: &Db,
+    account_name: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<GeneralLedger, Error> {
+    let account = match Account::by_name(db, account_name).await {
+        Ok(account) => account,
+        Err(ledger_db::Error::RowNotFound)
+            => return Err(Error::AccountNotFound(account_name.to_string())),
+        Err(e) => return Err(e.into()),
+    };
+    let transactions = match Transaction::by_period(db, start, end).await {
+        Ok(t) => t,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let side = account.account_type.amount_side();
+    let mut balance = 0_f32;
+    let mut lines = Vec::new();
+    for transaction in &transactions {
+        for detail in transaction.details.iter()
+            .filter(|d| d.account_name == account.account_name)
+        {
+            balance += match side {
+                AmountSide::Debit => detail.debit_amount - detail.credit_amount,
+                AmountSide::Credit => detail.credit_amount - detail.debit_amount,
+            };
+            lines.push(LedgerLine {
+                date: transaction.transaction_date,
+                transaction_type: transaction.transaction_type.into_japanese(),
+                description: transaction.description.clone(),
+                partner: transaction.partner_name.clone(),
+                counter_account: counter_account(
+                    transaction, detail.debit_amount != 0_f32,
+                ),
+                debit: detail.debit_amount,
+                credit: detail.credit_amount,
+                balance,
+            });
+        }
+    }
+    Ok(GeneralLedger {
+        account_name: account.account_name.clone(),
+        account_type: account.account_type.into_japanese(),
+        lines,
+        balance,
+    })
+}
+
+async fn show_year(
+    Path((y, account)): Path<(i32, String)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<GeneralLedgerOutput>) {
+    let result = match get_period_year(y, state.config.fiscal_year.start_month) {
+        Some((start, end)) => general_ledger(&state.db, &account, start, end).await,
+        None => Err(Error::DateTimeError(format!("year {}", y))),
+    };
+    match result {
+        Ok(ledger) => (StatusCode::OK, Json(GeneralLedgerOutput::ok(ledger))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
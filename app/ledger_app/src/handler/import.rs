@@ -0,0 +1,137 @@
+pub mod bank_csv;
+pub mod card;
+pub mod ofx;
+pub mod rule;
+pub mod staged;
+pub mod zengin;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::Router;
+use chrono::NaiveDate;
+
+use ledger_db::{
+    Db,
+    ImportSource,
+    LineStatus,
+    StagedLine as DbStagedLine,
+};
+
+use crate::{
+    AppState,
+    Error,
+};
+
+use rule::RuleSet;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .nest("/bank", bank_csv::build_router())
+    .nest("/card", card::build_router())
+    .nest("/ofx", ofx::build_router())
+    .nest("/zengin", zengin::build_router())
+    .nest("/rule", rule::build_router())
+    .nest("/staged", staged::build_router())
+}
+
+// one line of a statement file, whatever its format
+#[derive(Debug)]
+pub struct StatementLine {
+    pub date: NaiveDate,
+    pub description: String,
+    pub withdrawal: f32,
+    pub deposit: f32,
+    pub balance: Option<f32>,
+    pub external_id: Option<String>,
+}
+
+impl StatementLine {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_staged_line(
+        &self,
+        import_source: &ImportSource,
+        account_name: &str,
+    ) -> DbStagedLine {
+        DbStagedLine {
+            staged_line_id: 0,
+            import_source: import_source.clone(),
+            account_name: account_name.to_string(),
+            line_date: self.date,
+            description: self.description.clone(),
+            withdrawal_amount: self.withdrawal,
+            deposit_amount: self.deposit,
+            balance_amount: self.balance,
+            counter_account_name: None,
+            memo: None,
+            line_status: LineStatus::Draft,
+            transaction_id: None,
+            external_id: self.external_id.clone(),
+            tax_code: None,
+            partner_name: None,
+            rule_id: None,
+            transaction_detail_id: None,
+        }
+    }
+
+}
+
+// Formats without their own transaction id (CSV) are identified by
+// date, amounts and description. Identical lines within one statement
+// are numbered so that two equal charges on a day are both kept while
+// the same statement imported twice is still detected.
+pub(crate) fn assign_fingerprints(lines: &mut [StatementLine]) {
+    let mut occurrences = HashMap::<String, usize>::new();
+    for line in lines.iter_mut().filter(|l| l.external_id.is_none()) {
+        let key = format!(
+            "{}/{}/{}/{}",
+            line.date, line.withdrawal, line.deposit, line.description,
+        );
+        let n = occurrences.entry(key.clone()).or_insert(0);
+        *n += 1;
+        line.external_id = Some(format!("{}/{}", key, n));
+    }
+}
+
+// Stages the lines with the proposal of the import rules, returns the
+// ids of the staged lines and the number of duplicates.
+pub(crate) async fn stage_lines(
+    db: &Db,
+    import_source: &ImportSource,
+    account_name: &str,
+    lines: &[StatementLine],
+) -> Result<(Vec<i32>, usize), Error> {
+    let rule_set = RuleSet::load(db).await?;
+    let mut ids = Vec::new();
+    let mut duplicates = 0;
+    for line in lines {
+        let mut db_line
+            = line.into_db_staged_line(import_source, account_name);
+        rule_set.propose(&mut db_line);
+        match db_line.insert(db).await? {
+            Some(id) => ids.push(id),
+            None => duplicates += 1,
+        }
+    }
+    Ok((ids, duplicates))
+}
+
+// accepts 1,234 / ￥1,234 / 1234円 / △1,234 (negative) / full width digits
+pub(crate) fn parse_amount(value: &str) -> Option<f32> {
+    let mut negative = false;
+    let mut normalized = String::new();
+    for c in value.trim().chars() {
+        match c {
+            '０'..='９' => normalized.push(
+                char::from_u32(c as u32 - '０' as u32 + '0' as u32)?
+            ),
+            '-' | '－' | '△' | '▲' => negative = true,
+            ',' | '，' | '¥' | '￥' | '円' | '+' | ' ' | '　' => (),
+            _ => normalized.push(c),
+        }
+    }
+    if normalized.is_empty() { return Some(0_f32); }
+    let amount = normalized.parse::<f32>().ok()?;
+    Some(if negative { -amount } else { amount })
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    CsvMapping as DbCsvMapping,
+    ImportSource,
+};
+
+use crate::{
+    text_codec,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use super::{
+    assign_fingerprints,
+    parse_amount,
+    stage_lines,
+    StatementLine,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/mapping", get(show_mapping).post(insert_mapping))
+    .route("/{mapping}/{account}", post(import_csv))
+}
+
+fn default_encoding() -> String { "UTF-8".to_string() }
+fn default_skip_rows() -> i32 { 1 }
+fn default_date_format() -> String { "%Y/%m/%d".to_string() }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvMapping {
+    mapping_name: String,
+    #[serde(default = "default_encoding")]
+    encoding: String,
+    #[serde(default = "default_skip_rows")]
+    skip_rows: i32,
+    date_column: i32,
+    #[serde(default = "default_date_format")]
+    date_format: String,
+    description_column: i32,
+    withdrawal_column: Option<i32>,
+    deposit_column: Option<i32>,
+    balance_column: Option<i32>,
+}
+
+impl CsvMapping {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_mapping(&self) -> DbCsvMapping {
+        DbCsvMapping {
+            mapping_id: 0,
+            mapping_name: self.mapping_name.clone(),
+            encoding: self.encoding.clone(),
+            skip_rows: self.skip_rows,
+            date_column: self.date_column,
+            date_format: self.date_format.clone(),
+            description_column: self.description_column,
+            withdrawal_column: self.withdrawal_column,
+            deposit_column: self.deposit_column,
+            balance_column: self.balance_column,
+        }
+    }
+
+    fn from_db_mapping(mapping: &DbCsvMapping) -> Self {
+        CsvMapping {
+            mapping_name: mapping.mapping_name.clone(),
+            encoding: mapping.encoding.clone(),
+            skip_rows: mapping.skip_rows,
+            date_column: mapping.date_column,
+            date_format: mapping.date_format.clone(),
+            description_column: mapping.description_column,
+            withdrawal_column: mapping.withdrawal_column,
+            deposit_column: mapping.deposit_column,
+            balance_column: mapping.balance_column,
+        }
+    }
+
+}
+
+type CsvMappingInput = CsvMapping;
+type CsvMappingOutput = ApiResponse<Vec<CsvMapping>>;
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+fn column(record: &csv::StringRecord, index: Option<i32>) -> &str {
+    match index {
+        Some(i) if i >= 0 => record.get(i as usize).unwrap_or(""),
+        _ => "",
+    }
+}
+
+// rows whose date does not parse (headers, footers, blank rows)
+// are skipped and counted.
+pub(crate) fn parse_csv(
+    mapping: &DbCsvMapping,
+    bytes: &[u8],
+) -> Result<(Vec<StatementLine>, usize), Error> {
+    let text = text_codec::decode(bytes, &mapping.encoding)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut lines = Vec::new();
+    let mut skipped = 0;
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| Error::CsvError(e.to_string()))?;
+        if row < mapping.skip_rows.max(0) as usize { continue; }
+
+        let date_str = column(&record, Some(mapping.date_column)).trim();
+        let date = match NaiveDate::parse_from_str(
+            date_str, &mapping.date_format,
+        ) {
+            Ok(d) => d,
+            Err(_) => { skipped += 1; continue; },
+        };
+        let amount_of = |index: Option<i32>| {
+            let value = column(&record, index);
+            parse_amount(value).ok_or(Error::CsvError(
+                format!("row {}: '{}' is not an amount", row + 1, value)
+            ))
+        };
+        let net = if mapping.withdrawal_column == mapping.deposit_column {
+            amount_of(mapping.deposit_column)?
+        } else {
+            amount_of(mapping.deposit_column)?
+                - amount_of(mapping.withdrawal_column)?
+        };
+        let balance = match mapping.balance_column {
+            Some(_) => Some(amount_of(mapping.balance_column)?),
+            None => None,
+        };
+        lines.push(StatementLine {
+            date,
+            description: column(&record, Some(mapping.description_column))
+                .trim().to_string(),
+            withdrawal: if net < 0_f32 { -net } else { 0_f32 },
+            deposit: if net > 0_f32 { net } else { 0_f32 },
+            balance,
+            external_id: None,
+        });
+    }
+    assign_fingerprints(&mut lines);
+    Ok((lines, skipped))
+}
+
+async fn show_mapping(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CsvMappingOutput>) {
+    let db_mapping = match DbCsvMapping::all(&state.db).await {
+        Ok(m) => m,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let mapping = db_mapping.iter()
+        .map(CsvMapping::from_db_mapping)
+        .collect::<Vec<CsvMapping>>();
+    (StatusCode::OK, Json(CsvMappingOutput::ok(mapping)))
+}
+
+async fn insert_mapping(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<CsvMappingInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match input.into_db_mapping().upsert(&state.db).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn import_csv(
+    Path(ma): Path<(String, String)>,
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let mapping = match DbCsvMapping::by_name(&state.db, &ma.0).await {
+        Ok(m) => m,
+        Err(ledger_db::Error::RowNotFound) => return (
+            StatusCode::NOT_FOUND,
+            Json(Error::MappingNotFound(ma.0).into_api_response()),
+        ),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let (lines, skipped) = match parse_csv(&mapping, &body) {
+        Ok(l) => l,
+        Err(e) => return (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    };
+    match stage_lines(&state.db, &ImportSource::BankCsv, &ma.1, &lines).await {
+        Ok((ids, duplicates)) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!(
+                    "{} lines staged, {} duplicates, {} rows skipped",
+                    ids.len(), duplicates, skipped,
+                ),
+                ids,
+            )),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Account,
+    AccountType,
+    CreditCard as DbCreditCard,
+    CsvMapping,
+    Db,
+    ImportSource,
+};
+
+use crate::{
+    auth::CurrentUser,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use super::{
+    bank_csv::parse_csv,
+    stage_lines,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_card).post(insert_card))
+    .route("/{card}", post(import_csv))
+    .route("/{card}/settle", post(settle))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreditCard {
+    card_name: String,
+    account: Option<String>,
+    bank_account: Option<String>,
+    mapping: String,
+}
+
+impl CreditCard {
+
+    // the sub-account defaults to 未払金(card name),
+    // the payment account to 普通預金.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_card(&self) -> DbCreditCard {
+        DbCreditCard {
+            card_id: 0,
+            card_name: self.card_name.clone(),
+            account_name: self.account.clone()
+                .unwrap_or(format!("未払金({})", self.card_name)),
+            bank_account_name: self.bank_account.clone()
+                .unwrap_or("普通預金".to_string()),
+            mapping_name: self.mapping.clone(),
+        }
+    }
+
+    fn from_db_card(card: &DbCreditCard) -> Self {
+        CreditCard {
+            card_name: card.card_name.clone(),
+            account: Some(card.account_name.clone()),
+            bank_account: Some(card.bank_account_name.clone()),
+            mapping: card.mapping_name.clone(),
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct SettleInput {
+    date: NaiveDate,
+    total: f32,
+    desc: String,
+}
+
+impl SettleInput {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_journal(&self, card: &DbCreditCard) -> Journal {
+        Journal {
+            transaction_type: "InTerm".to_string(),
+            date: self.date,
+            debit: vec![AccountAmount {
+                account: card.account_name.clone(),
+                amount: self.total,
+                foreign: None,
+            }],
+            credit: vec![AccountAmount {
+                account: card.bank_account_name.clone(),
+                amount: self.total,
+                foreign: None,
+            }],
+            desc: self.desc.clone(),
+            partner: None,
+            tags: Vec::new(),
+            created_by: None,
+            id: None,
+            review: None,
+        }
+    }
+
+}
+
+type CreditCardInput = CreditCard;
+type CreditCardOutput = ApiResponse<Vec<CreditCard>>;
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+async fn card_by_name(
+    db: &Db,
+    card_name: &str,
+) -> Result<DbCreditCard, Error> {
+    match DbCreditCard::by_name(db, card_name).await {
+        Ok(c) => Ok(c),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::CardNotFound(card_name.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn register(
+    db: &Db,
+    card: &DbCreditCard,
+) -> Result<i32, Error> {
+    match Account::by_name(db, &card.account_name).await {
+        Ok(_) => (),
+        Err(ledger_db::Error::RowNotFound) => {
+            Account {
+                account_id: 0,
+                account_name: card.account_name.clone(),
+                account_type: AccountType::Liability,
+            }.insert(db).await?;
+        },
+        Err(e) => return Err(e.into()),
+    }
+    match card.upsert(db).await {
+        Ok(id) => Ok(id),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::MappingNotFound(card.mapping_name.clone())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn show_card(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CreditCardOutput>) {
+    let db_card = match DbCreditCard::all(&state.db).await {
+        Ok(c) => c,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let card = db_card.iter()
+        .map(CreditCard::from_db_card)
+        .collect::<Vec<CreditCard>>();
+    (StatusCode::OK, Json(CreditCardOutput::ok(card)))
+}
+
+async fn insert_card(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<CreditCardInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match register(&state.db, &input.into_db_card()).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn import_card_csv(
+    db: &Db,
+    card_name: &str,
+    bytes: &[u8],
+) -> Result<(Vec<i32>, usize, usize), Error> {
+    let card = card_by_name(db, card_name).await?;
+    let mapping = CsvMapping::by_name(db, &card.mapping_name).await?;
+    let (lines, skipped) = parse_csv(&mapping, bytes)?;
+    let (ids, duplicates)
+        = stage_lines(db, &ImportSource::CardCsv, &card.account_name, &lines)
+        .await?;
+    Ok((ids, duplicates, skipped))
+}
+
+async fn import_csv(
+    Path(card_name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    match import_card_csv(&state.db, &card_name, &body).await {
+        Ok((ids, duplicates, skipped)) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!(
+                    "{} lines staged, {} duplicates, {} rows skipped",
+                    ids.len(), duplicates, skipped,
+                ),
+                ids,
+            )),
+        ),
+        Err(e @ Error::CardNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(e.into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn settle_card(
+    db: &Db,
+    user: &CurrentUser,
+    card_name: &str,
+    input: &SettleInput,
+) -> Result<i32, Error> {
+    let card = card_by_name(db, card_name).await?;
+    let transaction = input.into_journal(&card).into_transaction(db).await?;
+    Ok(user.stamp(transaction)?.insert(db).await?)
+}
+
+// the monthly debit of the card bill from the bank account
+async fn settle(
+    Path(card_name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<SettleInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match settle_card(&state.db, &user, &card_name, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e @ Error::CardNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(e.into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
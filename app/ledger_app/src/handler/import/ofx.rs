@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::post,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use ledger_db::ImportSource;
+
+use crate::{
+    text_codec,
+    ApiResponse,
+    AppState,
+    Error,
+};
+
+use super::{
+    parse_amount,
+    stage_lines,
+    StatementLine,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{account}", post(import_ofx))
+}
+
+#[derive(Debug, Deserialize)]
+struct OfxQuery {
+    encoding: Option<String>,
+}
+
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+// The encoding declared in the header: ENCODING/CHARSET of the SGML
+// header of OFX 1.x (and QFX), or the XML declaration of OFX 2.x.
+fn declared_encoding(bytes: &[u8]) -> String {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)])
+        .to_uppercase();
+    let value_of = |key: &str| {
+        head.find(key).map(|i| {
+            head[i + key.len()..].chars()
+                .skip_while(|c| *c == '"' || *c == ' ')
+                .take_while(|c| {
+                    c.is_ascii_alphanumeric() || *c == '-' || *c == '_'
+                })
+                .collect::<String>()
+        })
+    };
+    if let Some(e) = value_of("ENCODING=") { return e; }
+    let encoding = value_of("ENCODING:");
+    let charset = value_of("CHARSET:");
+    match (encoding.as_deref(), charset.as_deref()) {
+        (Some("UTF-8"), _) => "UTF-8",
+        (_, Some("932" | "SHIFT_JIS" | "CSSHIFTJIS")) => "Shift_JIS",
+        (_, Some("1252")) => "windows-1252",
+        _ => "UTF-8",
+    }.to_string()
+}
+
+// The value of an element within a block. Leaf elements of SGML are
+// not closed, so the value ends at the next tag in both versions.
+fn element<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let start = block.find(&open)? + open.len();
+    let value = block[start..].split('<').next()?.trim();
+    if value.is_empty() { None } else { Some(value) }
+}
+
+// DTPOSTED is YYYYMMDD, optionally followed by the time and zone
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+// one STMTTRN per line, FITID is the id of the transaction
+pub(crate) fn parse_ofx(
+    bytes: &[u8],
+    encoding: Option<&str>,
+) -> Result<Vec<StatementLine>, Error> {
+    let label = encoding.map(|e| e.to_string())
+        .unwrap_or_else(|| declared_encoding(bytes));
+    let text = text_codec::decode(bytes, &label)?;
+
+    // element names of OFX are upper case in both versions
+    let mut lines = Vec::new();
+    let mut rest = 0;
+    while let Some(i) = text[rest..].find("<STMTTRN>") {
+        let start = rest + i;
+        let end = text[start..].find("</STMTTRN>")
+            .map(|j| start + j)
+            .unwrap_or(text.len());
+        let block = &text[start..end];
+        let value_of = |tag: &str| element(block, tag);
+        let fitid = value_of("FITID")
+            .ok_or(Error::FormatError("STMTTRN without FITID".to_string()))?;
+        let date = value_of("DTPOSTED").and_then(parse_date)
+            .ok_or(Error::FormatError(format!("{}: no DTPOSTED", fitid)))?;
+        let amount = value_of("TRNAMT").and_then(parse_amount)
+            .ok_or(Error::FormatError(format!("{}: no TRNAMT", fitid)))?;
+        let description = match (value_of("NAME"), value_of("MEMO")) {
+            (Some(name), Some(memo)) if name != memo
+                => format!("{} {}", name, memo),
+            (Some(name), _) => name.to_string(),
+            (None, Some(memo)) => memo.to_string(),
+            (None, None) => String::new(),
+        };
+        lines.push(StatementLine {
+            date,
+            description,
+            withdrawal: if amount < 0_f32 { -amount } else { 0_f32 },
+            deposit: if amount > 0_f32 { amount } else { 0_f32 },
+            balance: None,
+            external_id: Some(fitid.to_string()),
+        });
+        rest = end;
+    }
+    if lines.is_empty() && !text.contains("<OFX>") {
+        return Err(Error::FormatError("not an OFX file".to_string()));
+    }
+    Ok(lines)
+}
+
+async fn import_ofx(
+    Path(account): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<OfxQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let lines = match parse_ofx(&body, query.encoding.as_deref()) {
+        Ok(l) => l,
+        Err(e) => return (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    };
+    match stage_lines(&state.db, &ImportSource::Ofx, &account, &lines).await {
+        Ok((ids, duplicates)) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!(
+                    "{} lines staged, {} duplicates", ids.len(), duplicates,
+                ),
+                ids,
+            )),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
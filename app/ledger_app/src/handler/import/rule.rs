@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Db,
+    ImportRule as DbImportRule,
+    MatchType,
+    StagedLine as DbStagedLine,
+    TaxCode,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_rule).post(insert_rule))
+    .route("/test", post(test_rule))
+    .route("/{id}", delete(delete_rule))
+}
+
+fn default_priority() -> i32 { 100 }
+fn default_match_type() -> String { "Substring".to_string() }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImportRule {
+    #[serde(default)]
+    id: i32,
+    rule_name: String,
+    #[serde(default = "default_priority")]
+    priority: i32,
+    #[serde(default = "default_match_type")]
+    match_type: String,
+    pattern: Option<String>,
+    min_amount: Option<f32>,
+    max_amount: Option<f32>,
+    account: Option<String>,
+    partner: Option<String>,
+    counter_account: String,
+    tax_code: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    learned: bool,
+}
+
+impl ImportRule {
+
+    // a value that is none of the names is refused rather than taken
+    // as the default, a typo would assign every line it matches wrong
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_rule(&self) -> Result<DbImportRule, Error> {
+        let match_type = MatchType::parse(&self.match_type)
+            .ok_or(Error::RuleError(
+                format!("'{}' is not a match type", self.match_type)
+            ))?;
+        let tax_code = match &self.tax_code {
+            Some(t) => Some(tax_code_of(t)?),
+            None => None,
+        };
+        Ok(DbImportRule {
+            rule_id: 0,
+            rule_name: self.rule_name.clone(),
+            priority: self.priority,
+            match_type,
+            pattern: self.pattern.clone(),
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            source_account_name: self.account.clone(),
+            partner_name: self.partner.clone(),
+            counter_account_name: self.counter_account.clone(),
+            tax_code,
+            description: self.description.clone(),
+            learned: false,
+        })
+    }
+
+    fn from_db_rule(rule: &DbImportRule) -> Self {
+        ImportRule {
+            id: rule.rule_id,
+            rule_name: rule.rule_name.clone(),
+            priority: rule.priority,
+            match_type: rule.match_type.into_japanese(),
+            pattern: rule.pattern.clone(),
+            min_amount: rule.min_amount,
+            max_amount: rule.max_amount,
+            account: rule.source_account_name.clone(),
+            partner: rule.partner_name.clone(),
+            counter_account: rule.counter_account_name.clone(),
+            tax_code: rule.tax_code.as_ref().map(|t| t.into_japanese()),
+            description: rule.description.clone(),
+            learned: rule.learned,
+        }
+    }
+
+}
+
+pub(crate) fn tax_code_of(value: &str) -> Result<TaxCode, Error> {
+    TaxCode::parse(value)
+        .ok_or(Error::RuleError(format!("'{}' is not a tax code", value)))
+}
+
+// what a rule is matched against
+#[derive(Debug, Deserialize)]
+pub(crate) struct RuleTarget {
+    description: String,
+    amount: f32,
+    account: Option<String>,
+    partner: Option<String>,
+}
+
+impl RuleTarget {
+
+    pub(crate) fn from_db_staged_line(line: &DbStagedLine) -> Self {
+        RuleTarget {
+            description: line.description.clone(),
+            amount: line.withdrawal_amount.max(line.deposit_amount),
+            account: Some(line.account_name.clone()),
+            partner: line.partner_name.clone(),
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct RuleTestResult {
+    fired: Option<ImportRule>,
+    matched: Vec<ImportRule>,
+}
+
+type ImportRuleInput = ImportRule;
+type ImportRuleOutput = ApiResponse<Vec<ImportRule>>;
+type RuleTestInput = RuleTarget;
+type RuleTestOutput = ApiResponse<RuleTestResult>;
+
+fn compile(rule: &DbImportRule) -> Result<Option<Regex>, Error> {
+    match (&rule.match_type, &rule.pattern) {
+        (MatchType::Regex, Some(pattern)) => Regex::new(pattern)
+            .map(Some)
+            .map_err(|e| Error::RuleError(e.to_string())),
+        _ => Ok(None),
+    }
+}
+
+// the registered rules in the order they are tried
+pub(crate) struct RuleSet {
+    rules: Vec<(DbImportRule, Option<Regex>)>,
+}
+
+impl RuleSet {
+
+    pub(crate) async fn load(db: &Db) -> Result<Self, Error> {
+        let db_rules = match DbImportRule::all(db).await {
+            Ok(r) => r,
+            Err(ledger_db::Error::RowNotFound) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut rules = Vec::new();
+        for rule in db_rules {
+            let regex = compile(&rule)?;
+            rules.push((rule, regex));
+        }
+        Ok(RuleSet { rules })
+    }
+
+    pub(crate) fn by_id(&self, rule_id: i32) -> Option<&DbImportRule> {
+        self.rules.iter()
+            .map(|(r, _)| r)
+            .find(|r| r.rule_id == rule_id)
+    }
+
+    // substrings are compared ignoring the case of latin letters
+    fn is_match(
+        rule: &DbImportRule,
+        regex: &Option<Regex>,
+        target: &RuleTarget,
+    ) -> bool {
+        let pattern_matched = match (regex, &rule.pattern) {
+            (Some(re), _) => re.is_match(&target.description),
+            (None, Some(pattern)) => target.description.to_uppercase()
+                .contains(&pattern.to_uppercase()),
+            (None, None) => true,
+        };
+        pattern_matched
+            && rule.min_amount.is_none_or(|min| target.amount >= min)
+            && rule.max_amount.is_none_or(|max| target.amount <= max)
+            && (rule.source_account_name.is_none()
+                || rule.source_account_name == target.account)
+            && (rule.partner_name.is_none()
+                || rule.partner_name == target.partner)
+    }
+
+    fn matching(&self, target: &RuleTarget) -> Vec<&DbImportRule> {
+        self.rules.iter()
+            .filter(|(r, re)| RuleSet::is_match(r, re, target))
+            .map(|(r, _)| r)
+            .collect()
+    }
+
+    pub(crate) fn fire(&self, target: &RuleTarget) -> Option<&DbImportRule> {
+        self.rules.iter()
+            .find(|(r, re)| RuleSet::is_match(r, re, target))
+            .map(|(r, _)| r)
+    }
+
+    // fills a draft with the proposal of the first matching rule,
+    // returns whether a rule fired.
+    pub(crate) fn propose(&self, line: &mut DbStagedLine) -> bool {
+        let rule = match self.fire(&RuleTarget::from_db_staged_line(line)) {
+            Some(r) => r,
+            None => return false,
+        };
+        line.counter_account_name = Some(rule.counter_account_name.clone());
+        line.tax_code = rule.tax_code.clone();
+        if rule.description.is_some() {
+            line.memo = rule.description.clone();
+        }
+        line.rule_id = Some(rule.rule_id);
+        true
+    }
+
+}
+
+async fn show_rule(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ImportRuleOutput>) {
+    let db_rules = match DbImportRule::all(&state.db).await {
+        Ok(r) => r,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let rules = db_rules.iter()
+        .map(ImportRule::from_db_rule)
+        .collect::<Vec<ImportRule>>();
+    (StatusCode::OK, Json(ImportRuleOutput::ok(rules)))
+}
+
+async fn insert_db_rule(
+    db: &Db,
+    input: &ImportRuleInput,
+) -> Result<i32, Error> {
+    let rule = input.into_db_rule()?;
+    compile(&rule)?;
+    Ok(rule.insert(db).await?)
+}
+
+async fn insert_rule(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<ImportRuleInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match insert_db_rule(&state.db, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn delete_rule(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbImportRule::delete(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::RuleNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+// shows the rule that would fire for a line and every rule that
+// matches it, in the order they are tried.
+async fn test_rule(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<RuleTestInput>,
+) -> (StatusCode, Json<RuleTestOutput>) {
+    let rule_set = match RuleSet::load(&state.db).await {
+        Ok(r) => r,
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    };
+    let matched = rule_set.matching(&input).into_iter()
+        .map(ImportRule::from_db_rule)
+        .collect::<Vec<ImportRule>>();
+    let fired = rule_set.fire(&input).map(ImportRule::from_db_rule);
+    (
+        StatusCode::OK,
+        Json(RuleTestOutput::ok(RuleTestResult { fired, matched })),
+    )
+}
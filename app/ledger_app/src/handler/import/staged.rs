@@ -0,0 +1,423 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Db,
+    ImportRule,
+    LineStatus,
+    MatchType,
+    StagedLine as DbStagedLine,
+};
+
+use crate::{
+    auth::CurrentUser,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use super::rule::{
+    tax_code_of,
+    RuleSet,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_staged))
+    .route("/post", post(post_all))
+    .route("/apply", post(apply_rules))
+    .route("/{id}", post(assign).delete(discard))
+    .route("/{id}/post", post(post_one))
+}
+
+#[derive(Debug, Serialize)]
+struct StagedLine {
+    id: i32,
+    source: String,
+    account: String,
+    date: NaiveDate,
+    description: String,
+    withdrawal: f32,
+    deposit: f32,
+    balance: Option<f32>,
+    counter_account: Option<String>,
+    memo: Option<String>,
+    tax_code: Option<String>,
+    partner: Option<String>,
+    rule_id: Option<i32>,
+    status: String,
+    transaction_id: Option<i32>,
+}
+
+impl StagedLine {
+
+    fn from_db_staged_line(line: &DbStagedLine) -> Self {
+        StagedLine {
+            id: line.staged_line_id,
+            source: line.import_source.into_japanese(),
+            account: line.account_name.clone(),
+            date: line.line_date,
+            description: line.description.clone(),
+            withdrawal: line.withdrawal_amount,
+            deposit: line.deposit_amount,
+            balance: line.balance_amount,
+            counter_account: line.counter_account_name.clone(),
+            memo: line.memo.clone(),
+            tax_code: line.tax_code.as_ref().map(|t| t.into_japanese()),
+            partner: line.partner_name.clone(),
+            rule_id: line.rule_id,
+            status: line.line_status.into_japanese(),
+            transaction_id: line.transaction_id,
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct StagedQuery {
+    status: Option<String>,
+}
+
+fn default_learn() -> bool { true }
+
+#[derive(Debug, Deserialize)]
+struct AssignInput {
+    counter_account: String,
+    memo: Option<String>,
+    tax_code: Option<String>,
+    partner: Option<String>,
+    #[serde(default = "default_learn")]
+    learn: bool,
+}
+
+type StagedLineOutput = ApiResponse<Vec<StagedLine>>;
+type PostOutput = ApiResponse<Vec<i32>>;
+type ApplyOutput = ApiResponse<Vec<i32>>;
+
+// Withdrawals debit the counter account, deposits credit it. A taxable
+// line is split into the amount without tax and the consumption tax,
+// which goes to 仮払消費税 for withdrawals and 仮受消費税 for deposits.
+pub(crate) fn into_journal(line: &DbStagedLine) -> Result<Journal, Error> {
+    let counter_account = line.counter_account_name.clone()
+        .ok_or(Error::CounterAccountMissing(line.staged_line_id))?;
+    let (amount, tax_account) = if line.withdrawal_amount > 0_f32 {
+        (line.withdrawal_amount, "仮払消費税")
+    } else {
+        (line.deposit_amount, "仮受消費税")
+    };
+    let tax = line.tax_code.as_ref()
+        .map(|t| t.tax_of(amount))
+        .unwrap_or(0_f32);
+    let mut counter = vec![AccountAmount {
+        account: counter_account,
+        amount: amount - tax,
+        foreign: None,
+    }];
+    if tax > 0_f32 {
+        counter.push(AccountAmount {
+            account: tax_account.to_string(),
+            amount: tax,
+            foreign: None,
+        });
+    }
+    let own = vec![AccountAmount {
+        account: line.account_name.clone(),
+        amount,
+        foreign: None,
+    }];
+    let (debit, credit) = if line.withdrawal_amount > 0_f32 {
+        (counter, own)
+    } else {
+        (own, counter)
+    };
+    Ok(Journal {
+        transaction_type: "InTerm".to_string(),
+        date: line.line_date,
+        debit,
+        credit,
+        desc: line.memo.clone().unwrap_or(line.description.clone()),
+        partner: line.partner_name.clone(),
+        tags: Vec::new(),
+        created_by: None,
+        id: None,
+        review: None,
+    })
+}
+
+pub(crate) async fn post_line(
+    db: &Db,
+    user: &CurrentUser,
+    staged_line_id: i32,
+) -> Result<i32, Error> {
+    let line = match DbStagedLine::by_id(db, staged_line_id).await {
+        Ok(l) => l,
+        Err(ledger_db::Error::RowNotFound)
+            => return Err(Error::StagedLineNotFound(staged_line_id)),
+        Err(e) => return Err(e.into()),
+    };
+    if line.line_status != LineStatus::Draft {
+        return Err(Error::StagedLineNotDraft(staged_line_id));
+    }
+    let transaction = into_journal(&line)?.into_transaction(db).await?;
+    match DbStagedLine::post(db, staged_line_id, &user.stamp(transaction)?).await {
+        Ok(transaction_id) => Ok(transaction_id),
+        // posted by another request since it was read
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::StagedLineNotDraft(staged_line_id)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn show_staged(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StagedQuery>,
+) -> (StatusCode, Json<StagedLineOutput>) {
+    let status = query.status.as_ref()
+        .and_then(|s| s.parse::<LineStatus>().ok())
+        .unwrap_or(LineStatus::Draft);
+    let db_lines = match DbStagedLine::by_status(&state.db, &status).await {
+        Ok(l) => l,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let lines = db_lines.iter()
+        .map(StagedLine::from_db_staged_line)
+        .collect::<Vec<StagedLine>>();
+    (StatusCode::OK, Json(StagedLineOutput::ok(lines)))
+}
+
+// A manual assignment that differs from the proposal is learned as a
+// rule matching the whole description on the same account. It is tried
+// before the rule it corrects, so the next import of the line gets the
+// corrected proposal.
+async fn learn(
+    db: &Db,
+    line: &DbStagedLine,
+    rule_set: &RuleSet,
+) -> Result<(), Error> {
+    let proposed = line.rule_id.and_then(|id| rule_set.by_id(id));
+    let priority = match proposed {
+        Some(rule) if Some(&rule.counter_account_name)
+            == line.counter_account_name.as_ref()
+            && rule.tax_code == line.tax_code
+            => return Ok(()),
+        Some(rule) => rule.priority - 1,
+        None => 100,
+    };
+    if line.description.is_empty() { return Ok(()); }
+    ImportRule {
+        rule_id: 0,
+        // as the column holds, the description may take it all
+        rule_name: format!("学習: {}", line.description)
+            .chars().take(255).collect(),
+        priority,
+        match_type: MatchType::Substring,
+        pattern: Some(line.description.clone()),
+        min_amount: None,
+        max_amount: None,
+        source_account_name: Some(line.account_name.clone()),
+        partner_name: None,
+        counter_account_name: line.counter_account_name.clone()
+            .unwrap_or_default(),
+        tax_code: line.tax_code.clone(),
+        description: line.memo.clone(),
+        learned: true,
+    }.replace_learned(db).await?;
+    Ok(())
+}
+
+async fn assign_line(
+    db: &Db,
+    staged_line_id: i32,
+    input: &AssignInput,
+) -> Result<(), Error> {
+    let mut line = match DbStagedLine::by_id(db, staged_line_id).await {
+        Ok(l) if l.line_status == LineStatus::Draft => l,
+        Ok(_) | Err(ledger_db::Error::RowNotFound)
+            => return Err(Error::StagedLineNotFound(staged_line_id)),
+        Err(e) => return Err(e.into()),
+    };
+    line.counter_account_name = Some(input.counter_account.clone());
+    if input.memo.is_some() { line.memo = input.memo.clone(); }
+    if let Some(tax_code) = &input.tax_code {
+        line.tax_code = Some(tax_code_of(tax_code)?);
+    }
+    if input.partner.is_some() { line.partner_name = input.partner.clone(); }
+    if input.learn {
+        learn(db, &line, &RuleSet::load(db).await?).await?;
+    }
+    line.rule_id = None;
+    match line.update_assignment(db).await {
+        Ok(_) => Ok(()),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::StagedLineNotFound(staged_line_id)),
+        Err(ledger_db::Error::AccountNotFound)
+            => Err(Error::AccountNotFound(input.counter_account.clone())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn assign(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<AssignInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match assign_line(&state.db, id, &input).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e @ Error::StagedLineNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(e.into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+// proposes again for the drafts not assigned by hand,
+// returns the ids of the lines a rule fired for.
+async fn apply_to_drafts(db: &Db) -> Result<Vec<i32>, Error> {
+    let rule_set = RuleSet::load(db).await?;
+    let drafts = match DbStagedLine::by_status(db, &LineStatus::Draft).await {
+        Ok(l) => l,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut applied = Vec::new();
+    for mut line in drafts.into_iter()
+        .filter(|l| l.counter_account_name.is_none() || l.rule_id.is_some()) {
+        if rule_set.propose(&mut line) {
+            line.update_assignment(db).await?;
+            applied.push(line.staged_line_id);
+        }
+    }
+    Ok(applied)
+}
+
+async fn apply_rules(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApplyOutput>) {
+    match apply_to_drafts(&state.db).await {
+        Ok(ids) => (
+            StatusCode::OK,
+            Json(ApplyOutput::ok_with(
+                format!("{} lines proposed", ids.len()),
+                ids,
+            )),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn discard(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbStagedLine::discard(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::StagedLineNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn post_one(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<PostOutput>) {
+    match post_line(&state.db, &user, id).await {
+        Ok(tid) => (StatusCode::CREATED, Json(PostOutput::ok(vec![tid]))),
+        Err(e @ Error::StagedLineNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(e.into_api_response()),
+        ),
+        Err(e @ Error::StagedLineNotDraft(_)) => (
+            StatusCode::CONFLICT,
+            Json(e.into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+// posts every draft that already has a counter account,
+// the rest stays staged.
+async fn post_all(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<PostOutput>) {
+    let drafts
+        = match DbStagedLine::by_status(&state.db, &LineStatus::Draft).await {
+            Ok(l) => l,
+            Err(ledger_db::Error::RowNotFound) => Vec::new(),
+            Err(e) => return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Error::from(e).into_api_response()),
+            ),
+        };
+    let mut posted = Vec::new();
+    for line in drafts.iter().filter(|l| l.counter_account_name.is_some()) {
+        match post_line(&state.db, &user, line.staged_line_id).await {
+            Ok(tid) => posted.push(tid),
+            // taken by a request running alongside
+            Err(Error::StagedLineNotDraft(_)) => (),
+            Err(e) => return (
+                StatusCode::BAD_REQUEST,
+                Json(PostOutput {
+                    status: e.to_string(),
+                    message: format!("{}", e),
+                    body: Some(posted),
+                }),
+            ),
+        }
+    }
+    let remaining = drafts.len() - posted.len();
+    (
+        StatusCode::CREATED,
+        Json(PostOutput::ok_with(
+            format!("{} lines posted, {} remaining", posted.len(), remaining),
+            posted,
+        )),
+    )
+}
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::post,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use ledger_db::ImportSource;
+
+use crate::{
+    text_codec,
+    ApiResponse,
+    AppState,
+    Error,
+};
+
+use super::{
+    parse_amount,
+    stage_lines,
+    StatementLine,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{account}", post(import_zengin))
+}
+
+const RECORD_LENGTH: usize = 200;
+
+// the era the YYMMDD dates of the file are written in
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Era {
+    Reiwa,
+    Heisei,
+    Seireki,
+}
+
+impl Era {
+
+    fn first_year(&self) -> i32 {
+        match self {
+            Era::Reiwa => 2018,
+            Era::Heisei => 1988,
+            Era::Seireki => 2000,
+        }
+    }
+
+    fn parse_date(&self, value: &str) -> Option<NaiveDate> {
+        if value.len() != 6 { return None; }
+        let yy = value[0..2].parse::<i32>().ok()?;
+        let mm = value[2..4].parse::<u32>().ok()?;
+        let dd = value[4..6].parse::<u32>().ok()?;
+        NaiveDate::from_ymd_opt(self.first_year() + yy, mm, dd)
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct ZenginQuery {
+    era: Option<Era>,
+    encoding: Option<String>,
+}
+
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+// Records are 200 bytes, either one per line or written back to back.
+// A trailing EOF (0x1A) is ignored.
+fn records(bytes: &[u8]) -> Vec<&[u8]> {
+    let bytes = match bytes.iter().position(|b| *b == 0x1A) {
+        Some(eof) => &bytes[..eof],
+        None => bytes,
+    };
+    if bytes.contains(&b'\n') {
+        bytes.split(|b| *b == b'\n')
+            .map(|r| r.strip_suffix(b"\r").unwrap_or(r))
+            .filter(|r| !r.is_empty())
+            .collect()
+    } else {
+        bytes.chunks(RECORD_LENGTH).collect()
+    }
+}
+
+// the field at the 1-based byte position, as the layout lists them;
+// records whose trailing blanks were cut off give empty fields
+fn field(
+    record: &[u8],
+    position: usize,
+    length: usize,
+    encoding: &str,
+) -> Result<String, Error> {
+    let start = (position - 1).min(record.len());
+    let end = (start + length).min(record.len());
+    Ok(text_codec::decode(&record[start..end], encoding)?
+        .trim_matches(|c| c == ' ' || c == '　')
+        .to_string())
+}
+
+// 入出金取引明細: a header record (1) per account, data records (2),
+// a trailer (8) and an end record (9). 照会番号 identifies a data record
+// within the day it was booked on.
+pub(crate) fn parse_zengin(
+    bytes: &[u8],
+    era: Era,
+    encoding: &str,
+) -> Result<Vec<StatementLine>, Error> {
+    let mut lines = Vec::new();
+    let mut account_number: Option<String> = None;
+    let mut balance: Option<f32> = None;
+    for (n, record) in records(bytes).into_iter().enumerate() {
+        let f = |position, length| field(record, position, length, encoding);
+        let error = |what: &str| Error::FormatError(
+            format!("record {}: {}", n + 1, what)
+        );
+        match record.first() {
+            Some(b'1') => {
+                let number = f(64, 10)?;
+                if account_number.as_ref().is_some_and(|a| *a != number) {
+                    return Err(error("the file holds several accounts"));
+                }
+                account_number = Some(number);
+                let opening = parse_amount(&f(116, 14)?);
+                // 貸越区分 1 is an overdraft
+                balance = match f(114, 1)?.as_str() {
+                    "1" => opening.map(|b| -b),
+                    _ => opening,
+                };
+            },
+            Some(b'2') => {
+                let reference = f(2, 8)?;
+                let booked = f(10, 6)?;
+                let date = era.parse_date(&booked)
+                    .ok_or(error(&format!("'{}' is not a date", booked)))?;
+                let amount = parse_amount(&f(25, 12)?)
+                    .ok_or(error("no amount"))?;
+                let (withdrawal, deposit) = match f(22, 1)?.as_str() {
+                    "1" => (0_f32, amount),
+                    "2" => (amount, 0_f32),
+                    k => return Err(error(&format!("入払区分 '{}'", k))),
+                };
+                balance = balance.map(|b| b + deposit - withdrawal);
+                // 摘要内容 at 160 and 振込依頼人名 at 82
+                let description = [f(160, 20)?, f(82, 48)?].iter()
+                    .filter(|s| !s.is_empty())
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                lines.push(StatementLine {
+                    date,
+                    description,
+                    withdrawal,
+                    deposit,
+                    balance,
+                    external_id: Some(format!("{}-{}", booked, reference)),
+                });
+            },
+            Some(b'8' | b'9') => (),
+            _ => return Err(error("not a 全銀協 record")),
+        }
+    }
+    if account_number.is_none() {
+        return Err(Error::FormatError("no header record".to_string()));
+    }
+    Ok(lines)
+}
+
+async fn import_zengin(
+    Path(account): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ZenginQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let lines = match parse_zengin(
+        &body,
+        query.era.unwrap_or(Era::Reiwa),
+        query.encoding.as_deref().unwrap_or("Shift_JIS"),
+    ) {
+        Ok(l) => l,
+        Err(e) => return (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    };
+    match stage_lines(&state.db, &ImportSource::Zengin, &account, &lines)
+        .await {
+        Ok((ids, duplicates)) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!(
+                    "{} lines staged, {} duplicates", ids.len(), duplicates,
+                ),
+                ids,
+            )),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 200 byte record of blanks with the fields at their 1-based
+    // positions, in Shift_JIS as the banks send them
+    fn record(fields: &[(usize, &str)]) -> Vec<u8> {
+        let mut record = vec![b' '; RECORD_LENGTH];
+        for (position, value) in fields {
+            let bytes = text_codec::encode(value, "Shift_JIS").unwrap();
+            record[position - 1..position - 1 + bytes.len()]
+                .copy_from_slice(&bytes);
+        }
+        record
+    }
+
+    #[test]
+    fn parses_data_record_fields_at_their_positions() {
+        let header = record(&[
+            (1, "1"), (2, "03"), (4, "0"), (5, "080501"), (11, "080501"),
+            (17, "080531"), (23, "0001"), (27, "ﾐｽﾞﾎ"), (42, "001"),
+            (63, "1"), (64, "1234567"), (74, "ｶ)ｻﾝﾌﾟﾙ"), (114, "0"),
+            (116, "00000000100000"),
+        ]);
+        // the fields just before 振込依頼人名 and 摘要内容 are filled up
+        // to their last byte, so that an offset by one shows
+        let data = record(&[
+            (1, "2"), (2, "00000001"), (10, "080501"), (16, "080501"),
+            (22, "1"), (23, "11"), (25, "000000012800"),
+            (72, "1234567890"), (82, "ﾄｳｷﾖｳｼﾖｳｼﾞ"),
+            (130, "ﾐﾂﾋﾞｼﾕｰｴﾌｼﾞｪｲ"), (145, "ﾆｼｼﾝｼﾞﾕｸｴｷﾏｴｼﾃﾝ"), (160, "ﾌﾘｺﾐ"),
+        ]);
+        let trailer = record(&[(1, "8")]);
+        let end = record(&[(1, "9")]);
+        let file = [header, data, trailer, end].join(&b"\r\n"[..]);
+
+        let lines = parse_zengin(&file, Era::Reiwa, "Shift_JIS").unwrap();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.date, NaiveDate::from_ymd_opt(2026, 5, 1).unwrap());
+        assert_eq!(line.description, "ﾌﾘｺﾐ ﾄｳｷﾖｳｼﾖｳｼﾞ");
+        assert_eq!(line.withdrawal, 0_f32);
+        assert_eq!(line.deposit, 12800_f32);
+        assert_eq!(line.balance, Some(112800_f32));
+        assert_eq!(line.external_id.as_deref(), Some("080501-00000001"));
+    }
+}
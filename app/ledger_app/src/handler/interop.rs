@@ -0,0 +1,171 @@
+pub mod journal_csv;
+pub mod mapping;
+pub mod yayoi;
+
+use std::collections::{
+    BTreeSet,
+    HashMap,
+    HashSet,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::Router;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use ledger_db::{
+    Account,
+    AccountMapping,
+    Db,
+    ExternalSystem,
+    Transaction,
+};
+
+use crate::{
+    auth::CurrentUser,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::Journal;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .nest("/mapping", mapping::build_router())
+    .nest("/yayoi", yayoi::build_router())
+    .nest("/freee", journal_csv::build_router(&journal_csv::FREEE))
+    .nest(
+        "/moneyforward",
+        journal_csv::build_router(&journal_csv::MONEY_FORWARD),
+    )
+}
+
+pub(crate) fn amount_text(amount: f32) -> String {
+    format!("{}", amount.round())
+}
+
+pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
+    ["%Y/%m/%d", "%Y-%m-%d", "%Y%m%d"].iter()
+        .find_map(|f| NaiveDate::parse_from_str(value.trim(), f).ok())
+}
+
+pub(crate) fn system_of(name: &str) -> Result<ExternalSystem, Error> {
+    ExternalSystem::from_str(name)
+        .map_err(|_| Error::ExternalSystemNotFound(name.to_string()))
+}
+
+// Account names between our books and another system. Accounts
+// without a mapping go by the same name in both.
+pub(crate) struct AccountNames {
+    mappings: Vec<AccountMapping>,
+    by_account: HashMap<String, usize>,
+}
+
+impl AccountNames {
+
+    pub(crate) async fn load(
+        db: &Db,
+        external_system: &ExternalSystem,
+    ) -> Result<Self, Error> {
+        let mappings = AccountMapping::by_system(db, external_system).await?;
+        let by_account = mappings.iter().enumerate()
+            .map(|(i, m)| (m.account_name.clone(), i))
+            .collect();
+        Ok(AccountNames { mappings, by_account })
+    }
+
+    // the mapping of our account, if it has one
+    pub(crate) fn external(&self, account_name: &str) -> Option<&AccountMapping> {
+        self.by_account.get(account_name).map(|i| &self.mappings[*i])
+    }
+
+    // our account for the account and sub account of the other system.
+    // a mapping with a sub account is preferred to one without.
+    pub(crate) fn internal(
+        &self,
+        external_account: &str,
+        external_sub_account: &str,
+    ) -> String {
+        let candidates = self.mappings.iter()
+            .filter(|m| m.external_account == external_account)
+            .collect::<Vec<&AccountMapping>>();
+        candidates.iter()
+            .find(|m| {
+                m.external_sub_account.as_deref() == Some(external_sub_account)
+            })
+            .or(candidates.iter().find(|m| {
+                m.external_sub_account.as_deref().unwrap_or("").is_empty()
+            }))
+            .map(|m| m.account_name.clone())
+            .unwrap_or(external_account.to_string())
+    }
+
+}
+
+// the journals of a file and the accounts in them we do not have
+#[derive(Debug, Serialize)]
+pub struct Preview {
+    journals: Vec<Journal>,
+    unknown_accounts: Vec<String>,
+}
+
+impl Preview {
+
+    pub(crate) async fn of(
+        db: &Db,
+        journals: Vec<Journal>,
+    ) -> Result<Self, Error> {
+        let accounts = Account::all(db).await?.into_iter()
+            .map(|a| a.account_name)
+            .collect::<HashSet<String>>();
+        let unknown_accounts = journals.iter()
+            .flat_map(|j| j.debit.iter().chain(j.credit.iter()))
+            .filter(|line| !accounts.contains(&line.account))
+            .map(|line| line.account.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter().collect();
+        Ok(Preview { journals, unknown_accounts })
+    }
+
+}
+
+pub(crate) fn check_balance(journals: &[Journal]) -> Result<(), Error> {
+    for journal in journals {
+        let debit = journal.debit.iter().map(|d| d.amount).sum::<f32>();
+        let credit = journal.credit.iter().map(|c| c.amount).sum::<f32>();
+        if debit != credit {
+            return Err(Error::FormatError(format!(
+                "{} '{}': debit {} and credit {} do not balance",
+                journal.date, journal.desc, debit, credit,
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Nothing is posted unless every journal of a file can be. The accounts
+// a file names and the closing journals of a fiscal year beginning in
+// start_month are all checked first, and the journals are inserted in
+// one transaction.
+pub(crate) async fn post_journals(
+    db: &Db,
+    user: &CurrentUser,
+    start_month: u32,
+    journals: Vec<Journal>,
+) -> Result<Vec<i32>, Error> {
+    let preview = Preview::of(db, journals).await?;
+    if !preview.unknown_accounts.is_empty() {
+        return Err(Error::UnknownAccounts(
+            preview.unknown_accounts.join(", ")
+        ));
+    }
+    let mut transactions = Vec::new();
+    for journal in &preview.journals {
+        let transaction = journal.into_transaction(db).await?
+            .posted_by(db, user.0.as_ref(), start_month).await
+            .map_err(Error::from_posting)?;
+        transactions.push(transaction);
+    }
+    Ok(Transaction::insert_all(db, &transactions).await?)
+}
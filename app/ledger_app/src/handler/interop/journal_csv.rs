@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Query,
+        State,
+    },
+    http::{
+        header,
+        StatusCode,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use ledger_db::{
+    AmountSide,
+    Db,
+    ExternalSystem,
+    Transaction,
+    TransactionType,
+};
+
+use crate::{
+    auth::CurrentUser,
+    text_codec,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::import::parse_amount;
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use super::{
+    amount_text,
+    check_balance,
+    parse_date,
+    post_journals,
+    AccountNames,
+    Preview,
+};
+
+// The 仕訳帳 CSV of freee and マネーフォワード クラウド: a header row,
+// then a row for each pair of debit and credit lines. The rows of a
+// journal share 伝票番号 or 取引No.
+pub(crate) fn build_router(layout: &'static Layout) -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/export", get(
+        move |state: State<Arc<AppState>>, query: Query<ExportQuery>| {
+            export(layout, state, query)
+        }
+    ))
+    .route("/import", post(
+        move |state: State<Arc<AppState>>,
+              user: Extension<CurrentUser>,
+              query: Query<ImportQuery>,
+              body: Bytes| {
+            import(layout, state, user, query, body)
+        }
+    ))
+    .route("/import/preview", post(
+        move |state: State<Arc<AppState>>,
+              query: Query<ImportQuery>,
+              body: Bytes| {
+            preview(layout, state, query, body)
+        }
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Column {
+    Number,
+    Date,
+    Kessan,
+    Account(AmountSide),
+    SubAccount(AmountSide),
+    Partner(AmountSide),
+    TaxCategory(AmountSide),
+    Amount(AmountSide),
+    Tax(AmountSide),
+    Description,
+}
+
+#[derive(Debug)]
+pub(crate) struct Layout {
+    system: ExternalSystem,
+    file_name: &'static str,
+    encoding: &'static str,
+    // 決算整理仕訳 of the journals of 決算
+    kessan_mark: &'static str,
+    // the columns in the order of the file. ones we do not keep are
+    // exported empty and skipped on import.
+    columns: &'static [(&'static str, Option<Column>)],
+}
+
+const DEBIT: AmountSide = AmountSide::Debit;
+const CREDIT: AmountSide = AmountSide::Credit;
+
+pub(crate) const FREEE: Layout = Layout {
+    system: ExternalSystem::Freee,
+    file_name: "freee",
+    encoding: "Shift_JIS",
+    kessan_mark: "決算整理",
+    columns: &[
+        ("取引日", Some(Column::Date)),
+        ("伝票番号", Some(Column::Number)),
+        ("決算整理仕訳", Some(Column::Kessan)),
+        ("借方勘定科目", Some(Column::Account(DEBIT))),
+        ("借方取引先", Some(Column::Partner(DEBIT))),
+        ("借方部門", None),
+        ("借方品目", Some(Column::SubAccount(DEBIT))),
+        ("借方メモタグ", None),
+        ("借方税区分", Some(Column::TaxCategory(DEBIT))),
+        ("借方金額", Some(Column::Amount(DEBIT))),
+        ("借方税額", Some(Column::Tax(DEBIT))),
+        ("貸方勘定科目", Some(Column::Account(CREDIT))),
+        ("貸方取引先", Some(Column::Partner(CREDIT))),
+        ("貸方部門", None),
+        ("貸方品目", Some(Column::SubAccount(CREDIT))),
+        ("貸方メモタグ", None),
+        ("貸方税区分", Some(Column::TaxCategory(CREDIT))),
+        ("貸方金額", Some(Column::Amount(CREDIT))),
+        ("貸方税額", Some(Column::Tax(CREDIT))),
+        ("摘要", Some(Column::Description)),
+    ],
+};
+
+pub(crate) const MONEY_FORWARD: Layout = Layout {
+    system: ExternalSystem::MoneyForward,
+    file_name: "moneyforward",
+    encoding: "Shift_JIS",
+    kessan_mark: "1",
+    columns: &[
+        ("取引No", Some(Column::Number)),
+        ("取引日", Some(Column::Date)),
+        ("借方勘定科目", Some(Column::Account(DEBIT))),
+        ("借方補助科目", Some(Column::SubAccount(DEBIT))),
+        ("借方部門", None),
+        ("借方取引先", Some(Column::Partner(DEBIT))),
+        ("借方税区分", Some(Column::TaxCategory(DEBIT))),
+        ("借方インボイス", None),
+        ("借方金額(円)", Some(Column::Amount(DEBIT))),
+        ("借方税額", Some(Column::Tax(DEBIT))),
+        ("貸方勘定科目", Some(Column::Account(CREDIT))),
+        ("貸方補助科目", Some(Column::SubAccount(CREDIT))),
+        ("貸方部門", None),
+        ("貸方取引先", Some(Column::Partner(CREDIT))),
+        ("貸方税区分", Some(Column::TaxCategory(CREDIT))),
+        ("貸方インボイス", None),
+        ("貸方金額(円)", Some(Column::Amount(CREDIT))),
+        ("貸方税額", Some(Column::Tax(CREDIT))),
+        ("摘要", Some(Column::Description)),
+        ("仕訳メモ", None),
+        ("タグ", None),
+        ("MF仕訳タイプ", None),
+        ("決算整理仕訳", Some(Column::Kessan)),
+    ],
+};
+
+const NO_TAX: &str = "対象外";
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ImportQuery {
+    pub encoding: Option<String>,
+}
+
+type PreviewOutput = ApiResponse<Preview>;
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+impl Layout {
+
+    fn header(&self) -> Vec<String> {
+        self.columns.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    // the position of each of our columns in a header row.
+    // "借方金額" and "借方金額(円)" are taken as the same.
+    fn positions(
+        &self,
+        header: &csv::StringRecord,
+    ) -> Result<HashMap<Column, usize>, Error> {
+        let normalize = |name: &str| {
+            name.trim().trim_end_matches("(円)").trim_end_matches("（円）")
+                .to_string()
+        };
+        let by_name = self.columns.iter()
+            .filter_map(|(name, column)| column.map(|c| (normalize(name), c)))
+            .collect::<HashMap<String, Column>>();
+        let positions = header.iter().enumerate()
+            .filter_map(|(i, name)| by_name.get(&normalize(name))
+                .map(|c| (*c, i)))
+            .collect::<HashMap<Column, usize>>();
+        for (name, column) in self.columns {
+            let required = matches!(
+                column,
+                Some(Column::Date | Column::Account(_) | Column::Amount(_)),
+            );
+            if required && !positions.contains_key(&column.unwrap()) {
+                return Err(Error::FormatError(
+                    format!("no column '{}'", name)
+                ));
+            }
+        }
+        Ok(positions)
+    }
+
+}
+
+// the value of a column of one side of a row. the taxes are booked on
+// lines of their own, so the lines go without 税額.
+fn side_value(
+    names: &AccountNames,
+    line: Option<&AccountAmount>,
+    partner: &str,
+    column: Column,
+) -> String {
+    let Some(line) = line else {
+        return String::new();
+    };
+    let mapping = names.external(&line.account);
+    match column {
+        Column::Account(_) => mapping.map(|m| m.external_account.clone())
+            .unwrap_or(line.account.clone()),
+        Column::SubAccount(_) => mapping
+            .and_then(|m| m.external_sub_account.clone())
+            .unwrap_or_default(),
+        Column::Partner(_) => partner.to_string(),
+        Column::TaxCategory(_) => mapping
+            .and_then(|m| m.tax_category.clone())
+            .unwrap_or(NO_TAX.to_string()),
+        Column::Amount(_) => amount_text(line.amount),
+        Column::Tax(_) => "0".to_string(),
+        _ => String::new(),
+    }
+}
+
+// a row pairs the n-th debit with the n-th credit
+fn journal_rows(
+    layout: &Layout,
+    names: &AccountNames,
+    transaction: &Transaction,
+) -> Vec<Vec<String>> {
+    let journal = Journal::from_transaction(transaction);
+    let partner = journal.partner.as_deref().unwrap_or("");
+    let count = journal.debit.len().max(journal.credit.len());
+    let kessan = match transaction.transaction_type {
+        TransactionType::Kessan => layout.kessan_mark,
+        _ => "",
+    };
+    let mut rows = Vec::new();
+    for n in 0..count {
+        let row = layout.columns.iter()
+            .map(|(_, column)| match column {
+                Some(Column::Number) => transaction.transaction_id.to_string(),
+                Some(Column::Date) => transaction.transaction_date
+                    .format("%Y/%m/%d").to_string(),
+                Some(Column::Kessan) => kessan.to_string(),
+                Some(Column::Description) => journal.desc.clone(),
+                Some(c @ (
+                    Column::Account(side)
+                    | Column::SubAccount(side)
+                    | Column::Partner(side)
+                    | Column::TaxCategory(side)
+                    | Column::Amount(side)
+                    | Column::Tax(side)
+                )) => {
+                    let lines = match side {
+                        AmountSide::Debit => &journal.debit,
+                        AmountSide::Credit => &journal.credit,
+                    };
+                    side_value(names, lines.get(n), partner, *c)
+                },
+                None => String::new(),
+            })
+            .collect::<Vec<String>>();
+        rows.push(row);
+    }
+    rows
+}
+
+// Opening balances and the closing entries are left to the other
+// system, so only 期中仕訳 and 決算仕訳 are exported.
+pub(crate) async fn export_file(
+    layout: &Layout,
+    db: &Db,
+    query: &ExportQuery,
+) -> Result<Vec<u8>, Error> {
+    let names = AccountNames::load(db, &layout.system).await?;
+    let transactions = Transaction::by_period(db, query.from, query.to)
+        .await?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+    writer.write_record(layout.header())
+        .map_err(|e| Error::CsvError(e.to_string()))?;
+    for transaction in &transactions {
+        if !matches!(
+            transaction.transaction_type,
+            TransactionType::InTerm | TransactionType::Kessan,
+        ) {
+            continue;
+        }
+        for row in journal_rows(layout, &names, transaction) {
+            writer.write_record(&row)
+                .map_err(|e| Error::CsvError(e.to_string()))?;
+        }
+    }
+    let bytes = writer.into_inner()
+        .map_err(|e| Error::CsvError(e.to_string()))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|e| Error::CsvError(e.to_string()))?;
+    text_codec::encode(
+        &text, query.encoding.as_deref().unwrap_or(layout.encoding),
+    )
+}
+
+async fn export(
+    layout: &'static Layout,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    match export_file(layout, &state.db, &query).await {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (
+                    header::CONTENT_TYPE,
+                    format!(
+                        "text/csv; charset={}",
+                        query.encoding.as_deref().unwrap_or(layout.encoding),
+                    ),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"{}_{}_{}.csv\"",
+                        layout.file_name,
+                        query.from.format("%Y%m%d"),
+                        query.to.format("%Y%m%d"),
+                    ),
+                ),
+            ],
+            bytes,
+        ).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json::<ApiResponseWithoutBody>(e.into_api_response()),
+        ).into_response(),
+    }
+}
+
+// the rate of a 税区分 such as "課税仕入 10%" or "課税売上8%(軽)".
+// 対象外, 非課税 and 不課税 have none.
+fn tax_rate(tax_category: &str) -> Option<f32> {
+    if ["対象外", "非課税", "不課税", "免税"].iter()
+        .any(|no_tax| tax_category.contains(no_tax))
+    {
+        return None;
+    }
+    let head = tax_category.split(['%', '％']).next()?;
+    let digits = head.chars().rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .chars().rev().collect::<String>();
+    if digits.len() == head.len() { return None; }
+    digits.parse::<f32>().ok()
+}
+
+// The lines of one side of a row. 税額 is taken as included in 金額
+// and booked on 仮払消費税 or 仮受消費税. A row without 税額 has the tax
+// worked out from the rate of its 税区分.
+fn side_lines(
+    names: &AccountNames,
+    value: &dyn Fn(Column) -> String,
+    side: AmountSide,
+    row: usize,
+) -> Result<Vec<AccountAmount>, Error> {
+    let account = value(Column::Account(side));
+    if account.is_empty() { return Ok(Vec::new()); }
+    let amount_of = |column: Column| match value(column).as_str() {
+        "" => Ok(None),
+        v => parse_amount(v).map(Some).ok_or(Error::FormatError(
+            format!("row {}: '{}' is not an amount", row, v)
+        )),
+    };
+    let amount = amount_of(Column::Amount(side))?.unwrap_or(0_f32);
+    let tax = match amount_of(Column::Tax(side))? {
+        Some(tax) => tax,
+        None => tax_rate(&value(Column::TaxCategory(side)))
+            .map(|rate| (amount * rate / (100_f32 + rate)).floor())
+            .unwrap_or(0_f32),
+    };
+    let tax_account = match side {
+        AmountSide::Debit => "仮払消費税",
+        AmountSide::Credit => "仮受消費税",
+    };
+    let mut lines = vec![AccountAmount {
+        account: names.internal(
+            &account, &value(Column::SubAccount(side)),
+        ),
+        amount: amount - tax,
+        foreign: None,
+    }];
+    if tax != 0_f32 {
+        lines.push(AccountAmount {
+            account: tax_account.to_string(),
+            amount: tax,
+            foreign: None,
+        });
+    }
+    Ok(lines)
+}
+
+// the rows of a file put together into journals by their number.
+// a row without one is a journal by itself.
+fn parse_journal_csv(
+    layout: &Layout,
+    names: &AccountNames,
+    text: &str,
+) -> Result<Vec<Journal>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let header = reader.headers()
+        .map_err(|e| Error::CsvError(e.to_string()))?.clone();
+    let positions = layout.positions(&header)?;
+
+    let mut journals: Vec<Journal> = Vec::new();
+    let mut last_number = String::new();
+    for (n, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| Error::CsvError(e.to_string()))?;
+        let row = n + 2;
+        let value = |column: Column| positions.get(&column)
+            .and_then(|i| record.get(*i))
+            .unwrap_or("").trim().to_string();
+        if record.iter().all(|v| v.trim().is_empty()) { continue; }
+
+        let number = value(Column::Number);
+        let continues = !number.is_empty() && number == last_number;
+        last_number = number;
+        if !continues {
+            let kessan = !matches!(
+                value(Column::Kessan).to_lowercase().as_str(),
+                "" | "0" | "false" | "no",
+            );
+            let date = value(Column::Date);
+            journals.push(Journal {
+                transaction_type: if kessan { "Kessan" } else { "InTerm" }
+                    .to_string(),
+                date: parse_date(&date).ok_or(Error::FormatError(
+                    format!("row {}: '{}' is not a date", row, date)
+                ))?,
+                debit: Vec::new(),
+                credit: Vec::new(),
+                desc: String::new(),
+                partner: None,
+                tags: Vec::new(),
+                created_by: None,
+                id: None,
+                review: None,
+            });
+        }
+        let journal = journals.last_mut().unwrap();
+        journal.debit.extend(side_lines(names, &value, DEBIT, row)?);
+        journal.credit.extend(side_lines(names, &value, CREDIT, row)?);
+        if journal.desc.is_empty() {
+            journal.desc = value(Column::Description);
+        }
+        if journal.partner.is_none() {
+            journal.partner = [
+                value(Column::Partner(DEBIT)),
+                value(Column::Partner(CREDIT)),
+            ].into_iter().find(|p| !p.is_empty());
+        }
+    }
+    check_balance(&journals)?;
+    Ok(journals)
+}
+
+async fn read_file(
+    layout: &Layout,
+    db: &Db,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Vec<Journal>, Error> {
+    let names = AccountNames::load(db, &layout.system).await?;
+    let text = text_codec::decode(
+        body, query.encoding.as_deref().unwrap_or(layout.encoding),
+    )?;
+    parse_journal_csv(layout, &names, &text)
+}
+
+pub(crate) async fn import_file(
+    layout: &Layout,
+    db: &Db,
+    user: &CurrentUser,
+    start_month: u32,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Vec<i32>, Error> {
+    let journals = read_file(layout, db, query, body).await?;
+    post_journals(db, user, start_month, journals).await
+}
+
+async fn import(
+    layout: &'static Layout,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let start_month = state.config.fiscal_year.start_month;
+    match import_file(layout, &state.db, &user, start_month, &query, &body).await {
+        Ok(ids) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!("{} journals imported", ids.len()),
+                ids,
+            )),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    }
+}
+
+pub(crate) async fn preview_file(
+    layout: &Layout,
+    db: &Db,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Preview, Error> {
+    Preview::of(db, read_file(layout, db, query, body).await?).await
+}
+
+// the journals the file would post and the accounts we do not have,
+// without posting anything
+async fn preview(
+    layout: &'static Layout,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<PreviewOutput>) {
+    match preview_file(layout, &state.db, &query, &body).await {
+        Ok(preview) => (StatusCode::OK, Json(PreviewOutput::ok(preview))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    }
+}
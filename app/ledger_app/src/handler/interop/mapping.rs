@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+    },
+    Json,
+    Router,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AccountMapping as DbAccountMapping,
+    Db,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use super::system_of;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{system}", get(show_mapping).post(upsert_mapping))
+    .route("/{system}/{account}", delete(delete_mapping))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AccountMapping {
+    account: String,
+    external_account: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_sub_account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tax_category: Option<String>,
+}
+
+impl AccountMapping {
+
+    fn from_db_mapping(mapping: &DbAccountMapping) -> Self {
+        AccountMapping {
+            account: mapping.account_name.clone(),
+            external_account: mapping.external_account.clone(),
+            external_sub_account: mapping.external_sub_account.clone(),
+            tax_category: mapping.tax_category.clone(),
+        }
+    }
+
+}
+
+type AccountMappingInput = AccountMapping;
+type AccountMappingOutput = ApiResponse<Vec<AccountMapping>>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::ExternalSystemNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn mappings_of(
+    db: &Db,
+    system: &str,
+) -> Result<Vec<AccountMapping>, Error> {
+    let external_system = system_of(system)?;
+    Ok(DbAccountMapping::by_system(db, &external_system).await?.iter()
+        .map(AccountMapping::from_db_mapping)
+        .collect())
+}
+
+async fn show_mapping(
+    Path(system): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<AccountMappingOutput>) {
+    match mappings_of(&state.db, &system).await {
+        Ok(m) => (StatusCode::OK, Json(AccountMappingOutput::ok(m))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// the mappings given replace the ones of the same accounts
+async fn upsert_db_mapping(
+    db: &Db,
+    system: &str,
+    input: &[AccountMappingInput],
+) -> Result<(), Error> {
+    let external_system = system_of(system)?;
+    for mapping in input {
+        let db_mapping = DbAccountMapping {
+            external_system,
+            account_name: mapping.account.clone(),
+            external_account: mapping.external_account.clone(),
+            external_sub_account: mapping.external_sub_account.clone(),
+            tax_category: mapping.tax_category.clone(),
+        };
+        match db_mapping.upsert(db).await {
+            Ok(_) => (),
+            Err(ledger_db::Error::AccountNotFound)
+                => return Err(Error::AccountNotFound(mapping.account.clone())),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+async fn upsert_mapping(
+    Path(system): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<Vec<AccountMappingInput>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match upsert_db_mapping(&state.db, &system, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn delete_db_mapping(
+    db: &Db,
+    system: &str,
+    account: &str,
+) -> Result<(), Error> {
+    let external_system = system_of(system)?;
+    match DbAccountMapping::delete(db, &external_system, account).await {
+        Ok(_) => Ok(()),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::AccountNotFound(account.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn delete_mapping(
+    Path((system, account)): Path<(String, String)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match delete_db_mapping(&state.db, &system, &account).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(Error::AccountNotFound(a)) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::AccountNotFound(a).into_api_response()),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
@@ -0,0 +1,389 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Query,
+        State,
+    },
+    http::{
+        header,
+        StatusCode,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use ledger_db::{
+    Db,
+    ExternalSystem,
+    Transaction,
+    TransactionType,
+};
+
+use crate::{
+    auth::CurrentUser,
+    text_codec,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::import::parse_amount;
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use super::{
+    amount_text,
+    check_balance,
+    parse_date,
+    post_journals,
+    AccountNames,
+    Preview,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/export", get(export))
+    .route("/import", post(import))
+    .route("/import/preview", post(preview))
+}
+
+// 識別フラグ of the rows of a journal
+const SINGLE: &str = "2000";
+const FIRST: &str = "2110";
+const MIDDLE: &str = "2100";
+const LAST: &str = "2101";
+const SINGLE_SLIP: &str = "2111";
+
+const NO_TAX: &str = "対象外";
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ImportQuery {
+    pub encoding: Option<String>,
+}
+
+type PreviewOutput = ApiResponse<Preview>;
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+// 勘定科目, 補助科目, 部門, 税区分, 金額 and 税金額 of one side of a row
+fn side_columns(
+    names: &AccountNames,
+    line: Option<&AccountAmount>,
+) -> Vec<String> {
+    let Some(line) = line else {
+        return vec![String::new(); 6];
+    };
+    let mapping = names.external(&line.account);
+    vec![
+        mapping.map(|m| m.external_account.clone())
+            .unwrap_or(line.account.clone()),
+        mapping.and_then(|m| m.external_sub_account.clone())
+            .unwrap_or_default(),
+        String::new(),
+        mapping.and_then(|m| m.tax_category.clone())
+            .unwrap_or(NO_TAX.to_string()),
+        amount_text(line.amount),
+        "0".to_string(),
+    ]
+}
+
+// A row pairs the n-th debit with the n-th credit. The taxes are booked
+// on lines of their own, so the lines go without 税金額.
+fn journal_rows(
+    names: &AccountNames,
+    transaction: &Transaction,
+) -> Vec<Vec<String>> {
+    let journal = Journal::from_transaction(transaction);
+    let count = journal.debit.len().max(journal.credit.len());
+    let mut rows = Vec::new();
+    for n in 0..count {
+        let flag = match n {
+            _ if count == 1 => SINGLE,
+            0 => FIRST,
+            _ if n == count - 1 => LAST,
+            _ => MIDDLE,
+        };
+        let kessan = match transaction.transaction_type {
+            TransactionType::Kessan => "本決",
+            _ => "",
+        };
+        let mut row = vec![
+            flag.to_string(),
+            transaction.transaction_id.to_string(),
+            kessan.to_string(),
+            transaction.transaction_date.format("%Y/%m/%d").to_string(),
+        ];
+        row.extend(side_columns(names, journal.debit.get(n)));
+        row.extend(side_columns(names, journal.credit.get(n)));
+        row.extend([
+            journal.desc.clone(),
+            String::new(),  // 番号
+            String::new(),  // 期日
+            "0".to_string(),  // タイプ: 仕訳
+            String::new(),  // 生成元
+            String::new(),  // 仕訳メモ
+            "0".to_string(),  // 付箋1
+            "0".to_string(),  // 付箋2
+            "no".to_string(),  // 調整
+        ]);
+        rows.push(row);
+    }
+    rows
+}
+
+// 弥生インポート形式 of the 仕訳日記帳: 25 columns, no header,
+// Shift_JIS with CR LF. Opening balances and the closing entries are
+// left to 弥生会計, so only 期中仕訳 and 決算仕訳 are exported.
+pub(crate) async fn export_file(
+    db: &Db,
+    query: &ExportQuery,
+) -> Result<Vec<u8>, Error> {
+    let names = AccountNames::load(db, &ExternalSystem::Yayoi).await?;
+    let transactions = Transaction::by_period(db, query.from, query.to)
+        .await?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+    for transaction in &transactions {
+        if !matches!(
+            transaction.transaction_type,
+            TransactionType::InTerm | TransactionType::Kessan,
+        ) {
+            continue;
+        }
+        for row in journal_rows(&names, transaction) {
+            writer.write_record(&row)
+                .map_err(|e| Error::CsvError(e.to_string()))?;
+        }
+    }
+    let bytes = writer.into_inner()
+        .map_err(|e| Error::CsvError(e.to_string()))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|e| Error::CsvError(e.to_string()))?;
+    text_codec::encode(&text, "Shift_JIS")
+}
+
+async fn export(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    match export_file(&state.db, &query).await {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/csv; charset=Shift_JIS".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"yayoi_{}_{}.csv\"",
+                        query.from.format("%Y%m%d"),
+                        query.to.format("%Y%m%d"),
+                    ),
+                ),
+            ],
+            bytes,
+        ).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json::<ApiResponseWithoutBody>(e.into_api_response()),
+        ).into_response(),
+    }
+}
+
+// The lines of one side of a row. 税金額 is taken as included in
+// 金額 and booked on 仮払消費税 or 仮受消費税.
+fn side_lines(
+    names: &AccountNames,
+    record: &csv::StringRecord,
+    first: usize,
+    tax_account: &str,
+    row: usize,
+) -> Result<Vec<AccountAmount>, Error> {
+    let column = |i: usize| record.get(first + i).unwrap_or("").trim();
+    if column(0).is_empty() { return Ok(Vec::new()); }
+    let amount_of = |i: usize| match column(i) {
+        "" => Ok(0_f32),
+        value => parse_amount(value).ok_or(Error::FormatError(
+            format!("row {}: '{}' is not an amount", row, value)
+        )),
+    };
+    let amount = amount_of(4)?;
+    let tax = amount_of(5)?;
+    let mut lines = vec![AccountAmount {
+        account: names.internal(column(0), column(1)),
+        amount: amount - tax,
+        foreign: None,
+    }];
+    if tax != 0_f32 {
+        lines.push(AccountAmount {
+            account: tax_account.to_string(),
+            amount: tax,
+            foreign: None,
+        });
+    }
+    Ok(lines)
+}
+
+// the rows of a file put together into journals by 識別フラグ
+fn parse_yayoi(
+    names: &AccountNames,
+    text: &str,
+) -> Result<Vec<Journal>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut journals = Vec::new();
+    let mut current: Option<Journal> = None;
+    for (n, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| Error::CsvError(e.to_string()))?;
+        let row = n + 1;
+        let flag = record.get(0).unwrap_or("").trim();
+        if flag.is_empty() { continue; }
+        if record.len() < 17 {
+            return Err(Error::FormatError(
+                format!("row {}: {} columns", row, record.len())
+            ));
+        }
+        let starts = matches!(flag, SINGLE | SINGLE_SLIP | FIRST);
+        let ends = matches!(flag, SINGLE | SINGLE_SLIP | LAST);
+        if !starts && !matches!(flag, MIDDLE | LAST) {
+            return Err(Error::FormatError(
+                format!("row {}: 識別フラグ '{}'", row, flag)
+            ));
+        }
+        if starts == current.is_some() {
+            return Err(Error::FormatError(
+                format!("row {}: a journal is not closed", row)
+            ));
+        }
+        let journal = current.get_or_insert_with(|| {
+            let kessan = !record.get(2).unwrap_or("").trim().is_empty();
+            Journal {
+                transaction_type: if kessan { "Kessan" } else { "InTerm" }
+                    .to_string(),
+                date: NaiveDate::MIN,
+                debit: Vec::new(),
+                credit: Vec::new(),
+                desc: String::new(),
+                partner: None,
+                tags: Vec::new(),
+                created_by: None,
+                id: None,
+                review: None,
+            }
+        });
+        let date = record.get(3).unwrap_or("");
+        journal.date = parse_date(date).ok_or(Error::FormatError(
+            format!("row {}: '{}' is not a date", row, date)
+        ))?;
+        journal.debit.extend(
+            side_lines(names, &record, 4, "仮払消費税", row)?
+        );
+        journal.credit.extend(
+            side_lines(names, &record, 10, "仮受消費税", row)?
+        );
+        let desc = record.get(16).unwrap_or("").trim();
+        if journal.desc.is_empty() {
+            journal.desc = desc.to_string();
+        }
+        if ends {
+            journals.extend(current.take());
+        }
+    }
+    if current.is_some() {
+        return Err(Error::FormatError(
+            "the last journal is not closed".to_string()
+        ));
+    }
+    check_balance(&journals)?;
+    Ok(journals)
+}
+
+async fn read_file(
+    db: &Db,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Vec<Journal>, Error> {
+    let names = AccountNames::load(db, &ExternalSystem::Yayoi).await?;
+    let text = text_codec::decode(
+        body, query.encoding.as_deref().unwrap_or("Shift_JIS"),
+    )?;
+    parse_yayoi(&names, &text)
+}
+
+pub(crate) async fn import_file(
+    db: &Db,
+    user: &CurrentUser,
+    start_month: u32,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Vec<i32>, Error> {
+    let journals = read_file(db, query, body).await?;
+    post_journals(db, user, start_month, journals).await
+}
+
+async fn import(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let start_month = state.config.fiscal_year.start_month;
+    match import_file(&state.db, &user, start_month, &query, &body).await {
+        Ok(ids) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!("{} journals imported", ids.len()),
+                ids,
+            )),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    }
+}
+
+pub(crate) async fn preview_file(
+    db: &Db,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<Preview, Error> {
+    Preview::of(db, read_file(db, query, body).await?).await
+}
+
+// the journals the file would post, without posting them
+async fn preview(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> (StatusCode, Json<PreviewOutput>) {
+    match preview_file(&state.db, &query, &body).await {
+        Ok(preview) => (StatusCode::OK, Json(PreviewOutput::ok(preview))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    }
+}
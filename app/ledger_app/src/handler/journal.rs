@@ -1,40 +1,39 @@
 pub mod journal_payload;
-pub mod from_prev;
-pub mod buy;
-pub mod sell;
-pub mod bank;
-pub mod kessan;
-pub mod soneki;
-pub mod to_next;
+pub mod template;
+pub mod search;
 
 use std::sync::Arc;
 
 use axum::{
     extract::{
         Path,
+        Query,
         State,
     },
     http::StatusCode,
     routing::get,
+    Extension,
     Json,
     Router,
 };
-use chrono::{
-    Datelike,
-    TimeZone,
-    Utc,
-};
-use chrono_tz::Japan;
+use chrono::Datelike;
+use serde::Deserialize;
 
 use ledger_db::Transaction;
 
 use crate::{
+    auth::CurrentUser,
     ApiResponse,
     ApiResponseWithoutBody,
     AppState,
     Error,
 };
 
+use crate::handler::review::{
+    check_closable,
+    ReviewFilter,
+};
+
 use journal_payload::{
     AccountAmount,
     Journal,
@@ -42,24 +41,26 @@ use journal_payload::{
 
 pub(crate) fn build_router() -> Router<Arc<AppState>> {
     Router::new()
-    .nest("/from_prev", from_prev::build_router())
-    .nest("/buy", buy::build_router())
-    .nest("/sell", sell::build_router())
-    .nest("/bank", bank::build_router())
-    .nest("/kessan", kessan::build_router())
-    .nest("/soneki", soneki::build_router())
-    .nest("/to_next", to_next::build_router())
+    .nest("/template", template::build_router())
+    .merge(template::build_shortcut_router())
+    .merge(search::build_router())
     .route("/", get(show_journal_today).post(insert_journal))
     .route("/{year}/{month}", get(show_journal))
 }
 
+// ?review=open, needs_review or reviewed picks the journals under review
+#[derive(Debug, Deserialize)]
+struct JournalQuery {
+    review: Option<String>,
+}
+
 type JournalInput = Journal;
 type JournalOutput = ApiResponse<Vec<Journal>>;
 
 async fn show_journal_today(
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<JournalOutput>) {
-    let now = Japan.from_utc_datetime(&Utc::now().naive_utc());
+    let now = state.now();
     let ym = (now.year(), now.month());
     let trans
         = match Transaction::by_month(&state.db, ym.0, ym.1).await {
@@ -71,20 +72,35 @@ async fn show_journal_today(
             ),
         };
     let journals = trans.iter()
-        .map(|t| Journal::from_transaction(t))
+        .map(Journal::from_transaction)
         .collect::<Vec<Journal>>();
     (StatusCode::OK, Json(JournalOutput::ok(journals)))
 }
 
 async fn insert_journal(
     State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
     Json(input): Json<JournalInput>,
 ) -> (StatusCode, Json<ApiResponseWithoutBody>) {
-    let insert_result = match input.into_transaction(&state.db).await {
+    let tran = match input.into_transaction(&state.db).await
+        .and_then(|tran| user.stamp(tran))
+    {
+        Ok(tran) => check_closable(&state, &tran).await.map(|_| tran),
+        Err(e) => Err(e),
+    };
+    let insert_result = match tran {
         Ok(tran) => tran.insert(&state.db).await,
+        Err(e @ Error::Forbidden(_)) => return (
+            StatusCode::FORBIDDEN,
+            Json(e.into_api_response()),
+        ),
+        Err(e @ Error::ReviewOpen(..)) => return (
+            StatusCode::CONFLICT,
+            Json(e.into_api_response()),
+        ),
         Err(e) => return (
             StatusCode::BAD_REQUEST,
-            Json(Error::from(e).into_api_response()),
+            Json(e.into_api_response()),
         ),
     };
     match insert_result {
@@ -98,8 +114,14 @@ async fn insert_journal(
 
 async fn show_journal(
     Path(ym): Path<(i32, u32)>,
+    Query(query): Query<JournalQuery>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<JournalOutput>) {
+    let filter = match query.review.as_deref().map(ReviewFilter::parse) {
+        Some(Ok(filter)) => Some(filter),
+        Some(Err(e)) => return (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+        None => None,
+    };
     let trans
         = match Transaction::by_month(&state.db, ym.0, ym.1).await {
             Ok(t) => t,
@@ -110,7 +132,8 @@ async fn show_journal(
             ),
         };
     let journals = trans.iter()
-        .map(|t| Journal::from_transaction(t))
+        .filter(|t| filter.is_none_or(|f| f.matches(t)))
+        .map(Journal::from_transaction)
         .collect::<Vec<Journal>>();
     (StatusCode::OK, Json(JournalOutput::ok(journals)))
 }
@@ -8,24 +8,86 @@ use ledger_db::{
     Account,
     AmountSide,
     Db,
+    ExchangeRate,
+    ForeignAmount,
     Transaction,
     TransactionDetail,
 };
 
 use crate::Error;
 
+// An amount in another currency, converted to yen at the rate of the
+// date unless the rate is given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Foreign {
+    pub currency: String,
+    pub amount: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f32>,
+}
+
+impl Foreign {
+
+    #[allow(clippy::wrong_self_convention)]
+    pub async fn into_foreign_amount(
+        &self,
+        db: &Db,
+        date: NaiveDate,
+    ) -> Result<ForeignAmount, Error> {
+        let currency = self.currency.trim().to_uppercase();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::CurrencyError(
+                format!("'{}' is not a currency code", self.currency)
+            ));
+        }
+        if currency == "JPY" {
+            return Err(Error::CurrencyError(
+                "the books are kept in JPY already".to_string()
+            ));
+        }
+        let rate = match self.rate {
+            Some(rate) if rate > 0_f32 => rate,
+            Some(rate) => return Err(Error::CurrencyError(
+                format!("{} is not a rate", rate)
+            )),
+            None => match ExchangeRate::on(db, &currency, date).await {
+                Ok(r) => r.rate,
+                Err(ledger_db::Error::RowNotFound)
+                    => return Err(Error::RateNotFound(currency, date)),
+                Err(e) => return Err(e.into()),
+            },
+        };
+        Ok(ForeignAmount { currency, amount: self.amount, rate })
+    }
+
+    pub fn from_foreign_amount(foreign: &ForeignAmount) -> Self {
+        Foreign {
+            currency: foreign.currency.clone(),
+            amount: foreign.amount,
+            rate: Some(foreign.rate),
+        }
+    }
+
+}
+
+// the amount is left out for a line in another currency
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AccountAmount {
     pub account: String,
+    #[serde(default)]
     pub amount: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign: Option<Foreign>,
 }
 
 impl AccountAmount {
 
+    #[allow(clippy::wrong_self_convention)]
     pub async fn into_transaction_detail(
         &self,
         db: &Db,
         side: AmountSide,
+        date: NaiveDate,
     ) -> Result<TransactionDetail, Error> {
         let acc_search_result
             = Account::by_name(db, &self.account).await;
@@ -35,18 +97,28 @@ impl AccountAmount {
                 => return Err(Error::AccountNotFound(self.account.clone())),
             Err(err) => return Err(err.into()),
         };
+        // the yen have no fractions
+        let (amount, foreign) = match &self.foreign {
+            Some(f) => {
+                let foreign = f.into_foreign_amount(db, date).await?;
+                ((foreign.amount * foreign.rate).round(), Some(foreign))
+            },
+            None => (self.amount, None),
+        };
         match side {
             AmountSide::Debit => Ok(TransactionDetail {
                 account_name: self.account.clone(),
                 account_type,
-                debit_amount: self.amount,
+                debit_amount: amount,
                 credit_amount: 0_f32,
+                foreign,
             }),
             AmountSide::Credit => Ok(TransactionDetail {
                 account_name: self.account.clone(),
                 account_type,
                 debit_amount: 0_f32,
-                credit_amount: self.amount,
+                credit_amount: amount,
+                foreign,
             }),
         }
     }
@@ -64,7 +136,45 @@ impl AccountAmount {
             } else {
                 (td.credit_amount - td.debit_amount, AmountSide::Credit)
             };
-        (AccountAmount { account: td.account_name.clone(), amount }, side)
+        let foreign = td.foreign.as_ref().map(Foreign::from_foreign_amount);
+        (AccountAmount { account: td.account_name.clone(), amount, foreign }, side)
+    }
+
+}
+
+// trimmed and each once, in the order given
+pub fn tags_of(input: &[String]) -> Result<Vec<String>, Error> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if tag.contains(',') || tag.chars().count() > 50 {
+            return Err(Error::FormatError(format!("'{}' is not a tag", tag)));
+        }
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    Ok(tags)
+}
+
+// the review of a posted journal
+#[derive(Debug, Serialize)]
+pub struct JournalReview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    pub open_comments: i64,
+}
+
+impl JournalReview {
+
+    // None while nobody has asked about the transaction
+    pub fn from_transaction(tran: &Transaction) -> Option<Self> {
+        if tran.review_status.is_none() && tran.open_comments == 0 {
+            return None;
+        }
+        Some(JournalReview {
+            status: tran.review_status.map(|s| s.to_string()),
+            open_comments: tran.open_comments,
+        })
     }
 
 }
@@ -76,10 +186,23 @@ pub struct Journal {
     pub debit: Vec<AccountAmount>,
     pub credit: Vec<AccountAmount>,
     pub desc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // who posted it, not read from the input
+    #[serde(default, skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub created_by: Option<String>,
+    // the transaction to comment on, not read from the input either
+    #[serde(default, skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub review: Option<JournalReview>,
 }
 
 impl Journal {
 
+    #[allow(clippy::wrong_self_convention)]
     pub async fn into_transaction(
         &self,
         db: &Db,
@@ -88,13 +211,13 @@ impl Journal {
         let mut details = Vec::new();
         for debit in &self.debit {
             details.push(
-                debit.into_transaction_detail(db, AmountSide::Debit)
+                debit.into_transaction_detail(db, AmountSide::Debit, self.date)
                 .await?
             );
         }
         for credit in &self.credit {
             details.push(
-                credit.into_transaction_detail(db, AmountSide::Credit)
+                credit.into_transaction_detail(db, AmountSide::Credit, self.date)
                 .await?
             );
         }
@@ -103,6 +226,11 @@ impl Journal {
             transaction_date: self.date,
             transaction_type,
             description: self.desc.clone(),
+            partner_name: self.partner.clone(),
+            created_by: None,
+            tags: tags_of(&self.tags)?,
+            review_status: None,
+            open_comments: 0,
             details,
         })
     }
@@ -125,6 +253,11 @@ impl Journal {
             debit,
             credit,
             desc: tran.description.clone(),
+            partner: tran.partner_name.clone(),
+            tags: tran.tags.clone(),
+            created_by: tran.created_by.clone(),
+            id: Some(tran.transaction_id),
+            review: JournalReview::from_transaction(tran),
         }
     }
 
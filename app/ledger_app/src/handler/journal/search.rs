@@ -0,0 +1,260 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AmountSide,
+    Db,
+    SearchCursor,
+    SearchSort,
+    Transaction,
+    TransactionSearch,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::import::parse_amount;
+use crate::handler::summary::stage_from_str;
+
+use super::journal_payload::{
+    tags_of,
+    Journal,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/search", get(search_journal))
+    .route("/tags/{id}", post(set_tags))
+}
+
+// ?from=2026-03-01&to=2026-05-31&amount=12800 and so on, the amounts
+// as 12,800 or 12800円 too. tag takes a comma list, all of which are to
+// be on the journal.
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    #[serde(rename = "type")]
+    transaction_type: Option<String>,
+    account: Option<String>,
+    side: Option<String>,
+    amount: Option<String>,
+    min_amount: Option<String>,
+    max_amount: Option<String>,
+    q: Option<String>,
+    partner: Option<String>,
+    tag: Option<String>,
+    sort: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    journals: Vec<Journal>,
+    // for ?cursor= of the next page, None on the last one
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsInput {
+    tags: Vec<String>,
+}
+
+type SearchOutput = ApiResponse<SearchResult>;
+
+fn search_error(message: impl Into<String>) -> Error {
+    Error::SearchError(message.into())
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn amount_of(name: &str, value: &Option<String>) -> Result<Option<f32>, Error> {
+    match non_empty(value) {
+        Some(v) => parse_amount(&v).map(Some)
+            .ok_or(search_error(format!("{} '{}' is not an amount", name, v))),
+        None => Ok(None),
+    }
+}
+
+fn side_of(value: &str) -> Result<Option<AmountSide>, Error> {
+    match value.to_lowercase().as_str() {
+        "either" | "any" => Ok(None),
+        "debit" => Ok(Some(AmountSide::Debit)),
+        "credit" => Ok(Some(AmountSide::Credit)),
+        _ => AmountSide::from_japanese(value).map(Some)
+            .ok_or(search_error(format!("'{}' is not a side", value))),
+    }
+}
+
+fn sort_of(value: &str) -> Result<SearchSort, Error> {
+    match value {
+        "date" => Ok(SearchSort::DateAsc),
+        "-date" => Ok(SearchSort::DateDesc),
+        "amount" => Ok(SearchSort::AmountAsc),
+        "-amount" => Ok(SearchSort::AmountDesc),
+        _ => Err(search_error(format!(
+            "'{}' is not one of date, -date, amount and -amount", value,
+        ))),
+    }
+}
+
+// opaque to the client, d|2026-05-03|123 or a|12800.00|123 inside
+fn encode_cursor(cursor: &SearchCursor) -> String {
+    let text = match cursor {
+        SearchCursor::Date(date, id) => format!("d|{}|{}", date, id),
+        SearchCursor::Amount(amount, id) => format!("a|{}|{}", amount, id),
+    };
+    URL_SAFE_NO_PAD.encode(text)
+}
+
+fn decode_cursor(value: &str) -> Result<SearchCursor, Error> {
+    let text = URL_SAFE_NO_PAD.decode(value.trim()).ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(search_error("malformed cursor"))?;
+    let cursor = match text.split('|').collect::<Vec<&str>>().as_slice() {
+        ["d", date, id] => NaiveDate::from_str(date).ok()
+            .zip(id.parse::<i32>().ok())
+            .map(|(date, id)| SearchCursor::Date(date, id)),
+        ["a", amount, id] => Decimal::from_str(amount).ok()
+            .zip(id.parse::<i32>().ok())
+            .map(|(amount, id)| SearchCursor::Amount(amount, id)),
+        _ => None,
+    };
+    cursor.ok_or(search_error("malformed cursor"))
+}
+
+impl SearchQuery {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_search(&self) -> Result<TransactionSearch, Error> {
+        let transaction_type = match non_empty(&self.transaction_type) {
+            Some(t) => Some(stage_from_str(&t)
+                .ok_or(search_error(format!("'{}' is not a transaction type", t)))?),
+            None => None,
+        };
+        let side = match non_empty(&self.side) {
+            Some(s) => side_of(&s)?,
+            None => None,
+        };
+        // an exact amount is a range of one
+        let (min_amount, max_amount) = match amount_of("amount", &self.amount)? {
+            Some(amount) => (Some(amount), Some(amount)),
+            None => (
+                amount_of("min_amount", &self.min_amount)?,
+                amount_of("max_amount", &self.max_amount)?,
+            ),
+        };
+        let tags = self.tag.as_deref().unwrap_or("").split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<String>>();
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(search_error(format!("limit is from 1 to {}", MAX_LIMIT)));
+        }
+        Ok(TransactionSearch {
+            from: self.from,
+            to: self.to,
+            transaction_type,
+            account_name: non_empty(&self.account),
+            side,
+            min_amount,
+            max_amount,
+            description: non_empty(&self.q),
+            partner_name: non_empty(&self.partner),
+            tags,
+            sort: match non_empty(&self.sort) {
+                Some(s) => sort_of(&s)?,
+                None => SearchSort::default(),
+            },
+            after: match non_empty(&self.cursor) {
+                Some(c) => Some(decode_cursor(&c)?),
+                None => None,
+            },
+            limit,
+        })
+    }
+
+}
+
+async fn search_db_journal(db: &Db, query: &SearchQuery) -> Result<SearchResult, Error> {
+    let page = match Transaction::search(db, &query.into_search()?).await {
+        Ok(page) => page,
+        Err(ledger_db::Error::InvalidCursor)
+            => return Err(search_error("the cursor is of another sort")),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(SearchResult {
+        journals: page.transactions.iter().map(Journal::from_transaction).collect(),
+        next_cursor: page.next.as_ref().map(encode_cursor),
+    })
+}
+
+// newest first unless sorted otherwise
+async fn search_journal(
+    Query(query): Query<SearchQuery>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<SearchOutput>) {
+    match search_db_journal(&state.db, &query).await {
+        Ok(result) => (StatusCode::OK, Json(SearchOutput::ok(result))),
+        Err(e @ Error::SearchError(_)) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(e.into_api_response())),
+    }
+}
+
+async fn set_db_tags(db: &Db, id: i32, input: &TagsInput) -> Result<(), Error> {
+    match Transaction::set_tags(db, id, &tags_of(&input.tags)?).await {
+        Err(ledger_db::Error::RowNotFound) => Err(Error::JournalNotFound(id)),
+        result => Ok(result?),
+    }
+}
+
+// the tags of a posted journal, replaced as a whole
+async fn set_tags(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<TagsInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match set_db_tags(&state.db, id, &input).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e @ Error::JournalNotFound(_)) => (StatusCode::NOT_FOUND, Json(e.into_api_response())),
+        Err(e @ Error::DataBaseError(_))
+            => (StatusCode::INTERNAL_SERVER_ERROR, Json(e.into_api_response())),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(e.into_api_response())),
+    }
+}
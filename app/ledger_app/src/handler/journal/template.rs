@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+        MethodRouter,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    AmountSide,
+    AmountSource,
+    Db,
+    JournalTemplate as DbJournalTemplate,
+    JournalTemplateLine,
+    TaxCode,
+};
+
+use crate::{
+    auth::CurrentUser,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::review::check_closable;
+use crate::handler::summary::stage_from_str;
+
+use super::{
+    AccountAmount,
+    Journal,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_template).post(upsert_template))
+    .route(
+        "/{name}",
+        get(show_one).post(insert_by_template).delete(delete_template),
+    )
+    .route("/{name}/preview", post(preview))
+}
+
+// the shortcuts of /journal there were before templates, each posted
+// through the template seeded for it
+const SHORTCUTS: [(&str, &str); 21] = [
+    ("/from_prev/debit", "from_prev_debit"),
+    ("/from_prev/credit", "from_prev_credit"),
+    ("/buy/by_owner", "buy_by_owner"),
+    ("/buy/by_bank", "buy_by_bank"),
+    ("/buy/by_kaikakekin", "buy_by_kaikakekin"),
+    ("/buy/by_maebaraikin", "buy_by_maebaraikin"),
+    ("/sell/by_bank", "sell_by_bank"),
+    ("/sell/by_urikakekin", "sell_by_urikakekin"),
+    ("/sell/by_maeukekin", "sell_by_maeukekin"),
+    ("/bank/to_owner", "bank_to_owner"),
+    ("/bank/from_owner", "bank_from_owner"),
+    ("/kessan/to_misyuukin", "kessan_to_misyuukin"),
+    ("/kessan/to_mibaraikin", "kessan_to_mibaraikin"),
+    ("/kessan/sousai_syouhizei", "kessan_sousai_syouhizei"),
+    ("/kessan/sousai_owner", "kessan_sousai_owner"),
+    ("/soneki/income", "soneki_income"),
+    ("/soneki/expense", "soneki_expense"),
+    ("/to_next/to_shihonkin_plus", "to_next_to_shihonkin_plus"),
+    ("/to_next/to_shihonkin_minus", "to_next_to_shihonkin_minus"),
+    ("/to_next/debit", "to_next_debit"),
+    ("/to_next/credit", "to_next_credit"),
+];
+
+pub(crate) fn build_shortcut_router() -> Router<Arc<AppState>> {
+    SHORTCUTS.iter().fold(Router::new(), |router, (path, name)| {
+        router.route(path, shortcut(name))
+    })
+}
+
+fn shortcut(name: &'static str) -> MethodRouter<Arc<AppState>> {
+    post(move |state: State<Arc<AppState>>,
+               user: Extension<CurrentUser>,
+               input: Json<TemplateInput>| {
+        insert_by_template(Path(name.to_string()), state, user, input)
+    })
+}
+
+fn default_transaction_type() -> String { "InTerm".to_string() }
+fn default_amount() -> String { "Total".to_string() }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TemplateLine {
+    side: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    param: Option<String>,
+    #[serde(default = "default_amount")]
+    amount: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Template {
+    name: String,
+    #[serde(default = "default_transaction_type")]
+    transaction_type: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tax_code: Option<String>,
+    lines: Vec<TemplateLine>,
+}
+
+impl Template {
+
+    // a value that is none of the names is refused rather than taken
+    // as the default, a typo would post every journal wrong
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_template(&self) -> Result<DbJournalTemplate, Error> {
+        let invalid = |what: &str, value: &str| Error::TemplateError(
+            format!("'{}' is not {}", value, what)
+        );
+        let transaction_type = stage_from_str(&self.transaction_type)
+            .ok_or(invalid("a transaction type", &self.transaction_type))?;
+        let tax_code = match &self.tax_code {
+            Some(t) => Some(TaxCode::parse(t).ok_or(invalid("a tax code", t))?),
+            None => None,
+        };
+        let mut lines = Vec::new();
+        for l in &self.lines {
+            lines.push(JournalTemplateLine {
+                side: AmountSide::parse(&l.side)
+                    .ok_or(invalid("a side", &l.side))?,
+                account_name: l.account.clone(),
+                account_param: l.param.clone(),
+                amount_source: AmountSource::parse(&l.amount)
+                    .ok_or(invalid("an amount", &l.amount))?,
+            });
+        }
+        Ok(DbJournalTemplate {
+            template_id: 0,
+            template_name: self.name.clone(),
+            transaction_type,
+            description: self.desc.clone(),
+            tax_code,
+            lines,
+        })
+    }
+
+    fn from_db_template(template: &DbJournalTemplate) -> Self {
+        Template {
+            name: template.template_name.clone(),
+            transaction_type: template.transaction_type.into_japanese(),
+            desc: template.description.clone(),
+            tax_code: template.tax_code.as_ref().map(|t| t.into_japanese()),
+            lines: template.lines.iter().map(|l| TemplateLine {
+                side: l.side.into_japanese(),
+                account: l.account_name.clone(),
+                param: l.account_param.clone(),
+                amount: l.amount_source.into_japanese(),
+            }).collect(),
+        }
+    }
+
+    fn validate(&self) -> Result<DbJournalTemplate, Error> {
+        for line in &self.lines {
+            if line.account.is_some() == line.param.is_some() {
+                return Err(Error::TemplateError(
+                    "a line takes either an account or a param".to_string()
+                ));
+            }
+        }
+        let template = self.into_db_template()?;
+        let sides = template.lines.iter()
+            .map(|l| l.side)
+            .collect::<Vec<AmountSide>>();
+        if !sides.contains(&AmountSide::Debit)
+            || !sides.contains(&AmountSide::Credit) {
+            return Err(Error::TemplateError(
+                "both debit and credit lines are required".to_string()
+            ));
+        }
+        Ok(template)
+    }
+
+}
+
+// The amounts of a template. account is the account of the param
+// "account", accounts those of the others. The tax is taken off the
+// total by the tax code of the template when it is not given.
+#[derive(Debug, Deserialize)]
+pub(crate) struct TemplateInput {
+    date: NaiveDate,
+    account: Option<String>,
+    #[serde(default)]
+    accounts: HashMap<String, String>,
+    total: f32,
+    tax: Option<f32>,
+    #[serde(default)]
+    desc: String,
+    partner: Option<String>,
+}
+
+impl TemplateInput {
+
+    fn account_of(&self, param: &str) -> Option<String> {
+        match (param, &self.account) {
+            ("account", Some(account)) => Some(account.clone()),
+            _ => self.accounts.get(param).cloned(),
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_journal(
+        &self,
+        template: &DbJournalTemplate,
+    ) -> Result<Journal, Error> {
+        let tax = self.tax
+            .or(template.tax_code.as_ref().map(|t| t.tax_of(self.total)))
+            .unwrap_or(0_f32);
+        let mut debit = Vec::new();
+        let mut credit = Vec::new();
+        for line in &template.lines {
+            let amount = match line.amount_source {
+                AmountSource::Total => self.total,
+                AmountSource::TotalExTax => self.total - tax,
+                AmountSource::Tax => tax,
+            };
+            // the tax line is left out of journals without tax
+            if line.amount_source == AmountSource::Tax && amount == 0_f32 {
+                continue;
+            }
+            let account = match (&line.account_name, &line.account_param) {
+                (Some(account), _) => account.clone(),
+                (None, Some(param)) => self.account_of(param)
+                    .ok_or(Error::TemplateError(
+                        format!("no account given for '{}'", param)
+                    ))?,
+                (None, None) => return Err(Error::TemplateError(
+                    "a line without an account".to_string()
+                )),
+            };
+            let aa = AccountAmount { account, amount, foreign: None };
+            match line.side {
+                AmountSide::Debit => debit.push(aa),
+                AmountSide::Credit => credit.push(aa),
+            }
+        }
+        let debit_total = debit.iter().map(|d| d.amount).sum::<f32>();
+        let credit_total = credit.iter().map(|c| c.amount).sum::<f32>();
+        if debit_total != credit_total {
+            return Err(Error::TemplateError(format!(
+                "debit {} and credit {} do not balance",
+                debit_total, credit_total,
+            )));
+        }
+        Ok(Journal {
+            transaction_type: template.transaction_type.to_string(),
+            date: self.date,
+            debit,
+            credit,
+            desc: if self.desc.is_empty() {
+                template.description.clone()
+            } else {
+                self.desc.clone()
+            },
+            partner: self.partner.clone(),
+            tags: Vec::new(),
+            created_by: None,
+            id: None,
+            review: None,
+        })
+    }
+
+}
+
+type TemplateOutput = ApiResponse<Vec<Template>>;
+type JournalOutput = ApiResponse<Vec<Journal>>;
+type JournalIdOutput = ApiResponse<Vec<i32>>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::TemplateNotFound(_) => StatusCode::NOT_FOUND,
+        Error::Forbidden(_) => StatusCode::FORBIDDEN,
+        Error::ReviewOpen(..) => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn template_by_name(
+    db: &Db,
+    name: &str,
+) -> Result<DbJournalTemplate, Error> {
+    match DbJournalTemplate::by_name(db, name).await {
+        Ok(t) => Ok(t),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::TemplateNotFound(name.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn render(
+    db: &Db,
+    name: &str,
+    input: &TemplateInput,
+) -> Result<Journal, Error> {
+    input.into_journal(&template_by_name(db, name).await?)
+}
+
+async fn show_template(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<TemplateOutput>) {
+    let db_template = match DbJournalTemplate::all(&state.db).await {
+        Ok(t) => t,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let template = db_template.iter()
+        .map(Template::from_db_template)
+        .collect::<Vec<Template>>();
+    (StatusCode::OK, Json(TemplateOutput::ok(template)))
+}
+
+async fn show_one(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<TemplateOutput>) {
+    match template_by_name(&state.db, &name).await {
+        Ok(t) => (
+            StatusCode::OK,
+            Json(TemplateOutput::ok(vec![Template::from_db_template(&t)])),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn upsert_db_template(
+    db: &Db,
+    input: &Template,
+) -> Result<i32, Error> {
+    match input.validate()?.upsert(db).await {
+        Ok(id) => Ok(id),
+        Err(ledger_db::Error::AccountNotFound) => Err(Error::TemplateError(
+            "an account of the lines is not found".to_string()
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn upsert_template(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<Template>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match upsert_db_template(&state.db, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn delete_template(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbJournalTemplate::delete(&state.db, &name).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::TemplateNotFound(name).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+// the journal the template would post, without posting it
+async fn preview(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<TemplateInput>,
+) -> (StatusCode, Json<JournalOutput>) {
+    match render(&state.db, &name, &input).await {
+        Ok(journal) => (StatusCode::OK, Json(JournalOutput::ok(vec![journal]))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn insert_db_journal(
+    state: &AppState,
+    user: &CurrentUser,
+    name: &str,
+    input: &TemplateInput,
+) -> Result<i32, Error> {
+    let transaction = render(&state.db, name, input).await?
+        .into_transaction(&state.db).await?;
+    let transaction = user.stamp(transaction)?;
+    check_closable(state, &transaction).await?;
+    Ok(transaction.insert(&state.db).await?)
+}
+
+async fn insert_by_template(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<TemplateInput>,
+) -> (StatusCode, Json<JournalIdOutput>) {
+    match insert_db_journal(&state, &user, &name, &input).await {
+        Ok(id) => (StatusCode::CREATED, Json(JournalIdOutput::ok(vec![id]))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::get,
+    Json,
+    Router,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    BankAccount,
+    DepositKind,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use super::payment::transfer_file::validate;
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_partner).post(insert_partner))
+}
+
+fn default_fee_bearer() -> String { "当方負担".to_string() }
+
+// bank details are given all together or not at all
+#[derive(Debug, Serialize, Deserialize)]
+struct Partner {
+    partner_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bank_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bank_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    branch_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    branch_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deposit_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account_holder: Option<String>,
+    #[serde(default = "default_fee_bearer")]
+    fee_bearer: String,
+}
+
+impl Partner {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_partner(&self) -> ledger_db::Partner {
+        let bank_account = match (
+            &self.bank_code, &self.branch_code, &self.account_number,
+        ) {
+            (Some(bank_code), Some(branch_code), Some(account_number))
+                => Some(BankAccount {
+                bank_code: bank_code.clone(),
+                bank_name: self.bank_name.clone().unwrap_or_default(),
+                branch_code: branch_code.clone(),
+                branch_name: self.branch_name.clone().unwrap_or_default(),
+                deposit_kind: self.deposit_kind.as_ref()
+                    .map(DepositKind::from)
+                    .unwrap_or(DepositKind::Ordinary),
+                account_number: account_number.clone(),
+                account_holder: self.account_holder.clone()
+                    .unwrap_or_default(),
+            }),
+            _ => None,
+        };
+        ledger_db::Partner {
+            partner_id: 0,
+            partner_name: self.partner_name.clone(),
+            bank_account,
+            fee_bearer: (&self.fee_bearer).into(),
+        }
+    }
+
+    fn from_db_partner(partner: &ledger_db::Partner) -> Self {
+        let bank = partner.bank_account.as_ref();
+        Partner {
+            partner_name: partner.partner_name.clone(),
+            bank_code: bank.map(|b| b.bank_code.clone()),
+            bank_name: bank.map(|b| b.bank_name.clone()),
+            branch_code: bank.map(|b| b.branch_code.clone()),
+            branch_name: bank.map(|b| b.branch_name.clone()),
+            deposit_kind: bank.map(|b| b.deposit_kind.into_japanese()),
+            account_number: bank.map(|b| b.account_number.clone()),
+            account_holder: bank.map(|b| b.account_holder.clone()),
+            fee_bearer: partner.fee_bearer.into_japanese(),
+        }
+    }
+
+}
+
+type PartnerInput = Partner;
+type PartnerOutput = ApiResponse<Vec<Partner>>;
+
+async fn show_partner(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<PartnerOutput>) {
+    let db_partner = match ledger_db::Partner::all(&state.db).await {
+        Ok(p) => p,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let partner = db_partner.iter()
+        .map(Partner::from_db_partner)
+        .collect::<Vec<Partner>>();
+    (StatusCode::OK, Json(PartnerOutput::ok(partner)))
+}
+
+async fn upsert_db_partner(
+    db: &ledger_db::Db,
+    input: &PartnerInput,
+) -> Result<i32, Error> {
+    let partner = input.into_db_partner();
+    if let Some(bank) = &partner.bank_account {
+        validate(bank)?;
+    }
+    Ok(partner.upsert(db).await?)
+}
+
+async fn insert_partner(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<PartnerInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match upsert_db_partner(&state.db, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,574 @@
+pub mod transfer_file;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::{
+        header,
+        StatusCode,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::get,
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    BankAccount,
+    Db,
+    FeeBearer,
+    OpenPayable,
+    Partner,
+    Payment as DbPayment,
+    PaymentItem as DbPaymentItem,
+    PaymentStatus,
+    TransferSource as DbTransferSource,
+};
+
+use crate::{
+    auth::CurrentUser,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use transfer_file::{
+    transfer_file,
+    validate,
+    Transfer,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_payment).post(insert_payment))
+    .route("/source", get(show_source).post(insert_source))
+    .route("/open", get(show_open))
+    .route("/{id}", get(show_one).delete(cancel))
+    .route("/{id}/file", get(download_file))
+    .route("/{id}/execute", axum::routing::post(execute))
+}
+
+// payables are the credits of 買掛金 and of 未払金 with its sub-accounts
+const PAYABLE_ACCOUNTS: [&str; 2] = ["買掛金", "未払金%"];
+
+fn default_source() -> String { "普通預金".to_string() }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TransferSource {
+    #[serde(default = "default_source")]
+    account: String,
+    requester_code: String,
+    requester_name: String,
+    bank_code: String,
+    bank_name: String,
+    branch_code: String,
+    branch_name: String,
+    deposit_kind: String,
+    account_number: String,
+}
+
+impl TransferSource {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_source(&self) -> DbTransferSource {
+        DbTransferSource {
+            source_id: 0,
+            account_name: self.account.clone(),
+            requester_code: self.requester_code.clone(),
+            bank_account: BankAccount {
+                bank_code: self.bank_code.clone(),
+                bank_name: self.bank_name.clone(),
+                branch_code: self.branch_code.clone(),
+                branch_name: self.branch_name.clone(),
+                deposit_kind: (&self.deposit_kind).into(),
+                account_number: self.account_number.clone(),
+                account_holder: self.requester_name.clone(),
+            },
+        }
+    }
+
+    fn from_db_source(source: &DbTransferSource) -> Self {
+        let bank = &source.bank_account;
+        TransferSource {
+            account: source.account_name.clone(),
+            requester_code: source.requester_code.clone(),
+            requester_name: bank.account_holder.clone(),
+            bank_code: bank.bank_code.clone(),
+            bank_name: bank.bank_name.clone(),
+            branch_code: bank.branch_code.clone(),
+            branch_name: bank.branch_name.clone(),
+            deposit_kind: bank.deposit_kind.into_japanese(),
+            account_number: bank.account_number.clone(),
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct Payable {
+    detail_id: i32,
+    transaction_id: i32,
+    date: NaiveDate,
+    description: String,
+    partner: String,
+    account: String,
+    amount: f32,
+}
+
+impl Payable {
+
+    fn from_db_payable(payable: &OpenPayable) -> Self {
+        Payable {
+            detail_id: payable.transaction_detail_id,
+            transaction_id: payable.transaction_id,
+            date: payable.transaction_date,
+            description: payable.description.clone(),
+            partner: payable.partner_name.clone(),
+            account: payable.account_name.clone(),
+            amount: payable.amount,
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize)]
+struct PaymentItem {
+    detail_id: i32,
+    partner: String,
+    account: String,
+    amount: f32,
+    transaction_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+struct Payment {
+    id: i32,
+    source: String,
+    date: NaiveDate,
+    fee: f32,
+    status: String,
+    items: Vec<PaymentItem>,
+}
+
+impl Payment {
+
+    fn from_db_payment(payment: &DbPayment) -> Self {
+        Payment {
+            id: payment.payment_id,
+            source: payment.source_account_name.clone(),
+            date: payment.transfer_date,
+            fee: payment.fee_amount,
+            status: payment.payment_status.into_japanese(),
+            items: payment.items.iter().map(|i| PaymentItem {
+                detail_id: i.transaction_detail_id,
+                partner: i.partner_name.clone(),
+                account: i.account_name.clone(),
+                amount: i.amount,
+                transaction_id: i.transaction_id,
+            }).collect(),
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct PaymentInput {
+    #[serde(default = "default_source")]
+    source: String,
+    date: NaiveDate,
+    #[serde(default)]
+    fee: f32,
+    items: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenQuery {
+    account: Option<String>,
+}
+
+type TransferSourceInput = TransferSource;
+type TransferSourceOutput = ApiResponse<Vec<TransferSource>>;
+type PayableOutput = ApiResponse<Vec<Payable>>;
+type PaymentOutput = ApiResponse<Vec<Payment>>;
+type PaymentIdOutput = ApiResponse<Vec<i32>>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::PaymentNotFound(_)
+            | Error::TransferSourceNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn open_payables(
+    db: &Db,
+    account: Option<&str>,
+) -> Result<Vec<OpenPayable>, Error> {
+    let patterns = match account {
+        Some(a) => vec![a.to_string()],
+        None => PAYABLE_ACCOUNTS.iter().map(|p| p.to_string()).collect(),
+    };
+    match OpenPayable::by_accounts(db, &patterns).await {
+        Ok(p) => Ok(p),
+        Err(ledger_db::Error::RowNotFound) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn source_by_account(
+    db: &Db,
+    account_name: &str,
+) -> Result<DbTransferSource, Error> {
+    match DbTransferSource::by_account(db, account_name).await {
+        Ok(s) => Ok(s),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::TransferSourceNotFound(account_name.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn payment_by_id(
+    db: &Db,
+    payment_id: i32,
+) -> Result<DbPayment, Error> {
+    match DbPayment::by_id(db, payment_id).await {
+        Ok(p) => Ok(p),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::PaymentNotFound(payment_id)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// the items of a payment by partner, in the order of partner names
+fn by_partner(items: &[DbPaymentItem]) -> BTreeMap<&str, Vec<&DbPaymentItem>> {
+    let mut partners = BTreeMap::<&str, Vec<&DbPaymentItem>>::new();
+    for item in items {
+        partners.entry(&item.partner_name).or_default().push(item);
+    }
+    partners
+}
+
+async fn show_source(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<TransferSourceOutput>) {
+    let db_source = match DbTransferSource::all(&state.db).await {
+        Ok(s) => s,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let source = db_source.iter()
+        .map(TransferSource::from_db_source)
+        .collect::<Vec<TransferSource>>();
+    (StatusCode::OK, Json(TransferSourceOutput::ok(source)))
+}
+
+async fn insert_db_source(
+    db: &Db,
+    input: &TransferSourceInput,
+) -> Result<i32, Error> {
+    let source = input.into_db_source();
+    validate(&source.bank_account)?;
+    match source.upsert(db).await {
+        Ok(id) => Ok(id),
+        Err(ledger_db::Error::AccountNotFound)
+            => Err(Error::AccountNotFound(input.account.clone())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn insert_source(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<TransferSourceInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match insert_db_source(&state.db, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn show_open(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<OpenQuery>,
+) -> (StatusCode, Json<PayableOutput>) {
+    match open_payables(&state.db, query.account.as_deref()).await {
+        Ok(p) => (
+            StatusCode::OK,
+            Json(PayableOutput::ok(
+                p.iter().map(Payable::from_db_payable).collect()
+            )),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn show_payment(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<PaymentOutput>) {
+    let db_payment = match DbPayment::all(&state.db).await {
+        Ok(p) => p,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let payment = db_payment.iter()
+        .map(Payment::from_db_payment)
+        .collect::<Vec<Payment>>();
+    (StatusCode::OK, Json(PaymentOutput::ok(payment)))
+}
+
+async fn show_one(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<PaymentOutput>) {
+    match payment_by_id(&state.db, id).await {
+        Ok(p) => (
+            StatusCode::OK,
+            Json(PaymentOutput::ok(vec![Payment::from_db_payment(&p)])),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// every item has to be an open payable of a partner with bank details
+async fn insert_db_payment(
+    db: &Db,
+    input: &PaymentInput,
+) -> Result<i32, Error> {
+    source_by_account(db, &input.source).await?;
+    let open = open_payables(db, None).await?;
+    let mut items = Vec::new();
+    for detail_id in &input.items {
+        let payable = open.iter()
+            .find(|p| p.transaction_detail_id == *detail_id)
+            .ok_or(Error::PayableNotOpen(*detail_id))?;
+        items.push(DbPaymentItem {
+            payment_item_id: 0,
+            transaction_detail_id: payable.transaction_detail_id,
+            partner_name: payable.partner_name.clone(),
+            account_name: payable.account_name.clone(),
+            amount: payable.amount,
+            transaction_id: None,
+        });
+    }
+    for partner_name in by_partner(&items).keys() {
+        let partner = Partner::by_name(db, partner_name).await?;
+        match &partner.bank_account {
+            Some(bank) => validate(bank)?,
+            None => return Err(
+                Error::PartnerBankMissing(partner_name.to_string())
+            ),
+        }
+    }
+    let payment = DbPayment {
+        payment_id: 0,
+        source_account_name: input.source.clone(),
+        transfer_date: input.date,
+        fee_amount: input.fee,
+        payment_status: PaymentStatus::Pending,
+        items,
+    };
+    match payment.insert(db).await {
+        Ok(id) => Ok(id),
+        Err(ledger_db::Error::PayableTaken(id))
+            => Err(Error::PayableNotOpen(id)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn insert_payment(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<PaymentInput>,
+) -> (StatusCode, Json<PaymentIdOutput>) {
+    match insert_db_payment(&state.db, &input).await {
+        Ok(id) => (StatusCode::CREATED, Json(PaymentIdOutput::ok(vec![id]))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// A transfer per partner. When the partner bears the fee it is
+// deducted from the amount transferred.
+async fn transfers_of(
+    db: &Db,
+    payment: &DbPayment,
+) -> Result<Vec<(Partner, f32)>, Error> {
+    let mut transfers = Vec::new();
+    for (partner_name, items) in by_partner(&payment.items) {
+        let partner = Partner::by_name(db, partner_name).await?;
+        let total = items.iter().map(|i| i.amount).sum::<f32>();
+        transfers.push((partner, total));
+    }
+    Ok(transfers)
+}
+
+async fn payment_file(
+    db: &Db,
+    payment_id: i32,
+) -> Result<Vec<u8>, Error> {
+    let payment = payment_by_id(db, payment_id).await?;
+    if payment.payment_status != PaymentStatus::Pending {
+        return Err(Error::PaymentNotPending(payment_id));
+    }
+    let source = source_by_account(db, &payment.source_account_name).await?;
+    let mut transfers = Vec::new();
+    for (partner, total) in transfers_of(db, &payment).await? {
+        let bank_account = partner.bank_account.clone()
+            .ok_or(Error::PartnerBankMissing(partner.partner_name.clone()))?;
+        let amount = match partner.fee_bearer {
+            FeeBearer::Payer => total,
+            FeeBearer::Payee => total - payment.fee_amount,
+        };
+        transfers.push(Transfer { bank_account, amount });
+    }
+    transfer_file(&source, payment.transfer_date, &transfers)
+}
+
+async fn download_file(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    match payment_file(&state.db, id).await {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/plain; charset=Shift_JIS".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"furikomi_{}.txt\"", id),
+                ),
+            ],
+            bytes,
+        ).into_response(),
+        Err(e) => (
+            error_status(&e),
+            Json::<ApiResponseWithoutBody>(e.into_api_response()),
+        ).into_response(),
+    }
+}
+
+// The payables are debited and the source account credited with what
+// leaves it: the transfer and, when we bear it, the fee as 支払手数料.
+fn payment_journal(
+    payment: &DbPayment,
+    partner: &Partner,
+    items: &[&DbPaymentItem],
+) -> Journal {
+    let mut payables = BTreeMap::<&str, f32>::new();
+    for item in items {
+        *payables.entry(&item.account_name).or_default() += item.amount;
+    }
+    let mut debit = payables.iter()
+        .map(|(account, amount)| AccountAmount {
+            account: account.to_string(),
+            amount: *amount,
+            foreign: None,
+        })
+        .collect::<Vec<AccountAmount>>();
+    let mut paid = items.iter().map(|i| i.amount).sum::<f32>();
+    if partner.fee_bearer == FeeBearer::Payer && payment.fee_amount > 0_f32 {
+        debit.push(AccountAmount {
+            account: "支払手数料".to_string(),
+            amount: payment.fee_amount,
+            foreign: None,
+        });
+        paid += payment.fee_amount;
+    }
+    Journal {
+        transaction_type: "InTerm".to_string(),
+        date: payment.transfer_date,
+        debit,
+        credit: vec![AccountAmount {
+            account: payment.source_account_name.clone(),
+            amount: paid,
+            foreign: None,
+        }],
+        desc: format!("振込 {}", partner.partner_name),
+        partner: Some(partner.partner_name.clone()),
+        tags: Vec::new(),
+        created_by: None,
+        id: None,
+        review: None,
+    }
+}
+
+// posts the payment journals once the transfers went through
+async fn execute_payment(
+    db: &Db,
+    user: &CurrentUser,
+    payment_id: i32,
+) -> Result<Vec<i32>, Error> {
+    let payment = payment_by_id(db, payment_id).await?;
+    if payment.payment_status != PaymentStatus::Pending {
+        return Err(Error::PaymentNotPending(payment_id));
+    }
+    let mut journals = Vec::new();
+    for (partner_name, items) in by_partner(&payment.items) {
+        let partner = Partner::by_name(db, partner_name).await?;
+        let transaction = payment_journal(&payment, &partner, &items)
+            .into_transaction(db).await?;
+        journals.push((partner_name.to_string(), user.stamp(transaction)?));
+    }
+    match DbPayment::execute(db, payment_id, &journals).await {
+        Ok(posted) => Ok(posted),
+        // executed or cancelled by another request since it was read
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::PaymentNotPending(payment_id)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn execute(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<PaymentIdOutput>) {
+    match execute_payment(&state.db, &user, id).await {
+        Ok(ids) => (StatusCode::CREATED, Json(PaymentIdOutput::ok(ids))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn cancel(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbPayment::change_status(
+        &state.db, id, &PaymentStatus::Pending, &PaymentStatus::Cancelled,
+    ).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::PaymentNotPending(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::NaiveDate;
+
+use ledger_db::{
+    BankAccount,
+    TransferSource,
+};
+
+use crate::{
+    text_codec,
+    Error,
+};
+
+const RECORD_LENGTH: usize = 120;
+
+// one data record: the account paid into and the amount transferred
+#[derive(Debug)]
+pub(crate) struct Transfer {
+    pub bank_account: BankAccount,
+    pub amount: f32,
+}
+
+// Text fields are half-width (one byte in Shift_JIS), left aligned and
+// padded with blanks. Latin letters are written in upper case.
+fn text(value: &str, length: usize, name: &str) -> Result<Vec<u8>, Error> {
+    let upper = value.to_ascii_uppercase();
+    let mut bytes = text_codec::encode(&upper, "Shift_JIS")?;
+    if bytes.len() != upper.chars().count() {
+        return Err(Error::BankAccountError(
+            format!("{} '{}' must be half-width", name, value)
+        ));
+    }
+    if bytes.len() > length {
+        return Err(Error::BankAccountError(
+            format!("{} '{}' is longer than {} bytes", name, value, length)
+        ));
+    }
+    bytes.resize(length, b' ');
+    Ok(bytes)
+}
+
+// numeric fields are right aligned and padded with zeros
+fn number(value: &str, length: usize, name: &str) -> Result<Vec<u8>, Error> {
+    if value.is_empty() || value.len() > length
+        || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::BankAccountError(
+            format!("{} '{}' is not a number of {} digits", name, value, length)
+        ));
+    }
+    Ok(format!("{:0>width$}", value, width = length).into_bytes())
+}
+
+fn amount(value: f32, length: usize) -> Result<Vec<u8>, Error> {
+    if value <= 0_f32 || value.fract() != 0_f32 {
+        return Err(Error::BankAccountError(
+            format!("{} can not be transferred", value)
+        ));
+    }
+    number(&format!("{}", value as u64), length, "amount")
+}
+
+pub(crate) fn validate(bank_account: &BankAccount) -> Result<(), Error> {
+    number(&bank_account.bank_code, 4, "bank code")?;
+    text(&bank_account.bank_name, 15, "bank name")?;
+    number(&bank_account.branch_code, 3, "branch code")?;
+    text(&bank_account.branch_name, 15, "branch name")?;
+    number(&bank_account.account_number, 7, "account number")?;
+    text(&bank_account.account_holder, 30, "account holder")?;
+    Ok(())
+}
+
+fn record(fields: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut record = fields.concat();
+    record.resize(RECORD_LENGTH, b' ');
+    record.extend_from_slice(b"\r\n");
+    record
+}
+
+// 総合振込: a header (1), a data record (2) per transfer, a trailer (8)
+// and an end record (9) of 120 bytes each, Shift_JIS with CR LF.
+pub(crate) fn transfer_file(
+    source: &TransferSource,
+    transfer_date: NaiveDate,
+    transfers: &[Transfer],
+) -> Result<Vec<u8>, Error> {
+    let bank = &source.bank_account;
+    let mut file = record(vec![
+        b"1".to_vec(),
+        b"21".to_vec(),  // 種別コード: 総合振込
+        b"0".to_vec(),  // コード区分: JIS
+        number(&source.requester_code, 10, "requester code")?,
+        text(&bank.account_holder, 40, "requester name")?,
+        transfer_date.format("%m%d").to_string().into_bytes(),
+        number(&bank.bank_code, 4, "bank code")?,
+        text(&bank.bank_name, 15, "bank name")?,
+        number(&bank.branch_code, 3, "branch code")?,
+        text(&bank.branch_name, 15, "branch name")?,
+        bank.deposit_kind.code().as_bytes().to_vec(),
+        number(&bank.account_number, 7, "account number")?,
+    ]);
+
+    let mut total = 0_u64;
+    for t in transfers {
+        let payee = &t.bank_account;
+        file.extend(record(vec![
+            b"2".to_vec(),
+            number(&payee.bank_code, 4, "bank code")?,
+            text(&payee.bank_name, 15, "bank name")?,
+            number(&payee.branch_code, 3, "branch code")?,
+            text(&payee.branch_name, 15, "branch name")?,
+            b"    ".to_vec(),  // 手形交換所番号
+            payee.deposit_kind.code().as_bytes().to_vec(),
+            number(&payee.account_number, 7, "account number")?,
+            text(&payee.account_holder, 30, "account holder")?,
+            amount(t.amount, 10)?,
+            b"0".to_vec(),  // 新規コード
+            b"0000000000".to_vec(),  // 顧客コード1
+            b"0000000000".to_vec(),  // 顧客コード2
+            b"7".to_vec(),  // 振込指定区分: 電信
+        ]));
+        total += t.amount as u64;
+    }
+
+    file.extend(record(vec![
+        b"8".to_vec(),
+        number(&transfers.len().to_string(), 6, "count")?,
+        number(&total.to_string(), 12, "total")?,
+    ]));
+    file.extend(record(vec![b"9".to_vec()]));
+    Ok(file)
+}
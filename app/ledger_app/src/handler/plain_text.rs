@@ -0,0 +1,429 @@
+pub mod beancount;
+pub mod ledger;
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::{
+        header,
+        StatusCode,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use ledger_db::{
+    Account,
+    AccountType,
+    Db,
+    Transaction,
+};
+
+use crate::{
+    auth::CurrentUser,
+    text_codec,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::import::parse_amount;
+use crate::handler::interop::{
+    check_balance,
+    post_journals,
+    Preview,
+};
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+// journals of ledger-cli, hledger and beancount
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/export/{format}", get(export))
+    .route("/import/{format}", post(import))
+    .route("/import/{format}/preview", post(preview))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+pub(crate) fn format_of(name: &str) -> Result<Format, Error> {
+    match name.to_lowercase().as_str() {
+        "ledger" => Ok(Format::Ledger),
+        "hledger" => Ok(Format::Hledger),
+        "beancount" => Ok(Format::Beancount),
+        _ => Err(Error::PlainTextFormatNotFound(name.to_string())),
+    }
+}
+
+const COMMODITY: &str = "JPY";
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+type PreviewOutput = ApiResponse<Preview>;
+type ImportOutput = ApiResponse<Vec<i32>>;
+
+// Assets, Expenses and so on. The accounts of closing have no root of
+// their own in these tools and go under Equity.
+fn account_root(account_type: &AccountType) -> &'static str {
+    match account_type {
+        AccountType::Asset => "Assets",
+        AccountType::Liability => "Liabilities",
+        AccountType::Equity => "Equity",
+        AccountType::Income => "Income",
+        AccountType::Expense => "Expenses",
+        AccountType::UtilDebit | AccountType::UtilCredit => "Equity:作業用",
+    }
+}
+
+// Assets:普通預金, Expenses:通信費 and so on
+fn account_path(account_name: &str, account_type: &AccountType) -> String {
+    format!("{}:{}", account_root(account_type), account_name.replace(' ', "-"))
+}
+
+// our account of an account path, the last of its components
+fn account_name_of(path: &str) -> String {
+    path.rsplit(':').next().unwrap_or(path).to_string()
+}
+
+// the debits positive and the credits negative
+fn posting_amount(debit_amount: f32, credit_amount: f32) -> String {
+    format!("{} {}", debit_amount - credit_amount, COMMODITY)
+}
+
+// a transaction of a file, before it is made into a journal
+#[derive(Debug, Default)]
+struct Entry {
+    date: NaiveDate,
+    transaction_type: Option<String>,
+    partner: Option<String>,
+    desc: String,
+    // our accounts and amounts, one of which may be left out
+    postings: Vec<(String, Option<f32>)>,
+}
+
+impl Entry {
+
+    fn into_journal(self, line: usize) -> Result<Journal, Error> {
+        let given = self.postings.iter()
+            .filter_map(|(_, amount)| *amount)
+            .sum::<f32>();
+        let elided = self.postings.iter()
+            .filter(|(_, amount)| amount.is_none())
+            .count();
+        if elided > 1 {
+            return Err(Error::FormatError(format!(
+                "line {}: more than one posting without an amount", line,
+            )));
+        }
+        let mut debit = Vec::new();
+        let mut credit = Vec::new();
+        for (account, amount) in &self.postings {
+            let amount = amount.unwrap_or(-given);
+            let line = AccountAmount {
+                account: account.clone(),
+                amount: amount.abs(),
+                foreign: None,
+            };
+            if amount < 0_f32 {
+                credit.push(line);
+            } else {
+                debit.push(line);
+            }
+        }
+        Ok(Journal {
+            transaction_type: self.transaction_type
+                .unwrap_or("InTerm".to_string()),
+            date: self.date,
+            debit,
+            credit,
+            desc: self.desc,
+            partner: self.partner,
+            tags: Vec::new(),
+            created_by: None,
+            id: None,
+            review: None,
+        })
+    }
+
+}
+
+// the amount of a posting: "1,100 JPY", "JPY -1100", "¥1100".
+// a cost or price after it is not kept.
+fn parse_posting_amount(text: &str, line: usize) -> Result<f32, Error> {
+    let amount = text.split(['@', '{']).next().unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_ascii_alphabetic() && *c != '"')
+        .collect::<String>();
+    parse_amount(&amount).ok_or(Error::FormatError(
+        format!("line {}: '{}' is not an amount", line, text.trim())
+    ))
+}
+
+pub(crate) async fn export_file(
+    db: &Db,
+    format: Format,
+    query: &ExportQuery,
+) -> Result<String, Error> {
+    let accounts = Account::all(db).await?;
+    let transactions = Transaction::by_period(db, query.from, query.to)
+        .await?;
+    Ok(match format {
+        Format::Ledger | Format::Hledger
+            => ledger::write(format, &accounts, &transactions),
+        Format::Beancount
+            => beancount::write(query.from, &accounts, &transactions),
+    })
+}
+
+async fn export(
+    State(state): State<Arc<AppState>>,
+    Path(format): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    let result = match format_of(&format) {
+        Ok(format) => export_file(&state.db, format, &query).await
+            .map(|text| (format, text)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok((format, text)) => (
+            StatusCode::OK,
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/plain; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"ledger_{}_{}.{}\"",
+                        query.from.format("%Y%m%d"),
+                        query.to.format("%Y%m%d"),
+                        match format {
+                            Format::Ledger => "ledger",
+                            Format::Hledger => "journal",
+                            Format::Beancount => "beancount",
+                        },
+                    ),
+                ),
+            ],
+            text,
+        ).into_response(),
+        Err(e) => (
+            error_status(&e),
+            Json::<ApiResponseWithoutBody>(e.into_api_response()),
+        ).into_response(),
+    }
+}
+
+fn read_file(format: &str, body: &[u8]) -> Result<Vec<Journal>, Error> {
+    let format = format_of(format)?;
+    let text = text_codec::decode(body, "utf-8")?;
+    let entries = match format {
+        Format::Ledger | Format::Hledger => ledger::parse(&text)?,
+        Format::Beancount => beancount::parse(&text)?,
+    };
+    let journals = entries.into_iter()
+        .map(|(line, entry)| entry.into_journal(line))
+        .collect::<Result<Vec<Journal>, Error>>()?;
+    check_balance(&journals)?;
+    Ok(journals)
+}
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::PlainTextFormatNotFound(_) => StatusCode::NOT_FOUND,
+        Error::Forbidden(_) => StatusCode::FORBIDDEN,
+        Error::ReviewOpen(_, _) => StatusCode::CONFLICT,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+pub(crate) async fn import_file(
+    db: &Db,
+    user: &CurrentUser,
+    start_month: u32,
+    format: &str,
+    body: &[u8],
+) -> Result<Vec<i32>, Error> {
+    post_journals(db, user, start_month, read_file(format, body)?).await
+}
+
+async fn import(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Path(format): Path<String>,
+    body: Bytes,
+) -> (StatusCode, Json<ImportOutput>) {
+    let start_month = state.config.fiscal_year.start_month;
+    match import_file(&state.db, &user, start_month, &format, &body).await {
+        Ok(ids) => (
+            StatusCode::CREATED,
+            Json(ImportOutput::ok_with(
+                format!("{} journals imported", ids.len()),
+                ids,
+            )),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+pub(crate) async fn preview_file(
+    db: &Db,
+    format: &str,
+    body: &[u8],
+) -> Result<Preview, Error> {
+    Preview::of(db, read_file(format, body)?).await
+}
+
+// the journals the file would post, without posting them
+async fn preview(
+    State(state): State<Arc<AppState>>,
+    Path(format): Path<String>,
+    body: Bytes,
+) -> (StatusCode, Json<PreviewOutput>) {
+    match preview_file(&state.db, &format, &body).await {
+        Ok(preview) => (StatusCode::OK, Json(PreviewOutput::ok(preview))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_db::{
+        TransactionDetail,
+        TransactionType,
+    };
+
+    use super::*;
+
+    fn detail(
+        account_name: &str,
+        account_type: AccountType,
+        debit_amount: f32,
+        credit_amount: f32,
+    ) -> TransactionDetail {
+        TransactionDetail {
+            account_name: account_name.to_string(),
+            account_type,
+            debit_amount,
+            credit_amount,
+            foreign: None,
+        }
+    }
+
+    // the opening journal, which books the accounts of closing
+    fn from_prev() -> (Vec<Account>, Vec<Transaction>) {
+        let details = vec![
+            detail("普通預金", AccountType::Asset, 100000_f32, 0_f32),
+            detail(
+                "(前期繰越(貸方勘定用))", AccountType::UtilCredit,
+                0_f32, 100000_f32,
+            ),
+            detail(
+                "(前期繰越(借方勘定用))", AccountType::UtilDebit,
+                30000_f32, 0_f32,
+            ),
+            detail("買掛金", AccountType::Liability, 0_f32, 30000_f32),
+        ];
+        let accounts = details.iter().enumerate()
+            .map(|(i, d)| Account {
+                account_id: i as i32 + 1,
+                account_name: d.account_name.clone(),
+                account_type: d.account_type.clone(),
+            })
+            .collect();
+        let transaction = Transaction {
+            transaction_id: 1,
+            transaction_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            transaction_type: TransactionType::FromPrev,
+            description: "前期繰越".to_string(),
+            partner_name: None,
+            created_by: None,
+            tags: Vec::new(),
+            review_status: None,
+            open_comments: 0,
+            details,
+        };
+        (accounts, vec![transaction])
+    }
+
+    fn lines(amounts: &[AccountAmount]) -> Vec<(String, f32)> {
+        amounts.iter().map(|a| (a.account.clone(), a.amount)).collect()
+    }
+
+    fn round_trip(format: Format) {
+        let (accounts, transactions) = from_prev();
+        let text = match format {
+            Format::Ledger | Format::Hledger
+                => ledger::write(format, &accounts, &transactions),
+            Format::Beancount => beancount::write(
+                transactions[0].transaction_date, &accounts, &transactions,
+            ),
+        };
+        let entries = match format {
+            Format::Ledger | Format::Hledger => ledger::parse(&text),
+            Format::Beancount => beancount::parse(&text),
+        }.unwrap();
+        let journals = entries.into_iter()
+            .map(|(line, entry)| entry.into_journal(line))
+            .collect::<Result<Vec<Journal>, Error>>()
+            .unwrap();
+
+        assert_eq!(journals.len(), 1, "{:?}", format);
+        assert_eq!(journals[0].transaction_type, "FromPrev");
+        assert_eq!(lines(&journals[0].debit), vec![
+            ("普通預金".to_string(), 100000_f32),
+            ("(前期繰越(借方勘定用))".to_string(), 30000_f32),
+        ], "{:?}", format);
+        assert_eq!(lines(&journals[0].credit), vec![
+            ("(前期繰越(貸方勘定用))".to_string(), 100000_f32),
+            ("買掛金".to_string(), 30000_f32),
+        ], "{:?}", format);
+    }
+
+    #[test]
+    fn ledger_round_trip_keeps_closing_accounts() {
+        round_trip(Format::Ledger);
+    }
+
+    #[test]
+    fn hledger_round_trip_keeps_closing_accounts() {
+        round_trip(Format::Hledger);
+    }
+
+    #[test]
+    fn beancount_round_trip_keeps_closing_accounts() {
+        round_trip(Format::Beancount);
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::NaiveDate;
+
+use ledger_db::{
+    Account,
+    AccountType,
+    Transaction,
+};
+
+use crate::Error;
+
+use crate::handler::interop::parse_date;
+
+use super::{
+    account_name_of,
+    account_root,
+    parse_posting_amount,
+    posting_amount,
+    Entry,
+    COMMODITY,
+};
+
+// Beancount takes letters, digits and '-' of ASCII in an account, and
+// of them only a capital or a digit first; any other character is
+// taken as it is. The ASCII it does not take is written in full width,
+// (前期繰越) as （前期繰越）, and a full width character of the name
+// that would read back as such ASCII is escaped with ＼.
+const ESCAPE: char = '＼';
+
+fn takes(c: char, first: bool) -> bool {
+    !c.is_ascii() || c.is_ascii_uppercase() || c.is_ascii_digit()
+        || (!first && (c.is_ascii_lowercase() || c == '-'))
+}
+
+fn full_width(c: char) -> Option<char> {
+    match c {
+        ' ' => Some('\u{3000}'),
+        '!'..='~' => char::from_u32(c as u32 - 0x21 + 0xFF01),
+        _ => None,
+    }
+}
+
+fn half_width(c: char) -> Option<char> {
+    match c {
+        '\u{3000}' => Some(' '),
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFF01 + 0x21),
+        _ => None,
+    }
+}
+
+fn account_component(account_name: &str) -> String {
+    let mut component = String::new();
+    for (i, c) in account_name.chars().enumerate() {
+        match full_width(c) {
+            Some(w) if !takes(c, i == 0) => component.push(w),
+            _ => {
+                if half_width(c).is_some_and(|h| !takes(h, i == 0)) {
+                    component.push(ESCAPE);
+                }
+                component.push(c);
+            },
+        }
+    }
+    component
+}
+
+// the account a component was written for, as account_component undone
+fn account_name(component: &str) -> String {
+    let mut account_name = String::new();
+    let mut chars = component.chars();
+    while let Some(c) = chars.next() {
+        if c == ESCAPE {
+            account_name.extend(chars.next());
+            continue;
+        }
+        match half_width(c) {
+            Some(h) if !takes(h, account_name.is_empty())
+                => account_name.push(h),
+            _ => account_name.push(c),
+        }
+    }
+    account_name
+}
+
+fn account_path(account_name: &str, account_type: &AccountType) -> String {
+    format!(
+        "{}:{}", account_root(account_type), account_component(account_name),
+    )
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// The accounts are opened on the first day of the export, the partner
+// is the payee and the transaction type goes in the "type" metadata.
+pub(super) fn write(
+    open_date: NaiveDate,
+    accounts: &[Account],
+    transactions: &[Transaction],
+) -> String {
+    let mut text = format!(
+        "option \"operating_currency\" \"{}\"\n\n", COMMODITY,
+    );
+    for account in accounts {
+        text.push_str(&format!(
+            "{} open {} {}\n",
+            open_date.format("%Y-%m-%d"),
+            account_path(&account.account_name, &account.account_type),
+            COMMODITY,
+        ));
+    }
+    for transaction in transactions {
+        let strings = match &transaction.partner_name {
+            Some(partner) => format!(
+                "{} {}", quoted(partner), quoted(&transaction.description),
+            ),
+            None => quoted(&transaction.description),
+        };
+        text.push_str(&format!(
+            "\n{} * {}\n  type: \"{}\"\n",
+            transaction.transaction_date.format("%Y-%m-%d"),
+            strings,
+            transaction.transaction_type,
+        ));
+        for detail in &transaction.details {
+            text.push_str(&format!(
+                "  {}  {}\n",
+                account_path(&detail.account_name, &detail.account_type),
+                posting_amount(detail.debit_amount, detail.credit_amount),
+            ));
+        }
+    }
+    text
+}
+
+// the strings of a line, with the escapes of beancount undone
+fn strings_of(line: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == ';' { break; }
+        if c != '"' { continue; }
+        let mut string = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => string.extend(chars.next()),
+                _ => string.push(c),
+            }
+        }
+        strings.push(string);
+    }
+    strings
+}
+
+// "2026-04-03 * ..." and "2026-04-03 txn ..." but not the other
+// directives of a date such as open, balance or price
+fn read_title(title: &str) -> Option<Entry> {
+    let mut words = title.split_whitespace();
+    let date = parse_date(words.next()?)?;
+    if !matches!(words.next()?, "*" | "!" | "txn") {
+        return None;
+    }
+    let mut entry = Entry { date, ..Default::default() };
+    let mut strings = strings_of(title);
+    entry.desc = strings.pop().unwrap_or_default();
+    entry.partner = strings.pop().filter(|p| !p.is_empty());
+    Some(entry)
+}
+
+// a metadata key begins with a lower case letter, an account does not
+fn metadata_of(body: &str) -> Option<(&str, &str)> {
+    let (key, value) = body.split_once(':')?;
+    let is_key = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    is_key.then_some((key, value.trim()))
+}
+
+// the transactions of a file with the line each starts on
+pub(super) fn parse(text: &str) -> Result<Vec<(usize, Entry)>, Error> {
+    let mut entries = Vec::new();
+    let mut current: Option<(usize, Entry)> = None;
+    for (n, raw) in text.lines().enumerate() {
+        let line = n + 1;
+        if raw.trim().is_empty() { continue; }
+        if !raw.starts_with([' ', '\t']) {
+            entries.extend(current.take());
+            if raw.starts_with(|c: char| c.is_ascii_digit()) {
+                current = read_title(raw).map(|entry| (line, entry));
+            }
+            continue;
+        }
+        let Some((_, entry)) = current.as_mut() else { continue };
+        let body = raw.trim();
+        if body.starts_with(';') { continue; }
+        if let Some((key, value)) = metadata_of(body) {
+            let value = strings_of(value).pop()
+                .unwrap_or(value.to_string());
+            match key {
+                "type" => entry.transaction_type = Some(value),
+                "partner" => entry.partner = Some(value),
+                _ => (),
+            }
+            continue;
+        }
+        let posting = body.split(';').next().unwrap_or(body)
+            .trim_start_matches(['*', '!'])
+            .trim();
+        let (account, amount) = posting.split_once(char::is_whitespace)
+            .unwrap_or((posting, ""));
+        let amount = match amount.trim() {
+            "" => None,
+            amount => Some(parse_posting_amount(amount, line)?),
+        };
+        entry.postings.push((account_name(&account_name_of(account)), amount));
+    }
+    entries.extend(current.take());
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_components_are_valid_and_read_back() {
+        for name in [
+            "(前期繰越(借方勘定用))",
+            "（株）山田 預け金",
+            "cash",
+            "-1",
+            "a＼b",
+            "ｃ",
+        ] {
+            let component = account_component(name);
+            assert!(
+                component.chars().enumerate().all(|(i, c)| takes(c, i == 0)),
+                "{} as {}", name, component,
+            );
+            assert_eq!(account_name(&component), name);
+        }
+        assert_eq!(
+            account_component("(前期繰越(借方勘定用))"),
+            "（前期繰越（借方勘定用））",
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use ledger_db::{
+    Account,
+    Transaction,
+};
+
+use crate::Error;
+
+use crate::handler::interop::parse_date;
+
+use super::{
+    account_name_of,
+    account_path,
+    parse_posting_amount,
+    posting_amount,
+    Entry,
+    Format,
+    COMMODITY,
+};
+
+// The journal of ledger-cli and hledger. The transaction type goes in a
+// "type" tag, which both tools read as metadata. hledger keeps the
+// partner as the payee of "payee | note", ledger-cli in a tag.
+pub(super) fn write(
+    format: Format,
+    accounts: &[Account],
+    transactions: &[Transaction],
+) -> String {
+    let mut text = format!("commodity {}\n\n", COMMODITY);
+    for account in accounts {
+        text.push_str(&format!(
+            "account {}\n",
+            account_path(&account.account_name, &account.account_type),
+        ));
+    }
+    for transaction in transactions {
+        let title = match (&transaction.partner_name, format) {
+            (Some(partner), Format::Hledger)
+                => format!("{} | {}", partner, transaction.description),
+            _ => transaction.description.clone(),
+        };
+        text.push_str(&format!(
+            "\n{} * {}\n    ; type: {}\n",
+            transaction.transaction_date.format("%Y-%m-%d"),
+            title,
+            transaction.transaction_type,
+        ));
+        if let (Some(partner), Format::Ledger)
+            = (&transaction.partner_name, format)
+        {
+            text.push_str(&format!("    ; partner: {}\n", partner));
+        }
+        for detail in &transaction.details {
+            text.push_str(&format!(
+                "    {}  {}\n",
+                account_path(&detail.account_name, &detail.account_type),
+                posting_amount(detail.debit_amount, detail.credit_amount),
+            ));
+        }
+    }
+    text
+}
+
+// "; type: Kessan" of ledger-cli and "; type:Kessan, partner:..." of
+// hledger. other comments are left as they are.
+fn read_tags(comment: &str, entry: &mut Entry) {
+    for tag in comment.split(',') {
+        let Some((key, value)) = tag.split_once(':') else { continue };
+        let value = value.trim().to_string();
+        match key.trim().to_lowercase().as_str() {
+            "type" => entry.transaction_type = Some(value),
+            "partner" | "payee" => entry.partner = Some(value),
+            _ => (),
+        }
+    }
+}
+
+// the date, the status, the code and the description of a transaction.
+// None for the directives and the automated or periodic transactions.
+fn read_title(title: &str) -> Option<Entry> {
+    let (head, comment) = title.split_once(';').unwrap_or((title, ""));
+    let (date, rest) = head.split_once(char::is_whitespace)
+        .unwrap_or((head, ""));
+    // the secondary date of "2026-04-03=2026-04-05" is not kept
+    let date = parse_date(date.split('=').next().unwrap_or(date))?;
+    let mut rest = rest.trim()
+        .trim_start_matches(['*', '!'])
+        .trim_start();
+    if rest.starts_with('(') {
+        rest = rest.split_once(')').map(|(_, r)| r.trim()).unwrap_or(rest);
+    }
+    let mut entry = Entry { date, ..Default::default() };
+    match rest.split_once('|') {
+        Some((payee, note)) => {
+            entry.partner = Some(payee.trim().to_string());
+            entry.desc = note.trim().to_string();
+        },
+        None => entry.desc = rest.to_string(),
+    }
+    read_tags(comment, &mut entry);
+    Some(entry)
+}
+
+// the account of the virtual postings "(Assets:普通預金)" and
+// "[Assets:普通預金]". only a pair around the whole of it is taken off,
+// the parentheses of an account such as (前期繰越(借方勘定用)) are kept.
+fn unwrap_virtual(account: &str) -> &str {
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        if let Some(inner) = account.strip_prefix(open)
+            .and_then(|a| a.strip_suffix(close)) {
+            return inner;
+        }
+    }
+    account
+}
+
+// the transactions of a journal with the line each starts on
+pub(super) fn parse(text: &str) -> Result<Vec<(usize, Entry)>, Error> {
+    let mut entries = Vec::new();
+    let mut current: Option<(usize, Entry)> = None;
+    for (n, raw) in text.lines().enumerate() {
+        let line = n + 1;
+        if raw.trim().is_empty() { continue; }
+        if !raw.starts_with([' ', '\t']) {
+            entries.extend(current.take());
+            if raw.starts_with(|c: char| c.is_ascii_digit()) {
+                current = read_title(raw).map(|entry| (line, entry));
+            }
+            continue;
+        }
+        let Some((_, entry)) = current.as_mut() else { continue };
+        let body = raw.trim();
+        if let Some(comment) = body.strip_prefix(';') {
+            read_tags(comment, entry);
+            continue;
+        }
+        let (posting, comment) = body.split_once(';').unwrap_or((body, ""));
+        read_tags(comment, entry);
+        let posting = posting.trim_start_matches(['*', '!']).trim_start();
+        let (account, amount) = posting.split_once("  ")
+            .or(posting.split_once('\t'))
+            .unwrap_or((posting, ""));
+        let account = account_name_of(unwrap_virtual(account.trim()));
+        let amount = match amount.trim() {
+            "" => None,
+            amount => Some(parse_posting_amount(amount, line)?),
+        };
+        entry.postings.push((account, amount));
+    }
+    entries.extend(current.take());
+    Ok(entries)
+}
@@ -0,0 +1,484 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Account,
+    AmountSide,
+    BookItem as DbBookItem,
+    Db,
+    LineStatus,
+    ReconcileStatus,
+    Reconciliation as DbReconciliation,
+    StagedLine as DbStagedLine,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_reconciliation).post(insert_reconciliation))
+    .route("/{id}", get(show_report))
+    .route("/{id}/auto", post(auto_match))
+    .route("/{id}/tick", post(tick))
+    .route("/{id}/tick/{detail_id}", delete(untick))
+    .route("/{id}/close", post(close))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reconciliation {
+    #[serde(default)]
+    id: i32,
+    account: String,
+    start: NaiveDate,
+    end: NaiveDate,
+    statement_balance: f32,
+    #[serde(default)]
+    status: String,
+}
+
+impl Reconciliation {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_reconciliation(&self) -> DbReconciliation {
+        DbReconciliation {
+            reconciliation_id: 0,
+            account_name: self.account.clone(),
+            period_start: self.start,
+            period_end: self.end,
+            statement_balance: self.statement_balance,
+            reconcile_status: ReconcileStatus::Open,
+        }
+    }
+
+    fn from_db_reconciliation(rec: &DbReconciliation) -> Self {
+        Reconciliation {
+            id: rec.reconciliation_id,
+            account: rec.account_name.clone(),
+            start: rec.period_start,
+            end: rec.period_end,
+            statement_balance: rec.statement_balance,
+            status: rec.reconcile_status.into_japanese(),
+        }
+    }
+
+}
+
+// amounts are signed by their effect on the balance of the account
+#[derive(Debug, Serialize)]
+struct BookLine {
+    detail_id: i32,
+    transaction_id: i32,
+    date: NaiveDate,
+    description: String,
+    amount: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct StatementLine {
+    line_id: i32,
+    date: NaiveDate,
+    description: String,
+    amount: f32,
+    status: String,
+}
+
+// bank balance = book balance - outstanding book lines
+//     + statement lines not in the books;
+// adjusted_difference is what this leaves unexplained.
+#[derive(Debug, Serialize)]
+struct ReconciliationReport {
+    reconciliation: Reconciliation,
+    book_balance: f32,
+    statement_balance: f32,
+    difference: f32,
+    reconciled_count: usize,
+    outstanding_book: Vec<BookLine>,
+    outstanding_statement: Vec<StatementLine>,
+    adjusted_difference: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutoQuery {
+    days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TickInput {
+    detail_id: i32,
+    line_id: Option<i32>,
+}
+
+type ReconciliationInput = Reconciliation;
+type ReconciliationOutput = ApiResponse<Vec<Reconciliation>>;
+type ReportOutput = ApiResponse<ReconciliationReport>;
+type MatchOutput = ApiResponse<Vec<i32>>;
+
+// what a reconciliation works on: the statement, the sign of the
+// natural balance of its account, and the lines of the period
+struct Workspace {
+    reconciliation: DbReconciliation,
+    sign: f32,
+    book: Vec<DbBookItem>,
+    statement: Vec<DbStagedLine>,
+}
+
+impl Workspace {
+
+    async fn load(db: &Db, reconciliation_id: i32) -> Result<Self, Error> {
+        let reconciliation
+            = match DbReconciliation::by_id(db, reconciliation_id).await {
+                Ok(r) => r,
+                Err(ledger_db::Error::RowNotFound) => return Err(
+                    Error::ReconciliationNotFound(reconciliation_id)
+                ),
+                Err(e) => return Err(e.into()),
+            };
+        let name = &reconciliation.account_name;
+        let sign = match Account::by_name(db, name).await?
+            .account_type.amount_side() {
+            AmountSide::Debit => 1_f32,
+            AmountSide::Credit => -1_f32,
+        };
+        let (start, end)
+            = (reconciliation.period_start, reconciliation.period_end);
+        // lines left outstanding by the periods before count as well
+        let book = match DbBookItem::by_period(
+            db, name, start, end, reconciliation_id,
+        ).await {
+            Ok(b) => b,
+            Err(ledger_db::Error::RowNotFound) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let statement
+            = match DbStagedLine::by_account(db, name, start, end).await {
+                Ok(s) => s,
+                Err(ledger_db::Error::RowNotFound) => Vec::new(),
+                Err(e) => return Err(e.into()),
+            };
+        Ok(Workspace { reconciliation, sign, book, statement })
+    }
+
+    fn open(self) -> Result<Self, Error> {
+        match self.reconciliation.reconcile_status {
+            ReconcileStatus::Open => Ok(self),
+            ReconcileStatus::Closed => Err(Error::ReconciliationClosed(
+                self.reconciliation.reconciliation_id.to_string()
+            )),
+        }
+    }
+
+    fn book_amount(&self, item: &DbBookItem) -> f32 {
+        self.sign * (item.debit_amount - item.credit_amount)
+    }
+
+    // a withdrawal is a credit of the account in the books,
+    // for a bank account as well as for a card
+    fn statement_amount(&self, line: &DbStagedLine) -> f32 {
+        self.sign * (line.deposit_amount - line.withdrawal_amount)
+    }
+
+    fn is_pair(item: &DbBookItem, line: &DbStagedLine) -> bool {
+        item.debit_amount - item.credit_amount
+            == line.deposit_amount - line.withdrawal_amount
+    }
+
+    // Lines posted from the staging area are paired with their own
+    // journal first, the others with the unreconciled book line of the
+    // same amount whose date is nearest, within the given days.
+    fn pairs(&self, days: i64) -> Vec<(i32, i32)> {
+        let mut book = self.book.iter()
+            .filter(|b| !b.reconciled)
+            .collect::<Vec<&DbBookItem>>();
+        let lines = self.statement.iter()
+            .filter(|l| l.transaction_detail_id.is_none())
+            .collect::<Vec<&DbStagedLine>>();
+        let mut pairs = Vec::new();
+        let mut rest = Vec::new();
+        for line in lines {
+            let own = book.iter().position(|b| {
+                Some(b.transaction_id) == line.transaction_id
+                    && Workspace::is_pair(b, line)
+            });
+            match own {
+                Some(i) => pairs.push(
+                    (book.remove(i).transaction_detail_id, line.staged_line_id)
+                ),
+                None => rest.push(line),
+            }
+        }
+        for line in rest {
+            let nearest = book.iter().enumerate()
+                .filter(|(_, b)| Workspace::is_pair(b, line))
+                .map(|(i, b)| {
+                    (i, (b.transaction_date - line.line_date).num_days().abs())
+                })
+                .filter(|(_, d)| *d <= days)
+                .min_by_key(|(_, d)| *d)
+                .map(|(i, _)| i);
+            if let Some(i) = nearest {
+                pairs.push(
+                    (book.remove(i).transaction_detail_id, line.staged_line_id)
+                );
+            }
+        }
+        pairs
+    }
+
+    async fn report(&self, db: &Db) -> Result<ReconciliationReport, Error> {
+        let rec = &self.reconciliation;
+        let book_balance = self.sign * DbBookItem::balance_until(
+            db, &rec.account_name, rec.period_end,
+        ).await?;
+        let outstanding_book = self.book.iter()
+            .filter(|b| !b.reconciled)
+            .map(|b| BookLine {
+                detail_id: b.transaction_detail_id,
+                transaction_id: b.transaction_id,
+                date: b.transaction_date,
+                description: b.description.clone(),
+                amount: self.book_amount(b),
+            })
+            .collect::<Vec<BookLine>>();
+        // discarded lines are offered for matching
+        // but not reported as missing from the books
+        let outstanding_statement = self.statement.iter()
+            .filter(|l| l.transaction_detail_id.is_none())
+            .filter(|l| l.line_status != LineStatus::Discarded)
+            .map(|l| StatementLine {
+                line_id: l.staged_line_id,
+                date: l.line_date,
+                description: l.description.clone(),
+                amount: self.statement_amount(l),
+                status: l.line_status.into_japanese(),
+            })
+            .collect::<Vec<StatementLine>>();
+        let adjusted_balance = book_balance
+            - outstanding_book.iter().map(|b| b.amount).sum::<f32>()
+            + outstanding_statement.iter().map(|l| l.amount).sum::<f32>();
+        Ok(ReconciliationReport {
+            reconciliation: Reconciliation::from_db_reconciliation(rec),
+            book_balance,
+            statement_balance: rec.statement_balance,
+            difference: rec.statement_balance - book_balance,
+            reconciled_count: self.book.iter()
+                .filter(|b| b.reconciliation_id == Some(rec.reconciliation_id))
+                .count(),
+            outstanding_book,
+            outstanding_statement,
+            adjusted_difference: rec.statement_balance - adjusted_balance,
+        })
+    }
+
+}
+
+async fn show_reconciliation(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReconciliationOutput>) {
+    let db_rec = match DbReconciliation::all(&state.db).await {
+        Ok(r) => r,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let rec = db_rec.iter()
+        .map(Reconciliation::from_db_reconciliation)
+        .collect::<Vec<Reconciliation>>();
+    (StatusCode::OK, Json(ReconciliationOutput::ok(rec)))
+}
+
+async fn insert_db_reconciliation(
+    db: &Db,
+    input: &ReconciliationInput,
+) -> Result<i32, Error> {
+    match input.into_db_reconciliation().upsert(db).await {
+        Ok(id) => Ok(id),
+        Err(ledger_db::Error::AccountNotFound)
+            => Err(Error::AccountNotFound(input.account.clone())),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::ReconciliationClosed(
+                format!("{} {}", input.account, input.end)
+            )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn insert_reconciliation(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<ReconciliationInput>,
+) -> (StatusCode, Json<MatchOutput>) {
+    match insert_db_reconciliation(&state.db, &input).await {
+        Ok(id) => (StatusCode::CREATED, Json(MatchOutput::ok(vec![id]))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::ReconciliationNotFound(_)
+            | Error::BookItemNotFound(_)
+            | Error::StagedLineNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn show_report(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReportOutput>) {
+    let ws = match Workspace::load(&state.db, id).await {
+        Ok(w) => w,
+        Err(e) => return (error_status(&e), Json(e.into_api_response())),
+    };
+    match ws.report(&state.db).await {
+        Ok(r) => (StatusCode::OK, Json(ReportOutput::ok(r))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn match_all(
+    db: &Db,
+    reconciliation_id: i32,
+    days: i64,
+) -> Result<Vec<i32>, Error> {
+    let ws = Workspace::load(db, reconciliation_id).await?.open()?;
+    let mut matched = Vec::new();
+    for (detail_id, line_id) in ws.pairs(days) {
+        DbBookItem::reconcile(
+            db, detail_id, reconciliation_id, Some(line_id),
+        ).await?;
+        matched.push(detail_id);
+    }
+    Ok(matched)
+}
+
+// returns the ids of the book lines ticked off
+async fn auto_match(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AutoQuery>,
+) -> (StatusCode, Json<MatchOutput>) {
+    match match_all(&state.db, id, query.days.unwrap_or(3)).await {
+        Ok(ids) => (
+            StatusCode::OK,
+            Json(MatchOutput::ok_with(
+                format!("{} lines matched", ids.len()),
+                ids,
+            )),
+        ),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn tick_line(
+    db: &Db,
+    reconciliation_id: i32,
+    input: &TickInput,
+) -> Result<(), Error> {
+    let ws = Workspace::load(db, reconciliation_id).await?.open()?;
+    if !ws.book.iter().any(|b| {
+        b.transaction_detail_id == input.detail_id && !b.reconciled
+    }) {
+        return Err(Error::BookItemNotFound(input.detail_id));
+    }
+    if let Some(line_id) = input.line_id {
+        if !ws.statement.iter().any(|l| {
+            l.staged_line_id == line_id && l.transaction_detail_id.is_none()
+        }) {
+            return Err(Error::StagedLineNotFound(line_id));
+        }
+    }
+    DbBookItem::reconcile(
+        db, input.detail_id, reconciliation_id, input.line_id,
+    ).await?;
+    Ok(())
+}
+
+async fn tick(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<TickInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match tick_line(&state.db, id, &input).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn untick_line(
+    db: &Db,
+    reconciliation_id: i32,
+    detail_id: i32,
+) -> Result<(), Error> {
+    Workspace::load(db, reconciliation_id).await?.open()?;
+    match DbBookItem::unreconcile(db, detail_id, reconciliation_id).await {
+        Ok(_) => Ok(()),
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::BookItemNotFound(detail_id)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn untick(
+    Path((id, detail_id)): Path<(i32, i32)>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match untick_line(&state.db, id, detail_id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+// only a statement fully explained by the outstanding lines is closed
+async fn close_reconciliation(
+    db: &Db,
+    reconciliation_id: i32,
+) -> Result<(), Error> {
+    let ws = Workspace::load(db, reconciliation_id).await?.open()?;
+    let report = ws.report(db).await?;
+    if report.adjusted_difference.abs() >= 0.005_f32 {
+        return Err(Error::ReconcileDifference(report.adjusted_difference));
+    }
+    DbReconciliation::close(db, reconciliation_id).await?;
+    Ok(())
+}
+
+async fn close(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match close_reconciliation(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
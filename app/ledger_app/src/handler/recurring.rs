@@ -0,0 +1,479 @@
+pub mod schedule;
+pub mod scheduler;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        Query,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use chrono::{
+    Days,
+    NaiveDate,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    BusinessDay,
+    Db,
+    Frequency,
+    Holiday as DbHoliday,
+    RecurringEntry as DbRecurringEntry,
+    RecurringLine,
+    RecurringPosting,
+};
+
+use crate::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::{
+    AccountAmount,
+    Journal,
+};
+
+use schedule::{
+    occurrences,
+    Calendar,
+};
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_recurring).post(insert_recurring))
+    .route("/upcoming", get(show_upcoming))
+    .route("/run", post(run))
+    .route("/holiday", get(show_holiday).post(insert_holiday))
+    .route("/holiday/{date}", delete(delete_holiday))
+    .route("/{id}", get(show_one).delete(delete_recurring))
+}
+
+fn default_frequency() -> String { "毎月".to_string() }
+fn default_business_day() -> String { "翌営業日".to_string() }
+fn default_active() -> bool { true }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Recurring {
+    #[serde(default)]
+    id: i32,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partner: Option<String>,
+    #[serde(default = "default_frequency")]
+    frequency: String,
+    day: u32,
+    #[serde(default = "default_business_day")]
+    business_day: String,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    #[serde(default = "default_active")]
+    active: bool,
+    debit: Vec<AccountAmount>,
+    credit: Vec<AccountAmount>,
+}
+
+impl Recurring {
+
+    // a value that is none of the names is refused rather than taken
+    // as the default, a typo would post on the wrong days
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_recurring(&self) -> Result<DbRecurringEntry, Error> {
+        let invalid = |what: &str, value: &str| Error::RecurringError(
+            format!("'{}' is not {}", value, what)
+        );
+        let frequency = Frequency::parse(&self.frequency)
+            .ok_or(invalid("a frequency", &self.frequency))?;
+        let business_day = BusinessDay::parse(&self.business_day)
+            .ok_or(invalid("a business day", &self.business_day))?;
+        let debit = self.debit.iter().map(|d| RecurringLine {
+            account_name: d.account.clone(),
+            debit_amount: d.amount,
+            credit_amount: 0_f32,
+        });
+        let credit = self.credit.iter().map(|c| RecurringLine {
+            account_name: c.account.clone(),
+            debit_amount: 0_f32,
+            credit_amount: c.amount,
+        });
+        Ok(DbRecurringEntry {
+            recurring_id: 0,
+            recurring_name: self.name.clone(),
+            description: self.desc.clone(),
+            partner_name: self.partner.clone(),
+            frequency,
+            day_of_month: self.day,
+            business_day,
+            start_date: self.start,
+            end_date: self.end,
+            active: self.active,
+            lines: debit.chain(credit).collect(),
+        })
+    }
+
+    fn from_db_recurring(entry: &DbRecurringEntry) -> Self {
+        let mut debit = Vec::new();
+        let mut credit = Vec::new();
+        for line in &entry.lines {
+            if line.debit_amount > 0_f32 {
+                debit.push(AccountAmount {
+                    account: line.account_name.clone(),
+                    amount: line.debit_amount,
+                    foreign: None,
+                });
+            } else {
+                credit.push(AccountAmount {
+                    account: line.account_name.clone(),
+                    amount: line.credit_amount,
+                    foreign: None,
+                });
+            }
+        }
+        Recurring {
+            id: entry.recurring_id,
+            name: entry.recurring_name.clone(),
+            desc: entry.description.clone(),
+            partner: entry.partner_name.clone(),
+            frequency: entry.frequency.into_japanese(),
+            day: entry.day_of_month,
+            business_day: entry.business_day.into_japanese(),
+            start: entry.start_date,
+            end: entry.end_date,
+            active: entry.active,
+            debit,
+            credit,
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if !(1..=31).contains(&self.day) {
+            return Err(Error::RecurringError(
+                format!("day {} is not a day of month", self.day)
+            ));
+        }
+        if self.end.is_some_and(|end| end < self.start) {
+            return Err(Error::RecurringError(
+                "the end is before the start".to_string()
+            ));
+        }
+        let debit = self.debit.iter().map(|d| d.amount).sum::<f32>();
+        let credit = self.credit.iter().map(|c| c.amount).sum::<f32>();
+        if debit <= 0_f32 || debit != credit {
+            return Err(Error::RecurringError(
+                format!("debit {} and credit {} do not balance", debit, credit)
+            ));
+        }
+        Ok(())
+    }
+
+}
+
+// the journal of the entry for the occurrence posted on date
+fn journal_of(entry: &DbRecurringEntry, date: NaiveDate) -> Journal {
+    let recurring = Recurring::from_db_recurring(entry);
+    Journal {
+        transaction_type: "InTerm".to_string(),
+        date,
+        debit: recurring.debit,
+        credit: recurring.credit,
+        desc: if entry.description.is_empty() {
+            entry.recurring_name.clone()
+        } else {
+            entry.description.clone()
+        },
+        partner: entry.partner_name.clone(),
+        tags: Vec::new(),
+        created_by: None,
+        id: None,
+        review: None,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Scheduled {
+    id: i32,
+    name: String,
+    scheduled: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_id: Option<i32>,
+    journal: Journal,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpcomingQuery {
+    days: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Holiday {
+    date: NaiveDate,
+    name: String,
+}
+
+type RecurringInput = Recurring;
+type RecurringOutput = ApiResponse<Vec<Recurring>>;
+type RecurringIdOutput = ApiResponse<Vec<i32>>;
+type ScheduledOutput = ApiResponse<Vec<Scheduled>>;
+type HolidayInput = Holiday;
+type HolidayOutput = ApiResponse<Vec<Holiday>>;
+
+// the occurrences of the active entries falling on or before until
+// that have not been posted yet
+async fn unposted(
+    db: &Db,
+    until: NaiveDate,
+) -> Result<Vec<(DbRecurringEntry, Vec<schedule::Occurrence>)>, Error> {
+    let calendar = Calendar::new(&DbHoliday::all(db).await?);
+    let posted = RecurringPosting::all(db).await?.iter()
+        .map(|p| (p.recurring_id, p.scheduled_date))
+        .collect::<HashSet<(i32, NaiveDate)>>();
+    let mut unposted = Vec::new();
+    for entry in DbRecurringEntry::all(db).await? {
+        if !entry.active { continue; }
+        let due = occurrences(&entry, &calendar, until).into_iter()
+            .filter(|o| !posted.contains(&(entry.recurring_id, o.scheduled)))
+            .collect::<Vec<schedule::Occurrence>>();
+        unposted.push((entry, due));
+    }
+    Ok(unposted)
+}
+
+// Posts every occurrence due by today, the ones missed while the app
+// was down included. An entry that fails to post is retried on the
+// next run and does not hold the others back.
+pub(crate) async fn post_due(
+    state: &AppState,
+    today: NaiveDate,
+) -> Result<Vec<Scheduled>, Error> {
+    let _running = state.scheduler.lock().await;
+    let mut posted = Vec::new();
+    let mut failed = None;
+    for (entry, due) in unposted(&state.db, today).await? {
+        for occurrence in due {
+            let journal = journal_of(&entry, occurrence.date);
+            let result = async {
+                let transaction = journal.into_transaction(&state.db).await?;
+                Ok::<Option<i32>, Error>(DbRecurringEntry::post(
+                    &state.db,
+                    entry.recurring_id,
+                    occurrence.scheduled,
+                    &transaction,
+                ).await?)
+            }.await;
+            match result {
+                // posted by a scheduler of another process
+                Ok(None) => (),
+                Ok(Some(transaction_id)) => posted.push(Scheduled {
+                    id: entry.recurring_id,
+                    name: entry.recurring_name.clone(),
+                    scheduled: occurrence.scheduled,
+                    transaction_id: Some(transaction_id),
+                    journal,
+                }),
+                Err(e) => {
+                    failed = Some(Error::RecurringError(
+                        format!("'{}': {}", entry.recurring_name, e)
+                    ));
+                    break;
+                },
+            }
+        }
+    }
+    match failed {
+        Some(e) if posted.is_empty() => Err(e),
+        Some(e) => {
+            log::error!("{}", e);
+            Ok(posted)
+        },
+        None => Ok(posted),
+    }
+}
+
+async fn show_recurring(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<RecurringOutput>) {
+    let db_recurring = match DbRecurringEntry::all(&state.db).await {
+        Ok(r) => r,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    };
+    let recurring = db_recurring.iter()
+        .map(Recurring::from_db_recurring)
+        .collect::<Vec<Recurring>>();
+    (StatusCode::OK, Json(RecurringOutput::ok(recurring)))
+}
+
+async fn show_one(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<RecurringOutput>) {
+    match DbRecurringEntry::by_id(&state.db, id).await {
+        Ok(r) => (
+            StatusCode::OK,
+            Json(RecurringOutput::ok(vec![Recurring::from_db_recurring(&r)])),
+        ),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::RecurringNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn insert_db_recurring(
+    db: &Db,
+    input: &RecurringInput,
+) -> Result<i32, Error> {
+    input.validate()?;
+    let entry = input.into_db_recurring()?;
+    // the accounts are checked before anything is stored
+    journal_of(&entry, input.start).into_transaction(db).await?;
+    Ok(entry.insert(db).await?)
+}
+
+async fn insert_recurring(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<RecurringInput>,
+) -> (StatusCode, Json<RecurringIdOutput>) {
+    match insert_db_recurring(&state.db, &input).await {
+        Ok(id) => (StatusCode::CREATED, Json(RecurringIdOutput::ok(vec![id]))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn delete_recurring(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbRecurringEntry::delete(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::RecurringNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+// the occurrences not posted yet up to days ahead, 30 by default
+async fn show_upcoming(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UpcomingQuery>,
+) -> (StatusCode, Json<ScheduledOutput>) {
+    let until = state.today() + Days::new(query.days.unwrap_or(30));
+    let unposted = match unposted(&state.db, until).await {
+        Ok(u) => u,
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    };
+    let mut upcoming = unposted.iter()
+        .flat_map(|(entry, due)| due.iter().map(|o| Scheduled {
+            id: entry.recurring_id,
+            name: entry.recurring_name.clone(),
+            scheduled: o.scheduled,
+            transaction_id: None,
+            journal: journal_of(entry, o.date),
+        }))
+        .collect::<Vec<Scheduled>>();
+    upcoming.sort_by_key(|s| (s.journal.date, s.id));
+    (StatusCode::OK, Json(ScheduledOutput::ok(upcoming)))
+}
+
+// posts what is due now instead of waiting for the scheduler
+async fn run(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ScheduledOutput>) {
+    match post_due(&state, state.today()).await {
+        Ok(posted) => (StatusCode::OK, Json(ScheduledOutput::ok(posted))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(e.into_api_response()),
+        ),
+    }
+}
+
+async fn show_holiday(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<HolidayOutput>) {
+    match DbHoliday::all(&state.db).await {
+        Ok(h) => (
+            StatusCode::OK,
+            Json(HolidayOutput::ok(h.iter().map(|h| Holiday {
+                date: h.holiday_date,
+                name: h.holiday_name.clone(),
+            }).collect())),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn insert_holiday(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<Vec<HolidayInput>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    for holiday in &input {
+        let db_holiday = DbHoliday {
+            holiday_date: holiday.date,
+            holiday_name: holiday.name.clone(),
+        };
+        if let Err(e) = db_holiday.upsert(&state.db).await {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Error::from(e).into_api_response()),
+            );
+        }
+    }
+    (StatusCode::CREATED, Json(ApiResponse::ok_only()))
+}
+
+async fn delete_holiday(
+    Path(date): Path<NaiveDate>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match DbHoliday::delete(&state.db, date).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::HolidayNotFound(date).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use chrono::{
+    Datelike,
+    Days,
+    Months,
+    NaiveDate,
+    Weekday,
+};
+
+use ledger_db::{
+    BusinessDay,
+    Holiday,
+    RecurringEntry,
+};
+
+// business days are the weekdays that are not holidays
+pub(crate) struct Calendar {
+    holidays: HashSet<NaiveDate>,
+}
+
+impl Calendar {
+
+    pub(crate) fn new(holidays: &[Holiday]) -> Self {
+        Calendar {
+            holidays: holidays.iter().map(|h| h.holiday_date).collect(),
+        }
+    }
+
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+            && !self.holidays.contains(&date)
+    }
+
+    fn following(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_business_day(date) {
+            date = date + Days::new(1);
+        }
+        date
+    }
+
+    fn preceding(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_business_day(date) {
+            date = date - Days::new(1);
+        }
+        date
+    }
+
+    pub(crate) fn adjust(
+        &self,
+        date: NaiveDate,
+        business_day: BusinessDay,
+    ) -> NaiveDate {
+        match business_day {
+            BusinessDay::AsIs => date,
+            BusinessDay::Following => self.following(date),
+            BusinessDay::Preceding => self.preceding(date),
+            BusinessDay::ModifiedFollowing => {
+                let following = self.following(date);
+                if following.month() == date.month() {
+                    following
+                } else {
+                    self.preceding(date)
+                }
+            },
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Occurrence {
+    // the day of the schedule, which identifies the occurrence
+    pub scheduled: NaiveDate,
+    // the business day it is posted on
+    pub date: NaiveDate,
+}
+
+// day_of_month of the month, the last day for the days it does not have
+fn day_in_month(first: NaiveDate, day_of_month: u32) -> NaiveDate {
+    let last = (first + Months::new(1)) - Days::new(1);
+    first.with_day(day_of_month.min(last.day())).unwrap_or(last)
+}
+
+// the occurrences of the entry posted on or before until
+pub(crate) fn occurrences(
+    entry: &RecurringEntry,
+    calendar: &Calendar,
+    until: NaiveDate,
+) -> Vec<Occurrence> {
+    let mut occurrences = Vec::new();
+    let Some(mut month) = entry.start_date.with_day(1) else {
+        return occurrences;
+    };
+    // an adjustment moves a day by a week at most
+    let last_scheduled = match entry.end_date {
+        Some(end) => end.min(until + Days::new(7)),
+        None => until + Days::new(7),
+    };
+    loop {
+        let scheduled = day_in_month(month, entry.day_of_month);
+        if scheduled > last_scheduled { break; }
+        if scheduled >= entry.start_date {
+            let date = calendar.adjust(scheduled, entry.business_day);
+            if date <= until {
+                occurrences.push(Occurrence { scheduled, date });
+            }
+        }
+        month = month + Months::new(entry.frequency.months());
+    }
+    occurrences
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::AppState;
+
+use super::post_due;
+
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Posts the due recurring entries every hour. The first run is right
+// at startup and catches up on what was missed while the app was down.
+pub(crate) async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match post_due(&state, state.today()).await {
+            Ok(posted) if !posted.is_empty()
+                => log::info!("{} journals posted", posted.len()),
+            Ok(_) => (),
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
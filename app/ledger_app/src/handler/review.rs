@@ -0,0 +1,272 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    Db,
+    ReviewCount,
+    ReviewStatus,
+    Transaction,
+    TransactionComment,
+};
+
+use crate::{
+    auth::CurrentUser,
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::journal::journal_payload::Journal;
+use crate::handler::summary::get_period_year;
+
+// the questions of the accountant on the journals, and what is left
+// open of a year before it is closed
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/{y}", get(show_year))
+    .route("/journal/{id}/status", post(set_status))
+    .route("/journal/{id}/comment", get(show_comment).post(insert_comment))
+    .route("/comment/{id}/resolve", post(resolve_comment))
+}
+
+#[derive(Debug, Serialize)]
+struct ReviewYear {
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    needs_review: i64,
+    open_comments: i64,
+    // the journals still to be looked at
+    journals: Vec<Journal>,
+}
+
+#[derive(Debug, Serialize)]
+struct Comment {
+    id: i32,
+    comment_text: String,
+    resolved: bool,
+    created_by: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl Comment {
+
+    fn from_db_comment(comment: &TransactionComment) -> Self {
+        Comment {
+            id: comment.transaction_comment_id,
+            comment_text: comment.comment_text.clone(),
+            resolved: comment.resolved,
+            created_by: comment.created_by.clone(),
+            created_at: comment.created_at,
+        }
+    }
+
+}
+
+// needs_review, reviewed, 要確認, 確認済, or null to take the flag off
+#[derive(Debug, Deserialize)]
+struct StatusInput {
+    status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentInput {
+    comment_text: String,
+}
+
+type ReviewYearOutput = ApiResponse<ReviewYear>;
+type CommentOutput = ApiResponse<Vec<Comment>>;
+type CommentIdOutput = ApiResponse<i32>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::JournalNotFound(_) | Error::CommentNotFound(_)
+            => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+fn parse_status(status: &str) -> Result<ReviewStatus, Error> {
+    ReviewStatus::parse(status.trim())
+        .ok_or(Error::ReviewError(format!("'{}' is not a review status", status)))
+}
+
+// the journals of ?review=: open takes both the flagged ones and those
+// with open comments
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReviewFilter {
+    Open,
+    Status(ReviewStatus),
+}
+
+impl ReviewFilter {
+
+    pub fn parse(review: &str) -> Result<Self, Error> {
+        match review.trim() {
+            "open" => Ok(ReviewFilter::Open),
+            status => Ok(ReviewFilter::Status(parse_status(status)?)),
+        }
+    }
+
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        match self {
+            ReviewFilter::Open
+                => transaction.review_status == Some(ReviewStatus::NeedsReview)
+                    || transaction.open_comments > 0,
+            ReviewFilter::Status(status)
+                => transaction.review_status == Some(*status),
+        }
+    }
+
+}
+
+// The closing journals of a year wait until its questions are answered.
+pub(crate) async fn check_closable(
+    state: &AppState,
+    transaction: &Transaction,
+) -> Result<(), Error> {
+    transaction.check_closable(&state.db, state.config.fiscal_year.start_month)
+        .await
+        .map_err(Error::from_posting)
+}
+
+async fn review_year(db: &Db, start: NaiveDate, end: NaiveDate) -> Result<ReviewYear, Error> {
+    let count = ReviewCount::by_period(db, start, end).await?;
+    let transactions = match Transaction::by_period(db, start, end).await {
+        Ok(t) => t,
+        Err(ledger_db::Error::RowNotFound) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(ReviewYear {
+        period_start: start,
+        period_end: end,
+        needs_review: count.needs_review,
+        open_comments: count.open_comments,
+        journals: transactions.iter()
+            .filter(|t| ReviewFilter::Open.matches(t))
+            .map(Journal::from_transaction)
+            .collect(),
+    })
+}
+
+async fn show_year(
+    Path(y): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReviewYearOutput>) {
+    let result = match get_period_year(y, state.config.fiscal_year.start_month) {
+        Some((start, end)) => review_year(&state.db, start, end).await,
+        None => Err(Error::DateTimeError(format!("year {}", y))),
+    };
+    match result {
+        Ok(review) => (StatusCode::OK, Json(ReviewYearOutput::ok(review))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn set_db_status(db: &Db, id: i32, input: &StatusInput) -> Result<(), Error> {
+    let status = match &input.status {
+        Some(status) => Some(parse_status(status)?),
+        None => None,
+    };
+    match Transaction::set_review_status(db, id, status).await {
+        Err(ledger_db::Error::RowNotFound) => Err(Error::JournalNotFound(id)),
+        result => Ok(result?),
+    }
+}
+
+async fn set_status(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<StatusInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match set_db_status(&state.db, id, &input).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn show_comment(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<CommentOutput>) {
+    match TransactionComment::by_transaction(&state.db, id).await {
+        Ok(comments) => (
+            StatusCode::OK,
+            Json(CommentOutput::ok(comments.iter().map(Comment::from_db_comment).collect())),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn insert_db_comment(
+    db: &Db,
+    user: &CurrentUser,
+    id: i32,
+    input: &CommentInput,
+) -> Result<i32, Error> {
+    if input.comment_text.trim().is_empty() {
+        return Err(Error::ReviewError("the comment is empty".to_string()));
+    }
+    match TransactionComment::insert(
+        db, id, user.user_name().as_deref(), input.comment_text.trim(),
+    ).await {
+        Err(ledger_db::Error::RowNotFound) => Err(Error::JournalNotFound(id)),
+        result => Ok(result?),
+    }
+}
+
+// a comment flags the journal as needs_review again
+async fn insert_comment(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<CommentInput>,
+) -> (StatusCode, Json<CommentIdOutput>) {
+    match insert_db_comment(&state.db, &user, id, &input).await {
+        Ok(comment_id) => (StatusCode::CREATED, Json(CommentIdOutput::ok(comment_id))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn resolve_comment(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match TransactionComment::resolve(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::CommentNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
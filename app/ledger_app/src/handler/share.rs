@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    ShareLink,
+    ShareScope,
+};
+
+use crate::{
+    auth::{
+        share,
+        CurrentUser,
+    },
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+use crate::handler::summary::get_period_year;
+
+// read-only links for the 税理士 and the like, made by the owner
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_share).post(insert_share))
+    .route("/{id}", delete(revoke_share))
+}
+
+#[derive(Debug, Serialize)]
+struct Share {
+    id: i32,
+    share_name: String,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    scopes: Vec<String>,
+    expires_on: NaiveDate,
+    revoked: bool,
+    created_by: Option<String>,
+}
+
+impl Share {
+
+    fn from_db_share_link(link: &ShareLink) -> Self {
+        Share {
+            id: link.share_link_id,
+            share_name: link.share_name.clone(),
+            period_start: link.period_start,
+            period_end: link.period_end,
+            scopes: link.scopes.iter().map(|s| s.to_string()).collect(),
+            expires_on: link.expires_on,
+            revoked: link.revoked_at.is_some(),
+            created_by: link.created_by.clone(),
+        }
+    }
+
+}
+
+// scopes of journals, general_ledger, trial_balance and
+// financial_statements, or of 仕訳帳, 総勘定元帳, 試算表 and 決算書
+#[derive(Debug, Deserialize)]
+struct ShareInput {
+    share_name: String,
+    fiscal_year: i32,
+    scopes: Vec<String>,
+    expires_on: NaiveDate,
+}
+
+// the token is in the response only
+#[derive(Debug, Serialize)]
+struct NewShare {
+    id: i32,
+    token: String,
+}
+
+type ShareOutput = ApiResponse<Vec<Share>>;
+type NewShareOutput = ApiResponse<NewShare>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::ShareNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn show_share(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ShareOutput>) {
+    match ShareLink::all(&state.db).await {
+        Ok(links) => (
+            StatusCode::OK,
+            Json(ShareOutput::ok(links.iter().map(Share::from_db_share_link).collect())),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+fn into_db_share_link(
+    state: &AppState,
+    user: &CurrentUser,
+    input: &ShareInput,
+) -> Result<ShareLink, Error> {
+    if input.share_name.trim().is_empty() {
+        return Err(Error::ShareError("the share name is empty".to_string()));
+    }
+    let scopes = input.scopes.iter()
+        .map(|s| ShareScope::parse(s.trim())
+            .ok_or(Error::ShareError(format!("'{}' is not a report", s))))
+        .collect::<Result<Vec<ShareScope>, Error>>()?;
+    if scopes.is_empty() {
+        return Err(Error::ShareError("no reports to share".to_string()));
+    }
+    if input.expires_on < state.today() {
+        return Err(Error::ShareError(
+            format!("{} is already past", input.expires_on)
+        ));
+    }
+    let (period_start, period_end) = get_period_year(
+        input.fiscal_year, state.config.fiscal_year.start_month,
+    ).ok_or(Error::DateTimeError(format!("year {}", input.fiscal_year)))?;
+    Ok(ShareLink {
+        share_link_id: 0,
+        share_name: input.share_name.trim().to_string(),
+        period_start,
+        period_end,
+        scopes,
+        expires_on: input.expires_on,
+        revoked_at: None,
+        created_by: user.user_name(),
+    })
+}
+
+async fn insert_db_share_link(
+    state: &AppState,
+    user: &CurrentUser,
+    input: &ShareInput,
+) -> Result<NewShare, Error> {
+    let secret = &state.config.auth.share_secret;
+    if secret.is_empty() {
+        return Err(Error::ShareError("auth.share_secret is not set".to_string()));
+    }
+    let mut link = into_db_share_link(state, user, input)?;
+    link.share_link_id = link.insert(&state.db).await?;
+    Ok(NewShare {
+        id: link.share_link_id,
+        token: share::sign(secret, &link)?,
+    })
+}
+
+async fn insert_share(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<ShareInput>,
+) -> (StatusCode, Json<NewShareOutput>) {
+    match insert_db_share_link(&state, &user, &input).await {
+        Ok(share) => (StatusCode::CREATED, Json(NewShareOutput::ok(share))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn revoke_share(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match ShareLink::revoke(&state.db, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(ledger_db::Error::RowNotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(Error::ShareNotFound(id).into_api_response()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
@@ -21,6 +21,7 @@ use ledger_db::{
     AmountSide,
     Db,
     Summary as DbSummary,
+    TransactionType,
 };
 
 use crate::{
@@ -73,24 +74,76 @@ impl Summary {
 
 type SummaryOutput = ApiResponse<Vec<Summary>>;
 
-fn get_period_year(y: i32) -> Option<(NaiveDate, NaiveDate)> {
-    let start = NaiveDate::from_ymd_opt(y, 1, 1);
-    let end = NaiveDate::from_ymd_opt(y, 12, 31);
-    match (start, end) {
-        (Some(s), Some(e)) => Some((s, e)),
-        _ => None,
-    }
+// the fiscal year y, beginning on the first day of start_month
+pub(crate) fn get_period_year(
+    y: i32,
+    start_month: u32,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(y, start_month, 1)?;
+    let end = start + Months::new(12) - Days::new(1);
+    Some((start, end))
 }
 
-fn get_period_month(y: i32, m: u32) -> Option<(NaiveDate, NaiveDate)> {
-    let start = match NaiveDate::from_ymd_opt(y, m, 1) {
-        Some(s) => s,
-        None => return None,
-    };
+pub(crate) fn get_period_month(y: i32, m: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(y, m, 1)?;
     let end = start + Months::new(1) - Days::new(1);
     Some((start, end))
 }
 
+// the month m of the fiscal year fy beginning in start_month, the
+// months before start_month fall in the next calendar year
+pub(crate) fn get_period_fiscal_month(
+    fy: i32,
+    m: u32,
+    start_month: u32,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let y = if m < start_month { fy + 1 } else { fy };
+    get_period_month(y, m)
+}
+
+pub(crate) fn stage_from_str(stage: &str) -> Option<TransactionType> {
+    match stage {
+        "from_prev" => Some(TransactionType::FromPrev),
+        "in_term" => Some(TransactionType::InTerm),
+        "kessan" => Some(TransactionType::Kessan),
+        "soneki" => Some(TransactionType::Soneki),
+        "to_next" => Some(TransactionType::ToNext),
+        _ => stage.parse::<TransactionType>().ok()
+            .or_else(|| TransactionType::from_japanese(stage)),
+    }
+}
+
+pub(crate) fn balance_of(db_summary: &DbSummary) -> f32 {
+    match db_summary.account_type.amount_side() {
+        AmountSide::Debit => db_summary.debit - db_summary.credit,
+        AmountSide::Credit => db_summary.credit - db_summary.debit,
+    }
+}
+
+pub(crate) async fn db_summary_by_stage(
+    db: &Db,
+    stage: &TransactionType,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<DbSummary>, Error> {
+    let result = match stage {
+        TransactionType::FromPrev => DbSummary::upto_from_prev(db, start).await,
+        TransactionType::InTerm
+            => DbSummary::upto_in_term(db, start, end).await,
+        TransactionType::Kessan
+            => DbSummary::upto_kessan(db, start, end).await,
+        TransactionType::Soneki
+            => DbSummary::upto_soneki(db, start, end).await,
+        TransactionType::ToNext
+            => DbSummary::upto_to_next(db, start, end).await,
+    };
+    match result {
+        Ok(ds) => Ok(ds),
+        Err(ledger_db::Error::RowNotFound) => Ok(Vec::new()),
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
 async fn from_db_from_prev(
     db: &Db,
     start: NaiveDate,
@@ -102,7 +155,7 @@ async fn from_db_from_prev(
             Err(e) => return Err(Error::from(e)),
         };
     Ok(db_summary.iter()
-    .map(|s| Summary::from_db_summary(s)).collect::<Vec<Summary>>())
+    .map(Summary::from_db_summary).collect::<Vec<Summary>>())
 }
 
 async fn from_db_in_term(
@@ -117,7 +170,7 @@ async fn from_db_in_term(
             Err(e) => return Err(Error::from(e)),
         };
     Ok(db_summary.iter()
-    .map(|s| Summary::from_db_summary(s)).collect::<Vec<Summary>>())
+    .map(Summary::from_db_summary).collect::<Vec<Summary>>())
 }
 
 async fn from_db_kessan(
@@ -132,7 +185,7 @@ async fn from_db_kessan(
             Err(e) => return Err(Error::from(e)),
         };
     Ok(db_summary.iter()
-    .map(|s| Summary::from_db_summary(s)).collect::<Vec<Summary>>())
+    .map(Summary::from_db_summary).collect::<Vec<Summary>>())
 }
 
 async fn from_db_soneki(
@@ -147,7 +200,7 @@ async fn from_db_soneki(
             Err(e) => return Err(Error::from(e)),
         };
     Ok(db_summary.iter()
-    .map(|s| Summary::from_db_summary(s)).collect::<Vec<Summary>>())
+    .map(Summary::from_db_summary).collect::<Vec<Summary>>())
 }
 
 async fn from_db_to_next(
@@ -162,14 +215,14 @@ async fn from_db_to_next(
             Err(e) => return Err(Error::from(e)),
         };
     Ok(db_summary.iter()
-    .map(|s| Summary::from_db_summary(s)).collect::<Vec<Summary>>())
+    .map(Summary::from_db_summary).collect::<Vec<Summary>>())
 }
 
 async fn show_year_from_prev(
     Path(y): Path<i32>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<SummaryOutput>) {
-    let (start, _end) = match get_period_year(y) {
+    let (start, _end) = match get_period_year(y, state.config.fiscal_year.start_month) {
         Some(p) => p,
         None => return (
             StatusCode::BAD_REQUEST,
@@ -191,7 +244,7 @@ async fn show_year_in_term(
     Path(y): Path<i32>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<SummaryOutput>) {
-    let (start, end) = match get_period_year(y) {
+    let (start, end) = match get_period_year(y, state.config.fiscal_year.start_month) {
         Some(p) => p,
         None => return (
             StatusCode::BAD_REQUEST,
@@ -213,7 +266,7 @@ async fn show_year_kessan(
     Path(y): Path<i32>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<SummaryOutput>) {
-    let (start, end) = match get_period_year(y) {
+    let (start, end) = match get_period_year(y, state.config.fiscal_year.start_month) {
         Some(p) => p,
         None => return (
             StatusCode::BAD_REQUEST,
@@ -235,7 +288,7 @@ async fn show_year_soneki(
     Path(y): Path<i32>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<SummaryOutput>) {
-    let (start, end) = match get_period_year(y) {
+    let (start, end) = match get_period_year(y, state.config.fiscal_year.start_month) {
         Some(p) => p,
         None => return (
             StatusCode::BAD_REQUEST,
@@ -257,7 +310,7 @@ async fn show_year_to_next(
     Path(y): Path<i32>,
     State(state): State<Arc<AppState>>,
 ) -> (StatusCode, Json<SummaryOutput>) {
-    let (start, end) = match get_period_year(y) {
+    let (start, end) = match get_period_year(y, state.config.fiscal_year.start_month) {
         Some(p) => p,
         None => return (
             StatusCode::BAD_REQUEST,
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use axum::{
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+
+use crate::AppState;
+
+// The pages are part of the binary, so that the app serves them
+// offline and without any file beside it.
+const INDEX_HTML: &str = include_str!("../../ui/index.html");
+const APP_JS: &str = include_str!("../../ui/app.js");
+const STYLE_CSS: &str = include_str!("../../ui/style.css");
+
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(|| asset("text/html; charset=utf-8", INDEX_HTML)))
+    .route("/app.js", get(|| asset("text/javascript; charset=utf-8", APP_JS)))
+    .route("/style.css", get(|| asset("text/css; charset=utf-8", STYLE_CSS)))
+}
+
+// revalidated on each load, so that a new build is picked up at once
+async fn asset(
+    content_type: &'static str,
+    body: &'static str,
+) -> impl IntoResponse {
+    (
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        body,
+    )
+}
@@ -0,0 +1,319 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    routing::{
+        delete,
+        get,
+        post,
+    },
+    Extension,
+    Json,
+    Router,
+};
+use chrono::NaiveDateTime;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use ledger_db::{
+    ApiToken,
+    Db,
+};
+
+use crate::{
+    auth::{
+        hash_password,
+        new_token,
+        parse_role,
+        token_hash,
+        CurrentUser,
+    },
+    ApiResponse,
+    ApiResponseWithoutBody,
+    AppState,
+    Error,
+};
+
+// /user/me is for everyone signed in, the rest for the owner
+pub(crate) fn build_router() -> Router<Arc<AppState>> {
+    Router::new()
+    .route("/", get(show_user).post(insert_user))
+    .route("/me", get(show_me))
+    .route("/me/password", post(change_my_password))
+    .route("/me/token", get(show_token).post(insert_token))
+    .route("/me/token/{id}", delete(delete_token))
+    .route("/{name}", delete(delete_user))
+    .route("/{name}/password", post(change_password))
+}
+
+#[derive(Debug, Serialize)]
+struct User {
+    user_name: String,
+    user_role: String,
+}
+
+impl User {
+
+    fn from_db_user(user: &ledger_db::User) -> Self {
+        User {
+            user_name: user.user_name.clone(),
+            user_role: user.user_role.to_string(),
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInput {
+    user_name: String,
+    password: String,
+    user_role: String,
+}
+
+impl UserInput {
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_db_user(&self) -> Result<ledger_db::User, Error> {
+        if self.user_name.trim().is_empty() {
+            return Err(Error::UserError("the user name is empty".to_string()));
+        }
+        Ok(ledger_db::User {
+            user_id: 0,
+            user_name: self.user_name.trim().to_string(),
+            password_hash: hash_password(&self.password)?,
+            user_role: parse_role(&self.user_role)?,
+        })
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct PasswordInput {
+    password: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Token {
+    id: i32,
+    token_name: String,
+    last_used_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+impl Token {
+
+    fn from_db_token(token: &ApiToken) -> Self {
+        Token {
+            id: token.api_token_id,
+            token_name: token.token_name.clone(),
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenInput {
+    token_name: String,
+}
+
+// the token itself is in the response only
+#[derive(Debug, Serialize)]
+struct NewToken {
+    id: i32,
+    token: String,
+}
+
+type UserOutput = ApiResponse<Vec<User>>;
+type MeOutput = ApiResponse<User>;
+type TokenOutput = ApiResponse<Vec<Token>>;
+type NewTokenOutput = ApiResponse<NewToken>;
+
+fn error_status(e: &Error) -> StatusCode {
+    match e {
+        Error::UserNotFound(_) | Error::TokenNotFound(_) => StatusCode::NOT_FOUND,
+        Error::DataBaseError(ledger_db::Error::SqlError(_))
+            => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+fn me(user: &CurrentUser) -> Result<&ledger_db::User, Error> {
+    user.0.as_ref().ok_or(Error::UserError(
+        "there are no users while auth.enabled is off".to_string()
+    ))
+}
+
+async fn show_user(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<UserOutput>) {
+    match ledger_db::User::all(&state.db).await {
+        Ok(users) => (
+            StatusCode::OK,
+            Json(UserOutput::ok(users.iter().map(User::from_db_user).collect())),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error::from(e).into_api_response()),
+        ),
+    }
+}
+
+async fn insert_db_user(db: &Db, input: &UserInput) -> Result<i32, Error> {
+    Ok(input.into_db_user()?.insert(db).await?)
+}
+
+async fn insert_user(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<UserInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match insert_db_user(&state.db, &input).await {
+        Ok(_) => (StatusCode::CREATED, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn delete_db_user(
+    db: &Db,
+    user: &CurrentUser,
+    user_name: &str,
+) -> Result<(), Error> {
+    // someone has to be left to add the users back
+    if user.user_name().as_deref() == Some(user_name) {
+        return Err(Error::UserError(
+            "the signed in user can not be removed".to_string()
+        ));
+    }
+    match ledger_db::User::delete(db, user_name).await {
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::UserNotFound(user_name.to_string())),
+        result => Ok(result?),
+    }
+}
+
+async fn delete_user(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match delete_db_user(&state.db, &user, &name).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn set_db_password(
+    db: &Db,
+    user_name: &str,
+    password: &str,
+) -> Result<(), Error> {
+    match ledger_db::User::set_password(
+        db, user_name, &hash_password(password)?,
+    ).await {
+        Err(ledger_db::Error::RowNotFound)
+            => Err(Error::UserNotFound(user_name.to_string())),
+        result => Ok(result?),
+    }
+}
+
+async fn change_password(
+    Path(name): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<PasswordInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match set_db_password(&state.db, &name, &input.password).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn show_me(
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<MeOutput>) {
+    match me(&user) {
+        Ok(u) => (StatusCode::OK, Json(MeOutput::ok(User::from_db_user(u)))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn change_my_password(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<PasswordInput>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    let result = match me(&user) {
+        Ok(u) => set_db_password(&state.db, &u.user_name, &input.password).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn tokens_of(db: &Db, user: &CurrentUser) -> Result<Vec<Token>, Error> {
+    Ok(ApiToken::by_user(db, &me(user)?.user_name).await?
+        .iter().map(Token::from_db_token).collect())
+}
+
+async fn show_token(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<TokenOutput>) {
+    match tokens_of(&state.db, &user).await {
+        Ok(tokens) => (StatusCode::OK, Json(TokenOutput::ok(tokens))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn insert_db_token(
+    db: &Db,
+    user: &CurrentUser,
+    input: &TokenInput,
+) -> Result<NewToken, Error> {
+    let token = new_token();
+    let id = ApiToken::insert(
+        db, &me(user)?.user_name, &input.token_name, &token_hash(&token),
+    ).await?;
+    Ok(NewToken { id, token })
+}
+
+async fn insert_token(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(input): Json<TokenInput>,
+) -> (StatusCode, Json<NewTokenOutput>) {
+    match insert_db_token(&state.db, &user, &input).await {
+        Ok(token) => (StatusCode::CREATED, Json(NewTokenOutput::ok(token))),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
+
+async fn delete_db_token(
+    db: &Db,
+    user: &CurrentUser,
+    id: i32,
+) -> Result<(), Error> {
+    match ApiToken::delete(db, &me(user)?.user_name, id).await {
+        Err(ledger_db::Error::RowNotFound) => Err(Error::TokenNotFound(id)),
+        result => Ok(result?),
+    }
+}
+
+async fn delete_token(
+    Path(id): Path<i32>,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<CurrentUser>,
+) -> (StatusCode, Json<ApiResponseWithoutBody>) {
+    match delete_db_token(&state.db, &user, id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::ok_only())),
+        Err(e) => (error_status(&e), Json(e.into_api_response())),
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+
+use ledger_db::{
+    Db,
+    User,
+};
+
+use crate::{
+    auth::CurrentUser,
+    Error,
+};
+
+use crate::handler::interop::{
+    journal_csv,
+    yayoi,
+};
+use crate::handler::plain_text;
+
+pub use crate::handler::interop::Preview;
+
+// The journal files of /export, /import and /interop for `ledger`
+// without --api, so that the cli reads and writes them as the app does.
+// The formats are named as in the paths: ledger, hledger, beancount,
+// yayoi, freee and moneyforward.
+
+fn layout_of(format: &str) -> Option<&'static journal_csv::Layout> {
+    match format {
+        "freee" => Some(&journal_csv::FREEE),
+        "moneyforward" => Some(&journal_csv::MONEY_FORWARD),
+        _ => None,
+    }
+}
+
+pub async fn export(
+    db: &Db,
+    format: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    encoding: Option<&str>,
+) -> Result<Vec<u8>, Error> {
+    if format == "yayoi" {
+        return yayoi::export_file(db, &yayoi::ExportQuery { from, to }).await;
+    }
+    if let Some(layout) = layout_of(format) {
+        let query = journal_csv::ExportQuery {
+            from,
+            to,
+            encoding: encoding.map(|e| e.to_string()),
+        };
+        return journal_csv::export_file(layout, db, &query).await;
+    }
+    let query = plain_text::ExportQuery { from, to };
+    let text = plain_text::export_file(
+        db, plain_text::format_of(format)?, &query,
+    ).await?;
+    Ok(text.into_bytes())
+}
+
+// The journals of the file posted as the user, as the owner without
+// one. The closing journals are checked in the fiscal year beginning in
+// start_month.
+pub async fn import(
+    db: &Db,
+    user: Option<User>,
+    start_month: u32,
+    format: &str,
+    body: &[u8],
+    encoding: Option<&str>,
+) -> Result<Vec<i32>, Error> {
+    let user = CurrentUser(user);
+    let encoding = encoding.map(|e| e.to_string());
+    if format == "yayoi" {
+        let query = yayoi::ImportQuery { encoding };
+        return yayoi::import_file(db, &user, start_month, &query, body).await;
+    }
+    if let Some(layout) = layout_of(format) {
+        let query = journal_csv::ImportQuery { encoding };
+        return journal_csv::import_file(
+            layout, db, &user, start_month, &query, body,
+        ).await;
+    }
+    plain_text::import_file(db, &user, start_month, format, body).await
+}
+
+pub async fn preview(
+    db: &Db,
+    format: &str,
+    body: &[u8],
+    encoding: Option<&str>,
+) -> Result<Preview, Error> {
+    let encoding = encoding.map(|e| e.to_string());
+    if format == "yayoi" {
+        let query = yayoi::ImportQuery { encoding };
+        return yayoi::preview_file(db, &query, body).await;
+    }
+    if let Some(layout) = layout_of(format) {
+        let query = journal_csv::ImportQuery { encoding };
+        return journal_csv::preview_file(layout, db, &query, body).await;
+    }
+    plain_text::preview_file(db, format, body).await
+}
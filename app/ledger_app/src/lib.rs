@@ -0,0 +1,266 @@
+mod api_response;
+mod auth;
+mod config;
+mod handler;
+mod logger;
+mod text_codec;
+
+pub mod journal_file;
+
+use std::sync::Arc;
+
+use axum::{
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use chrono::{
+    DateTime,
+    NaiveDate,
+    TimeZone,
+    Utc,
+};
+use chrono_tz::Tz;
+use thiserror::Error;
+
+use ledger_db::{
+    Db,
+    Migration,
+    User,
+};
+
+use config::Config;
+
+use api_response::{
+    ApiResponse,
+    ApiResponseWithoutBody,
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    DataBaseError(#[from] ledger_db::Error),
+    #[error("account '{0}' not found")]
+    AccountNotFound(String),
+    #[error("'{0}' can not convert to datetime")]
+    DateTimeError(String),
+    #[error("'{0}' is not a summary stage")]
+    StageError(String),
+    #[error("at least two periods are required")]
+    PeriodCountError,
+    #[error("budget '{0}' not found")]
+    BudgetNotFound(String),
+    #[error("'{0}' is not a cash flow class")]
+    CashFlowClassError(String),
+    #[error("'{0}' is not a supported encoding")]
+    EncodingError(String),
+    #[error("csv error: {0}")]
+    CsvError(String),
+    #[error("format error: {0}")]
+    FormatError(String),
+    #[error("mapping '{0}' not found")]
+    MappingNotFound(String),
+    #[error("staged line '{0}' not found")]
+    StagedLineNotFound(i32),
+    #[error("staged line '{0}' is already posted or discarded")]
+    StagedLineNotDraft(i32),
+    #[error("staged line '{0}' has no counter account")]
+    CounterAccountMissing(i32),
+    #[error("card '{0}' not found")]
+    CardNotFound(String),
+    #[error("invalid rule: {0}")]
+    RuleError(String),
+    #[error("rule '{0}' not found")]
+    RuleNotFound(i32),
+    #[error("reconciliation '{0}' not found")]
+    ReconciliationNotFound(i32),
+    #[error("reconciliation '{0}' is closed")]
+    ReconciliationClosed(String),
+    #[error("book line '{0}' not found")]
+    BookItemNotFound(i32),
+    #[error("difference of {0} is not explained")]
+    ReconcileDifference(f32),
+    #[error("invalid bank account: {0}")]
+    BankAccountError(String),
+    #[error("partner '{0}' has no bank account")]
+    PartnerBankMissing(String),
+    #[error("no transfer source for account '{0}'")]
+    TransferSourceNotFound(String),
+    #[error("payable '{0}' is not open")]
+    PayableNotOpen(i32),
+    #[error("payment '{0}' not found")]
+    PaymentNotFound(i32),
+    #[error("payment '{0}' is not pending")]
+    PaymentNotPending(i32),
+    #[error("invalid recurring entry: {0}")]
+    RecurringError(String),
+    #[error("recurring entry '{0}' not found")]
+    RecurringNotFound(i32),
+    #[error("holiday '{0}' not found")]
+    HolidayNotFound(chrono::NaiveDate),
+    #[error("template '{0}' not found")]
+    TemplateNotFound(String),
+    #[error("invalid template: {0}")]
+    TemplateError(String),
+    #[error("'{0}' is not a supported accounting system")]
+    ExternalSystemNotFound(String),
+    #[error("accounts not found: {0}")]
+    UnknownAccounts(String),
+    #[error("'{0}' is not a supported plain text format")]
+    PlainTextFormatNotFound(String),
+    #[error("invalid backup: {0}")]
+    BackupError(String),
+    #[error("the database already holds journals")]
+    DatabaseNotEmpty,
+    #[error("configuration error: {0}")]
+    ConfigError(String),
+    #[error("server error: {0}")]
+    ServerError(String),
+    #[error("authentication required: {0}")]
+    Unauthorized(String),
+    #[error("'{0}' is left to another role")]
+    Forbidden(String),
+    #[error("user '{0}' not found")]
+    UserNotFound(String),
+    #[error("invalid user: {0}")]
+    UserError(String),
+    #[error("token '{0}' not found")]
+    TokenNotFound(i32),
+    #[error("invalid share link: {0}")]
+    ShareError(String),
+    #[error("share link '{0}' not found")]
+    ShareNotFound(i32),
+    #[error("journal '{0}' not found")]
+    JournalNotFound(i32),
+    #[error("comment '{0}' not found")]
+    CommentNotFound(i32),
+    #[error("invalid review: {0}")]
+    ReviewError(String),
+    #[error("{0} journals need review and {1} comments are open")]
+    ReviewOpen(i64, i64),
+    #[error("invalid currency: {0}")]
+    CurrencyError(String),
+    #[error("no rate of '{0}' on or before {1}")]
+    RateNotFound(String, chrono::NaiveDate),
+    #[error("invalid search: {0}")]
+    SearchError(String),
+}
+
+impl Error {
+
+    // the refusals of ledger_db to post a journal, as those of a request
+    fn from_posting(e: ledger_db::Error) -> Self {
+        match e {
+            ledger_db::Error::ClosingForbidden(t) => Error::Forbidden(t),
+            ledger_db::Error::ReviewOpen(n, c) => Error::ReviewOpen(n, c),
+            e => Error::DataBaseError(e),
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn into_api_response<T>(&self) -> ApiResponse<T> {
+        ApiResponse {
+            status: self.to_string(),
+            message: format!("{}", self),
+            body: None,
+        }
+    }
+
+}
+
+#[derive(Debug)]
+struct AppState {
+    db: Db,
+    config: Config,
+    // held while recurring entries are posted
+    scheduler: tokio::sync::Mutex<()>,
+}
+
+impl AppState {
+
+    // now and today in the timezone of the config
+    fn now(&self) -> DateTime<Tz> {
+        self.config.locale.timezone.from_utc_datetime(&Utc::now().naive_utc())
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+}
+
+pub async fn run() -> Result<(), Error> {
+    let config = Config::load()?;
+    logger::init(config.log.level_filter());
+
+    let db = Db::connect(
+        &config.database.url,
+        config.database.max_connections,
+        config.database.acquire_timeout(),
+    ).await.map_err(|e| Error::ServerError(format!("database: {}", e)))?;
+    // `ledger_app migrate` only brings the schema up to date
+    let command = std::env::args().nth(1);
+    let migrate_only = command.as_deref() == Some("migrate");
+    if config.database.migrate_on_startup || migrate_only {
+        let applied = Migration::run_pending(&db).await?;
+        if !applied.is_empty() {
+            log::info!("applied migrations {:?}", applied);
+        }
+    }
+    if migrate_only { return Ok(()); }
+    Migration::check(&db).await?;
+    if command.as_deref() == Some("add-user") {
+        let args = std::env::args().skip(2).collect::<Vec<String>>();
+        return auth::add_user(&db, &args).await;
+    }
+    if config.auth.enabled && User::all(&db).await?.is_empty() {
+        log::warn!("no users yet, add the owner by `ledger_app add-user NAME owner`");
+    }
+
+    let listen = config.server.listen;
+    let app_state = Arc::new(AppState {
+        db,
+        config,
+        scheduler: tokio::sync::Mutex::new(()),
+    });
+    tokio::spawn(handler::recurring::scheduler::run(app_state.clone()));
+
+    let router = Router::new()
+        .route("/", get(root))
+        .nest("/account", handler::account::build_router())
+        .nest("/journal", handler::journal::build_router())
+        .nest("/review", handler::review::build_router())
+        .nest("/summary", handler::summary::build_router())
+        .nest("/general_ledger", handler::general_ledger::build_router())
+        .nest("/compare", handler::compare::build_router())
+        .nest("/budget", handler::budget::build_router())
+        .nest("/cash_flow", handler::cash_flow::build_router())
+        .nest("/currency", handler::currency::build_router())
+        .nest("/import", handler::import::build_router())
+        .nest("/partner", handler::partner::build_router())
+        .nest("/reconcile", handler::reconcile::build_router())
+        .nest("/payment", handler::payment::build_router())
+        .nest("/recurring", handler::recurring::build_router())
+        .nest("/interop", handler::interop::build_router())
+        .merge(handler::plain_text::build_router())
+        .nest("/admin", handler::admin::build_router())
+        .nest("/ui", handler::ui::build_router())
+        .nest("/user", handler::user::build_router())
+        .nest("/share", handler::share::build_router())
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth::authenticate))
+        .with_state(app_state);
+
+    let listener = tokio::net::TcpListener::bind(listen).await
+        .map_err(|e| Error::ServerError(format!("{}: {}", listen, e)))?;
+    log::info!("listening on {}", listen);
+    axum::serve(listener, router).await
+        .map_err(|e| Error::ServerError(e.to_string()))
+}
+
+async fn root() -> impl IntoResponse {
+    (StatusCode::OK, "ledger version 0.1".to_string())
+}
+
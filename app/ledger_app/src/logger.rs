@@ -0,0 +1,40 @@
+use chrono::Local;
+use log::{
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+};
+
+// lines of "time level target: message" on stderr
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) { return; }
+        eprintln!(
+            "{} {:<5} {}: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            record.level(),
+            record.target(),
+            record.args(),
+        );
+    }
+
+    fn flush(&self) {}
+
+}
+
+pub(crate) fn init(level: LevelFilter) {
+    // only the first call sets the logger
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
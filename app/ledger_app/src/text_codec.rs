@@ -0,0 +1,30 @@
+use encoding_rs::{
+    Encoding,
+    SHIFT_JIS,
+};
+
+use crate::Error;
+
+fn encoding_of(label: &str) -> Result<&'static Encoding, Error> {
+    match label.to_lowercase().as_str() {
+        "cp932" | "932" | "sjis" | "shift-jis" => Ok(SHIFT_JIS),
+        l => Encoding::for_label(l.as_bytes())
+            .ok_or(Error::EncodingError(label.to_string())),
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8], label: &str) -> Result<String, Error> {
+    let (text, _, had_errors) = encoding_of(label)?.decode(bytes);
+    if had_errors {
+        return Err(Error::EncodingError(label.to_string()));
+    }
+    Ok(text.into_owned())
+}
+
+pub(crate) fn encode(text: &str, label: &str) -> Result<Vec<u8>, Error> {
+    let (bytes, _, had_errors) = encoding_of(label)?.encode(text);
+    if had_errors {
+        return Err(Error::EncodingError(label.to_string()));
+    }
+    Ok(bytes.into_owned())
+}
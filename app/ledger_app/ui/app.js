@@ -0,0 +1,449 @@
+// The pages of ledger, each drawn from the JSON API of the app.
+"use strict";
+
+const TRANSACTION_TYPES = [
+  ["InTerm", "期中仕訳"],
+  ["FromPrev", "前期繰越"],
+  ["Kessan", "決算整理"],
+  ["Soneki", "損益振替"],
+  ["ToNext", "次期繰越"],
+];
+
+const STAGES = [
+  ["in_term", "期中"],
+  ["from_prev", "前期繰越"],
+  ["kessan", "決算整理"],
+  ["soneki", "損益振替"],
+  ["to_next", "次期繰越"],
+];
+
+const ACCOUNT_TYPES = [
+  ["Asset", "資産"],
+  ["Liability", "負債"],
+  ["Equity", "資本"],
+  ["Income", "収益"],
+  ["Expense", "費用"],
+  ["UtilDebit", "作業用借方"],
+  ["UtilCredit", "作業用貸方"],
+];
+
+// the shortcuts of /journal, with whether each asks for an account
+const SHORTCUTS = [
+  ["buy/by_owner", "buy_by_owner", "事業主借で購入", true],
+  ["buy/by_bank", "buy_by_bank", "普通預金で購入", true],
+  ["buy/by_kaikakekin", "buy_by_kaikakekin", "買掛金で購入", true],
+  ["buy/by_maebaraikin", "buy_by_maebaraikin", "前払金で購入", true],
+  ["sell/by_bank", "sell_by_bank", "普通預金で売上", true],
+  ["sell/by_urikakekin", "sell_by_urikakekin", "売掛金で売上", true],
+  ["sell/by_maeukekin", "sell_by_maeukekin", "前受金で売上", true],
+  ["bank/to_owner", "bank_to_owner", "普通預金から事業主貸", false],
+  ["bank/from_owner", "bank_from_owner", "事業主借から普通預金", false],
+];
+
+let accounts = [];
+
+// el("td", {class: "num"}, "1,100") and the like
+function el(tag, attrs, ...children) {
+  const node = document.createElement(tag);
+  for (const [key, value] of Object.entries(attrs || {})) {
+    if (key.startsWith("on")) node.addEventListener(key.slice(2), value);
+    else if (value === true) node.setAttribute(key, "");
+    else if (value !== false && value != null) node.setAttribute(key, value);
+  }
+  for (const child of children.flat()) {
+    if (child == null) continue;
+    node.append(child instanceof Node ? child : String(child));
+  }
+  return node;
+}
+
+function options(pairs, selected) {
+  return pairs.map(([value, label]) =>
+    el("option", { value, selected: value === selected }, label));
+}
+
+function amount(value) {
+  if (value == null) return "";
+  return Number(value).toLocaleString("ja-JP", { maximumFractionDigits: 2 });
+}
+
+function parseAmount(text) {
+  const value = Number(String(text).replace(/,/g, "").trim());
+  return Number.isFinite(value) ? value : 0;
+}
+
+function today() {
+  const now = new Date();
+  const pad = (n) => String(n).padStart(2, "0");
+  return `${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}`;
+}
+
+function showMessage(text, ok) {
+  const box = document.getElementById("message");
+  box.hidden = !text;
+  box.className = ok ? "ok" : "error";
+  box.textContent = text || "";
+}
+
+// the body of an ApiResponse, or the message of the error
+async function api(method, path, body) {
+  const init = { method, headers: {} };
+  if (body !== undefined) {
+    init.headers["Content-Type"] = "application/json";
+    init.body = JSON.stringify(body);
+  }
+  const response = await fetch(path, init);
+  const text = await response.text();
+  let json = null;
+  try { json = JSON.parse(text); } catch (_) { /* plain text of axum */ }
+  if (!response.ok || (json && json.status !== "OK")) {
+    throw new Error((json && (json.message || json.status)) || text || response.statusText);
+  }
+  return json ? json.body : null;
+}
+
+async function loadAccounts() {
+  accounts = (await api("GET", "/account")) || [];
+  const list = document.getElementById("account-names");
+  list.replaceChildren(...accounts.map((a) => el("option", { value: a.account_name })));
+}
+
+function table(headers, rows, numeric) {
+  return el("table", {},
+    el("thead", {}, el("tr", {}, headers.map((h, i) =>
+      el("th", { class: numeric.includes(i) ? "num" : null }, h)))),
+    el("tbody", {}, rows.map((row) => el("tr", { class: row.total ? "total" : null },
+      row.cells.map((cell, i) =>
+        el("td", { class: numeric.includes(i) ? "num" : null }, cell))))));
+}
+
+function journalTable(journals) {
+  const rows = journals.map((j) => ({
+    cells: [
+      j.date,
+      j.transaction_type,
+      j.partner ? `${j.desc} (${j.partner})` : j.desc,
+      j.debit.map((d) => el("div", {}, d.account)),
+      j.debit.map((d) => el("div", {}, amount(d.amount))),
+      j.credit.map((c) => el("div", {}, c.account)),
+      j.credit.map((c) => el("div", {}, amount(c.amount))),
+    ],
+  }));
+  return table(["日付", "種別", "摘要", "借方", "金額", "貸方", "金額"], rows, [4, 6]);
+}
+
+// ---- 仕訳
+
+function lineInput(onChange) {
+  const account = el("input", { class: "account", list: "account-names", placeholder: "勘定科目", oninput: onChange });
+  const value = el("input", { class: "amount", inputmode: "decimal", placeholder: "金額", oninput: onChange });
+  const remove = el("button", { type: "button", title: "削除" }, "×");
+  const line = el("div", { class: "line" }, account, value, remove);
+  remove.addEventListener("click", () => {
+    if (line.parentNode.querySelectorAll(".line").length > 1) line.remove();
+    onChange();
+  });
+  return line;
+}
+
+function entryForm(onPosted) {
+  const date = el("input", { type: "date", value: today(), required: true });
+  const type = el("select", {}, options(TRANSACTION_TYPES, "InTerm"));
+  const desc = el("input", { placeholder: "摘要", size: 30 });
+  const partner = el("input", { placeholder: "取引先" });
+  const debit = el("div", {});
+  const credit = el("div", {});
+  const balance = el("span", { class: "balance" });
+  const submit = el("button", { class: "primary", type: "submit" }, "登録");
+
+  const linesOf = (side) => [...side.querySelectorAll(".line")]
+    .map((line) => {
+      const [account, value] = line.querySelectorAll("input");
+      return { account: account.value.trim(), amount: parseAmount(value.value) };
+    })
+    .filter((l) => l.account || l.amount);
+
+  // the totals of both sides follow the typing
+  const update = () => {
+    const d = linesOf(debit).reduce((sum, l) => sum + l.amount, 0);
+    const c = linesOf(credit).reduce((sum, l) => sum + l.amount, 0);
+    const known = [...linesOf(debit), ...linesOf(credit)]
+      .every((l) => accounts.some((a) => a.account_name === l.account));
+    const ok = d > 0 && Math.abs(d - c) < 0.005 && known;
+    balance.className = "balance " + (ok ? "ok" : "ng");
+    balance.textContent = `借方計 ${amount(d)} / 貸方計 ${amount(c)}` +
+      (Math.abs(d - c) >= 0.005 ? ` 差額 ${amount(d - c)}` : "") +
+      (known ? "" : " (未登録の勘定科目があります)");
+    submit.disabled = !ok;
+  };
+  const reset = () => {
+    desc.value = "";
+    partner.value = "";
+    debit.replaceChildren(lineInput(update));
+    credit.replaceChildren(lineInput(update));
+    update();
+  };
+
+  const form = el("form", {
+    onsubmit: async (event) => {
+      event.preventDefault();
+      const journal = {
+        transaction_type: type.value,
+        date: date.value,
+        debit: linesOf(debit),
+        credit: linesOf(credit),
+        desc: desc.value,
+      };
+      if (partner.value.trim()) journal.partner = partner.value.trim();
+      try {
+        await api("POST", "/journal", journal);
+        showMessage(`${date.value} の仕訳を登録しました`, true);
+        reset();
+        onPosted(date.value);
+      } catch (e) {
+        showMessage(e.message, false);
+      }
+    },
+  },
+    el("div", { class: "toolbar" }, date, type, desc, partner),
+    el("div", { class: "lines" },
+      el("div", {}, el("h3", {}, "借方"), debit,
+        el("button", { type: "button", onclick: () => { debit.append(lineInput(update)); } }, "+ 行")),
+      el("div", {}, el("h3", {}, "貸方"), credit,
+        el("button", { type: "button", onclick: () => { credit.append(lineInput(update)); } }, "+ 行"))),
+    el("div", { class: "toolbar" }, submit, balance));
+  reset();
+  return form;
+}
+
+async function journalPage(page) {
+  const now = new Date();
+  let year = now.getFullYear();
+  let month = now.getMonth() + 1;
+  const title = el("h2", {});
+  const list = el("div", {});
+  const load = async () => {
+    title.textContent = `${year}年${month}月の仕訳`;
+    list.replaceChildren(journalTable((await api("GET", `/journal/${year}/${month}`)) || []));
+  };
+  const step = (delta) => {
+    month += delta;
+    if (month < 1) { month = 12; year -= 1; }
+    if (month > 12) { month = 1; year += 1; }
+    load().catch((e) => showMessage(e.message, false));
+  };
+  page.append(
+    el("section", {}, el("h2", {}, "仕訳入力"), entryForm((date) => {
+      [year, month] = date.split("-").map(Number);
+      load().catch((e) => showMessage(e.message, false));
+    })),
+    el("section", {},
+      el("div", { class: "toolbar" },
+        el("button", { onclick: () => step(-1) }, "← 前月"), title,
+        el("button", { onclick: () => step(1) }, "翌月 →")),
+      list));
+  await load();
+}
+
+// ---- 定型仕訳
+
+async function shortcutPage(page) {
+  const kind = el("select", {}, SHORTCUTS.map(([path, , label]) => el("option", { value: path }, label)));
+  const date = el("input", { type: "date", value: today() });
+  const account = el("input", { list: "account-names", placeholder: "勘定科目" });
+  const total = el("input", { class: "amount", placeholder: "税込金額" });
+  const tax = el("input", { class: "amount", placeholder: "消費税 (省略可)" });
+  const desc = el("input", { placeholder: "摘要", size: 30 });
+  const partner = el("input", { placeholder: "取引先" });
+  const preview = el("div", {});
+
+  const selected = () => SHORTCUTS.find(([path]) => path === kind.value);
+  const input = () => {
+    const body = { date: date.value, total: parseAmount(total.value), desc: desc.value };
+    if (selected()[3]) body.account = account.value.trim();
+    if (tax.value.trim()) body.tax = parseAmount(tax.value);
+    if (partner.value.trim()) body.partner = partner.value.trim();
+    return body;
+  };
+  const showPreview = async () => {
+    try {
+      const journals = await api("POST", `/journal/template/${selected()[1]}/preview`, input());
+      preview.replaceChildren(journalTable(journals || []));
+    } catch (e) {
+      preview.replaceChildren(el("p", { class: "muted" }, e.message));
+    }
+  };
+  const refresh = () => {
+    account.hidden = !selected()[3];
+    showPreview();
+  };
+  for (const field of [kind, date, account, total, tax, desc, partner]) {
+    field.addEventListener("change", refresh);
+  }
+
+  page.append(el("section", {}, el("h2", {}, "定型仕訳"),
+    el("form", {
+      class: "inline",
+      onsubmit: async (event) => {
+        event.preventDefault();
+        try {
+          await api("POST", `/journal/${kind.value}`, input());
+          showMessage(`${selected()[2]} を登録しました`, true);
+          total.value = "";
+          tax.value = "";
+          desc.value = "";
+          refresh();
+        } catch (e) {
+          showMessage(e.message, false);
+        }
+      },
+    }, kind, date, account, total, tax, desc, partner,
+      el("button", { class: "primary", type: "submit" }, "登録")),
+    preview));
+  refresh();
+}
+
+// ---- 勘定科目
+
+async function accountsPage(page) {
+  const name = el("input", { placeholder: "勘定科目名", required: true });
+  const type = el("select", {}, options(ACCOUNT_TYPES, "Expense"));
+  const list = el("div", {});
+  const draw = () => list.replaceChildren(table(["勘定科目", "区分", "貸借"],
+    accounts.map((a) => ({ cells: [a.account_name, a.account_type, a.amount_side] })), []));
+  page.append(
+    el("section", {}, el("h2", {}, "勘定科目の追加"),
+      el("form", {
+        class: "inline",
+        onsubmit: async (event) => {
+          event.preventDefault();
+          try {
+            await api("POST", "/account", { account_name: name.value.trim(), account_type: type.value });
+            showMessage(`${name.value} を追加しました`, true);
+            name.value = "";
+            await loadAccounts();
+            draw();
+          } catch (e) {
+            showMessage(e.message, false);
+          }
+        },
+      }, name, type, el("button", { class: "primary", type: "submit" }, "追加"))),
+    el("section", {}, el("h2", {}, "勘定科目"), list));
+  draw();
+}
+
+// ---- 集計
+
+async function summaryPage(page) {
+  const year = el("input", { type: "number", value: new Date().getFullYear(), style: "width: 90px" });
+  const month = el("select", {}, el("option", { value: "" }, "通年"),
+    [...Array(12).keys()].map((m) => el("option", { value: m + 1 }, `${m + 1}月`)));
+  const stage = el("select", {}, options(STAGES, "in_term"));
+  const result = el("div", {});
+  const load = async () => {
+    const period = month.value ? `${year.value}/${month.value}` : year.value;
+    const rows = (await api("GET", `/summary/${period}/${stage.value}`)) || [];
+    const debit = rows.reduce((sum, r) => sum + r.debit, 0);
+    const credit = rows.reduce((sum, r) => sum + r.credit, 0);
+    result.replaceChildren(table(["勘定科目", "借方", "貸方"], [
+      ...rows.map((r) => ({ cells: [r.account_name, amount(r.debit), amount(r.credit)] })),
+      { total: true, cells: ["合計", amount(debit), amount(credit)] },
+    ], [1, 2]));
+  };
+  for (const field of [year, month, stage]) {
+    field.addEventListener("change", () => load().catch((e) => showMessage(e.message, false)));
+  }
+  page.append(el("section", {}, el("h2", {}, "集計"),
+    el("div", { class: "toolbar" }, year, month, stage), result));
+  await load();
+}
+
+// ---- レポート
+
+function cashFlowTable(statement) {
+  const section = (label, s) => [
+    ...s.items.map((i) => ({ cells: [`${label} ${i.account_name}`, amount(i.amount)] })),
+    { total: true, cells: [`${label} 計`, amount(s.total)] },
+  ];
+  return table(["キャッシュ・フロー", "金額"], [
+    { cells: ["当期純利益", amount(statement.net_income)] },
+    ...section("営業", statement.operating),
+    ...section("投資", statement.investing),
+    ...section("財務", statement.financing),
+    { total: true, cells: ["増減", amount(statement.net_change)] },
+    { cells: ["期首残高", amount(statement.opening_cash)] },
+    { cells: ["期末残高", amount(statement.closing_cash)] },
+  ], [1]);
+}
+
+async function reportsPage(page) {
+  const year = el("input", { type: "number", value: new Date().getFullYear(), style: "width: 90px" });
+  const cashFlow = el("div", {});
+  const budget = el("div", {});
+  const compare = el("div", {});
+  const show = async (target, load) => {
+    try {
+      target.replaceChildren(await load());
+    } catch (e) {
+      target.replaceChildren(el("p", { class: "muted" }, e.message));
+    }
+  };
+  const load = () => {
+    const y = Number(year.value);
+    show(cashFlow, async () => cashFlowTable(await api("GET", `/cash_flow/${y}`)));
+    show(budget, async () => table(["勘定科目", "予算", "実績", "差異", "消化率"],
+      ((await api("GET", `/budget/${y}/report`)) || []).map((r) => ({
+        cells: [r.account_name, amount(r.budget), amount(r.actual), amount(r.variance),
+          r.consumption == null ? "" : `${amount(r.consumption)}%`],
+      })), [1, 2, 3, 4]));
+    show(compare, async () => {
+      const c = await api("GET", `/compare/${y - 1}/${y}`);
+      return table(["勘定科目", ...c.periods, "増減", "増減率"], c.rows.map((r) => ({
+        cells: [
+          r.account_name,
+          ...r.amounts.map((a) => amount(a.amount)),
+          amount(r.amounts[r.amounts.length - 1].difference),
+          r.amounts[r.amounts.length - 1].percentage == null
+            ? "" : `${amount(r.amounts[r.amounts.length - 1].percentage)}%`,
+        ],
+      })), [1, 2, 3, 4]);
+    });
+  };
+  year.addEventListener("change", load);
+  page.append(
+    el("div", { class: "toolbar" }, el("label", {}, "年度", year)),
+    el("section", {}, el("h2", {}, "キャッシュ・フロー計算書"), cashFlow),
+    el("section", {}, el("h2", {}, "予算実績"), budget),
+    el("section", {}, el("h2", {}, "前年比較"), compare));
+  load();
+}
+
+// ---- routing
+
+const PAGES = {
+  "#/journal": journalPage,
+  "#/shortcut": shortcutPage,
+  "#/accounts": accountsPage,
+  "#/summary": summaryPage,
+  "#/reports": reportsPage,
+};
+
+async function route() {
+  const hash = PAGES[location.hash] ? location.hash : "#/journal";
+  for (const link of document.querySelectorAll("nav a")) {
+    link.classList.toggle("active", link.getAttribute("href") === hash);
+  }
+  const page = document.getElementById("page");
+  page.replaceChildren();
+  showMessage("");
+  try {
+    await PAGES[hash](page);
+  } catch (e) {
+    showMessage(e.message, false);
+  }
+}
+
+window.addEventListener("hashchange", route);
+loadAccounts()
+  .catch((e) => showMessage(e.message, false))
+  .then(route);
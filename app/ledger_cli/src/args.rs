@@ -0,0 +1,314 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use clap::{
+    Args,
+    Parser,
+    Subcommand,
+    ValueEnum,
+};
+
+use ledger_db::{
+    AccountType,
+    TransactionType,
+};
+
+// the same default as database.url of the app
+const DEFAULT_DATABASE_URL: &str = "postgres://postgres:postgres@db:5432/ledger";
+
+/// Posts journals and prints the books of ledger from a terminal
+#[derive(Debug, Parser)]
+#[command(name = "ledger", version)]
+pub(crate) struct Cli {
+    /// The url of a running app such as http://localhost:2480.
+    /// The database is used directly without it.
+    #[arg(long, global = true, env = "LEDGER_API")]
+    pub api: Option<String>,
+
+    /// A token of `POST /user/me/token` sent with --api
+    #[arg(long, global = true, env = "LEDGER_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// The database used without --api
+    #[arg(
+        long,
+        global = true,
+        env = "LEDGER_DATABASE_URL",
+        default_value = DEFAULT_DATABASE_URL,
+        hide_default_value = true,
+    )]
+    pub database_url: String,
+
+    /// The user the journals are posted as without --api, one of
+    /// `ledger_app add-user`. The app takes the user of the token.
+    #[arg(long, global = true, env = "LEDGER_USER")]
+    pub user: Option<String>,
+
+    /// The first month of a fiscal year without --api, of the summaries
+    /// and of the closing journals. The app uses its own
+    /// fiscal_year.start_month.
+    #[arg(
+        long,
+        global = true,
+        env = "LEDGER_FISCAL_YEAR_START_MONTH",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=12),
+    )]
+    pub fiscal_start_month: u32,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// List or add accounts
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// Add or list journals
+    #[command(subcommand)]
+    Journal(JournalCommand),
+    /// Print the balance of each account for a year or a month
+    Summary(SummaryArgs),
+    /// Export the journals of a period
+    Export(ExportArgs),
+    /// Import the journals of a file
+    Import(ImportArgs),
+    /// Write a backup of every table
+    Backup(BackupArgs),
+    /// Replace every row of the database with a backup
+    Restore(RestoreArgs),
+    /// Browse and enter journals in the terminal
+    Tui,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum AccountCommand {
+    /// List the accounts
+    List,
+    /// Add an account
+    Add {
+        name: String,
+        /// Asset, Liability, Equity, Income, Expense, UtilDebit or
+        /// UtilCredit, or 資産, 負債 and the like
+        #[arg(long = "type", value_parser = parse_account_type)]
+        account_type: AccountType,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum JournalCommand {
+    /// Add a journal, e.g. --debit 通信費=1100 --credit 普通預金=1100
+    Add(JournalAddArgs),
+    /// List the journals of a month, this month by default
+    List {
+        /// 2026-04 and the like
+        #[arg(value_parser = parse_year_month)]
+        month: Option<(i32, u32)>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct JournalAddArgs {
+    /// Today by default
+    #[arg(long)]
+    pub date: Option<NaiveDate>,
+    #[arg(long)]
+    pub desc: String,
+    #[arg(long, required = true, value_parser = parse_posting)]
+    pub debit: Vec<(String, f32)>,
+    #[arg(long, required = true, value_parser = parse_posting)]
+    pub credit: Vec<(String, f32)>,
+    #[arg(long = "type", value_enum, default_value_t = Stage::InTerm)]
+    pub stage: Stage,
+    #[arg(long)]
+    pub partner: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SummaryArgs {
+    /// A year such as 2026 or a month such as 2026-04
+    #[arg(value_parser = parse_period)]
+    pub period: Period,
+    #[arg(long, value_enum, default_value_t = Stage::InTerm)]
+    pub stage: Stage,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ExportArgs {
+    #[arg(value_enum)]
+    pub format: Format,
+    #[arg(long)]
+    pub from: NaiveDate,
+    #[arg(long)]
+    pub to: NaiveDate,
+    /// Shift_JIS or UTF-8 for the csv formats
+    #[arg(long)]
+    pub encoding: Option<String>,
+    /// Standard output by default
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ImportArgs {
+    #[arg(value_enum)]
+    pub format: Format,
+    pub file: PathBuf,
+    /// Shift_JIS or UTF-8 for the csv formats
+    #[arg(long)]
+    pub encoding: Option<String>,
+    /// Print the journals of the file without posting them
+    #[arg(long)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BackupArgs {
+    /// Standard output by default
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct RestoreArgs {
+    pub file: PathBuf,
+    /// Refuse to restore into a database that holds journals
+    #[arg(long)]
+    pub empty: bool,
+    /// Check the backup without restoring it
+    #[arg(long)]
+    pub validate: bool,
+}
+
+// the stages of /summary and the transaction types of a journal
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub(crate) enum Stage {
+    FromPrev,
+    InTerm,
+    Kessan,
+    Soneki,
+    ToNext,
+}
+
+impl Stage {
+
+    pub fn transaction_type(&self) -> TransactionType {
+        match self {
+            Stage::FromPrev => TransactionType::FromPrev,
+            Stage::InTerm => TransactionType::InTerm,
+            Stage::Kessan => TransactionType::Kessan,
+            Stage::Soneki => TransactionType::Soneki,
+            Stage::ToNext => TransactionType::ToNext,
+        }
+    }
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            Stage::FromPrev => "from_prev",
+            Stage::InTerm => "in_term",
+            Stage::Kessan => "kessan",
+            Stage::Soneki => "soneki",
+            Stage::ToNext => "to_next",
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Period {
+    Year(i32),
+    Month(i32, u32),
+}
+
+// the formats of /export/{format} and /interop/{system}
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[value(rename_all = "lower")]
+pub(crate) enum Format {
+    Ledger,
+    Hledger,
+    Beancount,
+    Yayoi,
+    Freee,
+    MoneyForward,
+}
+
+impl Format {
+
+    // the name of the format in the paths of the app
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Ledger => "ledger",
+            Format::Hledger => "hledger",
+            Format::Beancount => "beancount",
+            Format::Yayoi => "yayoi",
+            Format::Freee => "freee",
+            Format::MoneyForward => "moneyforward",
+        }
+    }
+
+    pub fn export_path(&self) -> String {
+        match self {
+            Format::Ledger => "/export/ledger".to_string(),
+            Format::Hledger => "/export/hledger".to_string(),
+            Format::Beancount => "/export/beancount".to_string(),
+            Format::Yayoi => "/interop/yayoi/export".to_string(),
+            Format::Freee => "/interop/freee/export".to_string(),
+            Format::MoneyForward => "/interop/moneyforward/export".to_string(),
+        }
+    }
+
+    pub fn import_path(&self, preview: bool) -> String {
+        let path = match self {
+            Format::Ledger => "/import/ledger",
+            Format::Hledger => "/import/hledger",
+            Format::Beancount => "/import/beancount",
+            Format::Yayoi => "/interop/yayoi/import",
+            Format::Freee => "/interop/freee/import",
+            Format::MoneyForward => "/interop/moneyforward/import",
+        };
+        match preview {
+            true => format!("{}/preview", path),
+            false => path.to_string(),
+        }
+    }
+
+}
+
+fn parse_account_type(value: &str) -> Result<AccountType, String> {
+    AccountType::from_str(value).ok()
+        .or_else(|| AccountType::from_japanese(value))
+        .ok_or(format!("'{}' is not an account type", value))
+}
+
+fn parse_year_month(value: &str) -> Result<(i32, u32), String> {
+    let error = || format!("'{}' is not a month such as 2026-04", value);
+    let (y, m) = value.split_once('-').ok_or_else(error)?;
+    let y = y.parse::<i32>().map_err(|_| error())?;
+    let m = m.parse::<u32>().map_err(|_| error())?;
+    match (1..=12).contains(&m) {
+        true => Ok((y, m)),
+        false => Err(error()),
+    }
+}
+
+fn parse_period(value: &str) -> Result<Period, String> {
+    match value.parse::<i32>() {
+        Ok(y) => Ok(Period::Year(y)),
+        Err(_) => parse_year_month(value).map(|(y, m)| Period::Month(y, m)),
+    }
+}
+
+// 通信費=1100, with the separators of 1,100 allowed
+fn parse_posting(value: &str) -> Result<(String, f32), String> {
+    let error = || format!("'{}' is not ACCOUNT=AMOUNT", value);
+    let (account, amount) = value.rsplit_once('=').ok_or_else(error)?;
+    let amount = amount.replace(',', "").parse::<f32>()
+        .map_err(|_| error())?;
+    match account.trim().is_empty() || amount <= 0_f32 {
+        true => Err(error()),
+        false => Ok((account.trim().to_string(), amount)),
+    }
+}
@@ -0,0 +1,173 @@
+mod api;
+mod direct;
+
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::Error;
+
+use crate::args::{
+    Format,
+    Period,
+    Stage,
+};
+
+pub(crate) use api::Api;
+pub(crate) use direct::Direct;
+
+// the payloads of the app, so that both clients print the same
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Account {
+    pub account_name: String,
+    pub account_type: String,
+    pub amount_side: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AccountAmount {
+    pub account: String,
+    pub amount: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Journal {
+    pub transaction_type: String,
+    pub date: NaiveDate,
+    pub debit: Vec<AccountAmount>,
+    pub credit: Vec<AccountAmount>,
+    pub desc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partner: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Summary {
+    pub account_name: String,
+    pub debit: f32,
+    pub credit: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Preview {
+    pub journals: Vec<Journal>,
+    pub unknown_accounts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TableCount {
+    pub table: String,
+    pub rows: u64,
+}
+
+// the database through ledger_db, or a running app through its API
+pub(crate) enum Client {
+    Direct(Direct),
+    Api(Api),
+}
+
+impl Client {
+
+    pub async fn accounts(&self) -> Result<Vec<Account>, Error> {
+        match self {
+            Client::Direct(d) => d.accounts().await,
+            Client::Api(a) => a.accounts().await,
+        }
+    }
+
+    pub async fn add_account(&self, account: &Account) -> Result<(), Error> {
+        match self {
+            Client::Direct(d) => d.add_account(account).await,
+            Client::Api(a) => a.add_account(account).await,
+        }
+    }
+
+    pub async fn journals(
+        &self,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<Journal>, Error> {
+        match self {
+            Client::Direct(d) => d.journals(year, month).await,
+            Client::Api(a) => a.journals(year, month).await,
+        }
+    }
+
+    pub async fn add_journal(&self, journal: &Journal) -> Result<(), Error> {
+        match self {
+            Client::Direct(d) => d.add_journal(journal).await,
+            Client::Api(a) => a.add_journal(journal).await,
+        }
+    }
+
+    pub async fn summary(
+        &self,
+        period: Period,
+        stage: Stage,
+    ) -> Result<Vec<Summary>, Error> {
+        match self {
+            Client::Direct(d) => d.summary(period, stage).await,
+            Client::Api(a) => a.summary(period, stage).await,
+        }
+    }
+
+    // the formats are read and written by the app, or by its code
+    pub async fn export(
+        &self,
+        format: Format,
+        from: NaiveDate,
+        to: NaiveDate,
+        encoding: Option<&str>,
+    ) -> Result<Vec<u8>, Error> {
+        match self {
+            Client::Direct(d) => d.export(format, from, to, encoding).await,
+            Client::Api(a) => a.export(format, from, to, encoding).await,
+        }
+    }
+
+    pub async fn import(
+        &self,
+        format: Format,
+        body: Vec<u8>,
+        encoding: Option<&str>,
+    ) -> Result<String, Error> {
+        match self {
+            Client::Direct(d) => d.import(format, &body, encoding).await,
+            Client::Api(a) => a.import(format, body, encoding).await,
+        }
+    }
+
+    pub async fn preview(
+        &self,
+        format: Format,
+        body: Vec<u8>,
+        encoding: Option<&str>,
+    ) -> Result<Preview, Error> {
+        match self {
+            Client::Direct(d) => d.preview(format, &body, encoding).await,
+            Client::Api(a) => a.preview(format, body, encoding).await,
+        }
+    }
+
+    pub async fn backup(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            Client::Direct(d) => d.backup().await,
+            Client::Api(a) => a.backup().await,
+        }
+    }
+
+    pub async fn restore(
+        &self,
+        body: Vec<u8>,
+        empty: bool,
+        dry_run: bool,
+    ) -> Result<Vec<TableCount>, Error> {
+        match self {
+            Client::Direct(d) => d.restore(&body, empty, dry_run).await,
+            Client::Api(a) => a.restore(body, empty, dry_run).await,
+        }
+    }
+
+}
@@ -0,0 +1,203 @@
+use chrono::NaiveDate;
+use reqwest::{
+    RequestBuilder,
+    Response,
+};
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+};
+
+use crate::Error;
+
+use crate::args::{
+    Format,
+    Period,
+    Stage,
+};
+
+use super::{
+    Account,
+    Journal,
+    Preview,
+    Summary,
+    TableCount,
+};
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    status: String,
+    message: String,
+    body: Option<T>,
+}
+
+pub(crate) struct Api {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+// The message of a failed request. Most handlers answer with an
+// ApiResponse, the extractors of axum with plain text.
+async fn error_of(response: Response) -> Error {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    match serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
+        Ok(r) if !r.message.is_empty() => Error::ApiError(r.message),
+        Ok(r) => Error::ApiError(r.status),
+        Err(_) => Error::ApiError(format!("{}: {}", status, text.trim())),
+    }
+}
+
+impl Api {
+
+    pub fn new(base_url: &str, token: Option<String>) -> Self {
+        Api {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let request = match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await?;
+        match response.status().is_success() {
+            true => Ok(response),
+            false => Err(error_of(response).await),
+        }
+    }
+
+    // the message and the body of an ApiResponse
+    async fn call<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<(String, Option<T>), Error> {
+        let response: ApiResponse<T> = self.send(request).await?.json().await?;
+        match response.status.as_str() {
+            "OK" => Ok((response.message, response.body)),
+            _ => Err(Error::ApiError(response.message)),
+        }
+    }
+
+    async fn body_of<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Error> {
+        self.call::<T>(request).await?.1
+            .ok_or(Error::ApiError("the response has no body".to_string()))
+    }
+
+    pub async fn accounts(&self) -> Result<Vec<Account>, Error> {
+        self.body_of(self.http.get(self.url("/account"))).await
+    }
+
+    pub async fn add_account(&self, account: &Account) -> Result<(), Error> {
+        self.call::<()>(self.http.post(self.url("/account")).json(account))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn journals(
+        &self,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<Journal>, Error> {
+        let path = format!("/journal/{}/{}", year, month);
+        self.body_of(self.http.get(self.url(&path))).await
+    }
+
+    pub async fn add_journal(&self, journal: &Journal) -> Result<(), Error> {
+        self.call::<()>(self.http.post(self.url("/journal")).json(journal))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn summary(
+        &self,
+        period: Period,
+        stage: Stage,
+    ) -> Result<Vec<Summary>, Error> {
+        let path = match period {
+            Period::Year(y) => format!("/summary/{}/{}", y, stage.path()),
+            Period::Month(y, m)
+                => format!("/summary/{}/{}/{}", y, m, stage.path()),
+        };
+        self.body_of(self.http.get(self.url(&path))).await
+    }
+
+    pub async fn export(
+        &self,
+        format: Format,
+        from: NaiveDate,
+        to: NaiveDate,
+        encoding: Option<&str>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut query = vec![("from", from.to_string()), ("to", to.to_string())];
+        query.extend(encoding.map(|e| ("encoding", e.to_string())));
+        let request = self.http.get(self.url(&format.export_path()))
+            .query(&query);
+        Ok(self.send(request).await?.bytes().await?.to_vec())
+    }
+
+    fn import_request(
+        &self,
+        format: Format,
+        body: Vec<u8>,
+        encoding: Option<&str>,
+        preview: bool,
+    ) -> RequestBuilder {
+        self.http.post(self.url(&format.import_path(preview)))
+            .query(&[("encoding", encoding)])
+            .body(body)
+    }
+
+    pub async fn import(
+        &self,
+        format: Format,
+        body: Vec<u8>,
+        encoding: Option<&str>,
+    ) -> Result<String, Error> {
+        let request = self.import_request(format, body, encoding, false);
+        Ok(self.call::<Vec<i32>>(request).await?.0)
+    }
+
+    pub async fn preview(
+        &self,
+        format: Format,
+        body: Vec<u8>,
+        encoding: Option<&str>,
+    ) -> Result<Preview, Error> {
+        self.body_of(self.import_request(format, body, encoding, true)).await
+    }
+
+    pub async fn backup(&self) -> Result<Vec<u8>, Error> {
+        let request = self.http.get(self.url("/admin/backup"));
+        Ok(self.send(request).await?.bytes().await?.to_vec())
+    }
+
+    pub async fn restore(
+        &self,
+        body: Vec<u8>,
+        empty: bool,
+        dry_run: bool,
+    ) -> Result<Vec<TableCount>, Error> {
+        let path = match dry_run {
+            true => "/admin/restore/validate",
+            false => "/admin/restore",
+        };
+        let request = self.http.post(self.url(path))
+            .query(&[("empty", empty)])
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        self.body_of(request).await
+    }
+
+}
@@ -0,0 +1,297 @@
+use chrono::{
+    Days,
+    Local,
+    Months,
+    NaiveDate,
+};
+
+use ledger_db::{
+    AccountType,
+    AmountSide,
+    Backup,
+    Db,
+    Migration,
+    Summary as DbSummary,
+    TableRows,
+    Transaction,
+    TransactionDetail,
+    TransactionType,
+    User,
+};
+
+use ledger_app::journal_file;
+
+use crate::Error;
+
+use crate::args::{
+    Format,
+    Period,
+    Stage,
+};
+
+use super::{
+    Account,
+    AccountAmount,
+    Journal,
+    Preview,
+    Summary,
+    TableCount,
+};
+
+pub(crate) struct Direct {
+    db: Db,
+    // the journals are posted as this user, as the owner without one
+    user: Option<User>,
+    start_month: u32,
+}
+
+fn empty_if_not_found<T>(
+    result: Result<Vec<T>, ledger_db::Error>,
+) -> Result<Vec<T>, Error> {
+    match result {
+        Ok(rows) => Ok(rows),
+        Err(ledger_db::Error::RowNotFound) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl Direct {
+
+    // refuses a database the app has not migrated to this build
+    pub async fn connect(
+        database_url: &str,
+        user_name: Option<&str>,
+        start_month: u32,
+    ) -> Result<Self, Error> {
+        // Transaction::insert looks the accounts up beside its transaction
+        let db = Db::connect(
+            database_url, 2, std::time::Duration::from_secs(10),
+        ).await.map_err(|e| Error::ConnectError(e.to_string()))?;
+        Migration::check(&db).await?;
+        let user = match user_name {
+            Some(name) => match User::by_name(&db, name).await {
+                Ok(user) => Some(user),
+                Err(ledger_db::Error::RowNotFound)
+                    => return Err(Error::UserNotFound(name.to_string())),
+                Err(e) => return Err(e.into()),
+            },
+            None => None,
+        };
+        Ok(Direct { db, user, start_month })
+    }
+
+    pub async fn accounts(&self) -> Result<Vec<Account>, Error> {
+        let accounts = empty_if_not_found(
+            ledger_db::Account::all(&self.db).await
+        )?;
+        Ok(accounts.iter()
+            .map(|a| Account {
+                account_name: a.account_name.clone(),
+                account_type: a.account_type.into_japanese(),
+                amount_side: Some(
+                    a.account_type.amount_side().into_japanese()
+                ),
+            })
+            .collect())
+    }
+
+    pub async fn add_account(&self, account: &Account) -> Result<(), Error> {
+        ledger_db::Account {
+            account_id: 0,
+            account_name: account.account_name.clone(),
+            account_type: AccountType::from(&account.account_type),
+        }.insert(&self.db).await?;
+        Ok(())
+    }
+
+    pub async fn journals(
+        &self,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<Journal>, Error> {
+        let transactions = empty_if_not_found(
+            Transaction::by_month(&self.db, year, month).await
+        )?;
+        Ok(transactions.iter().map(journal_of).collect())
+    }
+
+    pub async fn add_journal(&self, journal: &Journal) -> Result<(), Error> {
+        let mut details = Vec::new();
+        for (amounts, side) in [
+            (&journal.debit, AmountSide::Debit),
+            (&journal.credit, AmountSide::Credit),
+        ] {
+            for amount in amounts {
+                details.push(self.detail_of(amount, side).await?);
+            }
+        }
+        let transaction = Transaction {
+            transaction_id: 0,
+            transaction_date: journal.date,
+            transaction_type: TransactionType::from(&journal.transaction_type),
+            description: journal.desc.clone(),
+            partner_name: journal.partner.clone(),
+            created_by: None,
+            tags: Vec::new(),
+            review_status: None,
+            open_comments: 0,
+            details,
+        }.posted_by(&self.db, self.user.as_ref(), self.start_month).await?;
+        transaction.insert(&self.db).await?;
+        Ok(())
+    }
+
+    async fn detail_of(
+        &self,
+        amount: &AccountAmount,
+        side: AmountSide,
+    ) -> Result<TransactionDetail, Error> {
+        let account_type
+            = match ledger_db::Account::by_name(&self.db, &amount.account).await {
+                Ok(a) => a.account_type,
+                Err(ledger_db::Error::RowNotFound)
+                    => return Err(Error::AccountNotFound(amount.account.clone())),
+                Err(e) => return Err(e.into()),
+            };
+        let (debit_amount, credit_amount) = match side {
+            AmountSide::Debit => (amount.amount, 0_f32),
+            AmountSide::Credit => (0_f32, amount.amount),
+        };
+        Ok(TransactionDetail {
+            account_name: amount.account.clone(),
+            account_type,
+            debit_amount,
+            credit_amount,
+            foreign: None,
+        })
+    }
+
+    // the year begins on the first day of start_month as /summary/{y}
+    fn period_of(&self, period: Period) -> Result<(NaiveDate, NaiveDate), Error> {
+        let (start, months) = match period {
+            Period::Year(y) => (NaiveDate::from_ymd_opt(y, self.start_month, 1), 12),
+            Period::Month(y, m) => (NaiveDate::from_ymd_opt(y, m, 1), 1),
+        };
+        let start = start.ok_or(Error::DateTimeError(format!("{:?}", period)))?;
+        Ok((start, start + Months::new(months) - Days::new(1)))
+    }
+
+    pub async fn summary(
+        &self,
+        period: Period,
+        stage: Stage,
+    ) -> Result<Vec<Summary>, Error> {
+        let (start, end) = self.period_of(period)?;
+        let db = &self.db;
+        let result = match stage.transaction_type() {
+            TransactionType::FromPrev => DbSummary::upto_from_prev(db, start).await,
+            TransactionType::InTerm => DbSummary::upto_in_term(db, start, end).await,
+            TransactionType::Kessan => DbSummary::upto_kessan(db, start, end).await,
+            TransactionType::Soneki => DbSummary::upto_soneki(db, start, end).await,
+            TransactionType::ToNext => DbSummary::upto_to_next(db, start, end).await,
+        };
+        Ok(empty_if_not_found(result)?.iter()
+            .map(|s| {
+                let (debit, credit) = match s.account_type.amount_side() {
+                    AmountSide::Debit => (s.debit - s.credit, 0_f32),
+                    AmountSide::Credit => (0_f32, s.credit - s.debit),
+                };
+                Summary { account_name: s.account_name.clone(), debit, credit }
+            })
+            .collect())
+    }
+
+    pub async fn export(
+        &self,
+        format: Format,
+        from: NaiveDate,
+        to: NaiveDate,
+        encoding: Option<&str>,
+    ) -> Result<Vec<u8>, Error> {
+        Ok(journal_file::export(
+            &self.db, format.name(), from, to, encoding,
+        ).await?)
+    }
+
+    // the message of the app's response to an import
+    pub async fn import(
+        &self,
+        format: Format,
+        body: &[u8],
+        encoding: Option<&str>,
+    ) -> Result<String, Error> {
+        let ids = journal_file::import(
+            &self.db,
+            self.user.clone(),
+            self.start_month,
+            format.name(),
+            body,
+            encoding,
+        ).await?;
+        Ok(format!("{} journals imported", ids.len()))
+    }
+
+    // the preview of the app, read back as its payload
+    pub async fn preview(
+        &self,
+        format: Format,
+        body: &[u8],
+        encoding: Option<&str>,
+    ) -> Result<Preview, Error> {
+        let preview = journal_file::preview(
+            &self.db, format.name(), body, encoding,
+        ).await?;
+        Ok(serde_json::from_value(serde_json::to_value(preview)?)?)
+    }
+
+    pub async fn backup(&self) -> Result<Vec<u8>, Error> {
+        let backup = Backup::dump(&self.db, Local::now().fixed_offset()).await?;
+        Ok(serde_json::to_vec(&backup)?)
+    }
+
+    // the checks of /admin/restore, without holding back the scheduler
+    // of a running app
+    pub async fn restore(
+        &self,
+        body: &[u8],
+        empty: bool,
+        dry_run: bool,
+    ) -> Result<Vec<TableCount>, Error> {
+        let backup: Backup = serde_json::from_slice(body)?;
+        let tables = backup.table_rows(
+            Migration::current_version(&self.db).await?,
+        )?;
+        if empty && TableRows::holds_journals(&self.db).await? {
+            return Err(Error::DatabaseNotEmpty);
+        }
+        let counts = TableRows::restore(&self.db, &tables, dry_run).await?;
+        Ok(counts.into_iter()
+            .map(|c| TableCount { table: c.table_name, rows: c.row_count })
+            .collect())
+    }
+
+}
+
+fn journal_of(transaction: &Transaction) -> Journal {
+    let mut debit = Vec::new();
+    let mut credit = Vec::new();
+    for d in &transaction.details {
+        let net = d.debit_amount - d.credit_amount;
+        let amount = AccountAmount {
+            account: d.account_name.clone(),
+            amount: net.abs(),
+        };
+        match net >= 0_f32 {
+            true => debit.push(amount),
+            false => credit.push(amount),
+        }
+    }
+    Journal {
+        transaction_type: transaction.transaction_type.into_japanese(),
+        date: transaction.transaction_date,
+        debit,
+        credit,
+        desc: transaction.description.clone(),
+        partner: transaction.partner_name.clone(),
+    }
+}
@@ -0,0 +1,166 @@
+mod args;
+mod client;
+mod output;
+mod tui;
+
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use chrono::{
+    Datelike,
+    Local,
+};
+use clap::Parser;
+use thiserror::Error;
+
+use args::{
+    AccountCommand,
+    Cli,
+    Command,
+    JournalAddArgs,
+    JournalCommand,
+};
+use client::{
+    Account,
+    AccountAmount,
+    Api,
+    Client,
+    Direct,
+    Journal,
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Error, Debug)]
+enum Error {
+    #[error(transparent)]
+    DataBaseError(#[from] ledger_db::Error),
+    #[error("can not connect to the database: {0}")]
+    ConnectError(String),
+    #[error(transparent)]
+    HttpError(#[from] reqwest::Error),
+    #[error("{0}")]
+    ApiError(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("account '{0}' not found")]
+    AccountNotFound(String),
+    #[error("user '{0}' not found")]
+    UserNotFound(String),
+    #[error("'{0}' can not convert to datetime")]
+    DateTimeError(String),
+    #[error("debit {0} and credit {1} do not balance")]
+    UnbalancedError(f32, f32),
+    #[error(transparent)]
+    AppError(#[from] ledger_app::Error),
+    #[error("the database already holds journals")]
+    DatabaseNotEmpty,
+}
+
+fn write_output(output: Option<&Path>, bytes: &[u8]) -> Result<(), Error> {
+    match output {
+        Some(path) => std::fs::write(path, bytes)?,
+        None => std::io::stdout().write_all(bytes)?,
+    }
+    Ok(())
+}
+
+fn journal_of(args: &JournalAddArgs) -> Result<Journal, Error> {
+    let amounts = |postings: &[(String, f32)]| postings.iter()
+        .map(|(account, amount)| AccountAmount {
+            account: account.clone(),
+            amount: *amount,
+        })
+        .collect::<Vec<AccountAmount>>();
+    let debit = args.debit.iter().map(|d| d.1).sum::<f32>();
+    let credit = args.credit.iter().map(|c| c.1).sum::<f32>();
+    if (debit - credit).abs() > 0.005 {
+        return Err(Error::UnbalancedError(debit, credit));
+    }
+    Ok(Journal {
+        transaction_type: args.stage.transaction_type().to_string(),
+        date: args.date.unwrap_or(Local::now().date_naive()),
+        debit: amounts(&args.debit),
+        credit: amounts(&args.credit),
+        desc: args.desc.clone(),
+        partner: args.partner.clone(),
+    })
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
+    let client = match &cli.api {
+        Some(url) => Client::Api(Api::new(url, cli.token.clone())),
+        None => Client::Direct(
+            Direct::connect(
+                &cli.database_url, cli.user.as_deref(), cli.fiscal_start_month,
+            ).await?
+        ),
+    };
+    match cli.command {
+        Command::Account(AccountCommand::List) => {
+            output::print_accounts(&client.accounts().await?);
+        },
+        Command::Account(AccountCommand::Add { name, account_type }) => {
+            client.add_account(&Account {
+                account_name: name,
+                account_type: account_type.to_string(),
+                amount_side: None,
+            }).await?;
+        },
+        Command::Journal(JournalCommand::Add(args)) => {
+            client.add_journal(&journal_of(&args)?).await?;
+        },
+        Command::Journal(JournalCommand::List { month }) => {
+            let today = Local::now().date_naive();
+            let (y, m) = month.unwrap_or((today.year(), today.month()));
+            output::print_journals(&client.journals(y, m).await?);
+        },
+        Command::Summary(args) => {
+            output::print_summary(
+                &client.summary(args.period, args.stage).await?
+            );
+        },
+        Command::Export(args) => {
+            let bytes = client.export(
+                args.format, args.from, args.to, args.encoding.as_deref(),
+            ).await?;
+            write_output(args.output.as_deref(), &bytes)?;
+        },
+        Command::Import(args) => {
+            let body = std::fs::read(&args.file)?;
+            let encoding = args.encoding.as_deref();
+            match args.preview {
+                true => output::print_preview(
+                    &client.preview(args.format, body, encoding).await?
+                ),
+                false => println!(
+                    "{}", client.import(args.format, body, encoding).await?
+                ),
+            }
+        },
+        Command::Backup(args) => {
+            write_output(args.output.as_deref(), &client.backup().await?)?;
+        },
+        Command::Restore(args) => {
+            let body = std::fs::read(&args.file)?;
+            output::print_table_counts(
+                &client.restore(body, args.empty, args.validate).await?
+            );
+        },
+        Command::Tui => tui::run(&client).await?,
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ledger: {}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,140 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::client::{
+    Account,
+    Journal,
+    Preview,
+    Summary,
+    TableCount,
+};
+
+// padded by the width on the terminal, 2 for each kanji
+fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+fn pad_left(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(text.width());
+    format!("{}{}", " ".repeat(fill), text)
+}
+
+// 1100 as 1,100 and 0.5 as it is
+pub(crate) fn amount(value: f32) -> String {
+    let rounded = value.round();
+    if (value - rounded).abs() > f32::EPSILON {
+        return format!("{}", value);
+    }
+    let digits = format!("{}", rounded.abs() as i64);
+    let mut text = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 { text.push(','); }
+        text.push(c);
+    }
+    match rounded < 0_f32 {
+        true => format!("-{}", text),
+        false => text,
+    }
+}
+
+fn format_rows(rows: &[Vec<String>], right_aligned: &[usize]) -> Vec<String> {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths = (0..columns)
+        .map(|c| rows.iter()
+            .filter_map(|r| r.get(c))
+            .map(|t| t.width())
+            .max()
+            .unwrap_or(0))
+        .collect::<Vec<usize>>();
+    rows.iter()
+        .map(|row| row.iter().enumerate()
+            .map(|(c, text)| match right_aligned.contains(&c) {
+                true => pad_left(text, widths[c]),
+                false => pad(text, widths[c]),
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string())
+        .collect()
+}
+
+fn print_rows(rows: &[Vec<String>], right_aligned: &[usize]) {
+    for line in format_rows(rows, right_aligned) {
+        println!("{}", line);
+    }
+}
+
+pub(crate) fn print_accounts(accounts: &[Account]) {
+    let rows = accounts.iter()
+        .map(|a| vec![
+            a.account_name.clone(),
+            a.account_type.clone(),
+            a.amount_side.clone().unwrap_or_default(),
+        ])
+        .collect::<Vec<Vec<String>>>();
+    print_rows(&rows, &[]);
+}
+
+// The debit lines on the left and the credit lines on the right, under
+// the date and the description. The lines of every journal share the
+// widths of their columns.
+pub(crate) fn print_journals(journals: &[Journal]) {
+    let mut lines = Vec::new();
+    for journal in journals {
+        let count = journal.debit.len().max(journal.credit.len());
+        for i in 0..count {
+            let debit = journal.debit.get(i);
+            let credit = journal.credit.get(i);
+            lines.push(vec![
+                debit.map(|d| d.account.clone()).unwrap_or_default(),
+                debit.map(|d| amount(d.amount)).unwrap_or_default(),
+                credit.map(|c| c.account.clone()).unwrap_or_default(),
+                credit.map(|c| amount(c.amount)).unwrap_or_default(),
+            ]);
+        }
+    }
+    let mut lines = format_rows(&lines, &[1, 3]).into_iter();
+    for journal in journals {
+        let title = match &journal.partner {
+            Some(partner) => format!("{} ({})", journal.desc, partner),
+            None => journal.desc.clone(),
+        };
+        println!("{}  {}  {}", journal.date, journal.transaction_type, title);
+        let count = journal.debit.len().max(journal.credit.len());
+        for line in lines.by_ref().take(count) {
+            println!("    {}", line);
+        }
+    }
+}
+
+// a trial balance with the totals of both sides
+pub(crate) fn print_summary(summary: &[Summary]) {
+    let mut rows = vec![vec![
+        "勘定科目".to_string(), "借方".to_string(), "貸方".to_string(),
+    ]];
+    for s in summary {
+        rows.push(vec![s.account_name.clone(), amount(s.debit), amount(s.credit)]);
+    }
+    rows.push(vec![
+        "合計".to_string(),
+        amount(summary.iter().map(|s| s.debit).sum()),
+        amount(summary.iter().map(|s| s.credit).sum()),
+    ]);
+    print_rows(&rows, &[1, 2]);
+}
+
+pub(crate) fn print_preview(preview: &Preview) {
+    print_journals(&preview.journals);
+    if !preview.unknown_accounts.is_empty() {
+        println!();
+        println!("accounts not found: {}", preview.unknown_accounts.join(", "));
+    }
+}
+
+pub(crate) fn print_table_counts(counts: &[TableCount]) {
+    let rows = counts.iter()
+        .map(|c| vec![c.table.clone(), c.rows.to_string()])
+        .collect::<Vec<Vec<String>>>();
+    print_rows(&rows, &[1]);
+}
@@ -0,0 +1,192 @@
+mod entry;
+mod journals;
+mod summary;
+
+use ratatui::{
+    crossterm::event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyEventKind,
+        KeyModifiers,
+    },
+    layout::{
+        Constraint,
+        Layout,
+        Rect,
+    },
+    style::{
+        Color,
+        Style,
+        Stylize,
+    },
+    text::Line,
+    widgets::{
+        Paragraph,
+        Tabs,
+    },
+    DefaultTerminal,
+    Frame,
+};
+
+use crate::Error;
+
+use crate::client::Client;
+
+use entry::EntryForm;
+use journals::JournalsView;
+use summary::SummaryView;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Journals,
+    Entry,
+    Summary,
+}
+
+const VIEWS: [(View, &str); 3] = [
+    (View::Journals, "F1 仕訳帳"),
+    (View::Entry, "F2 仕訳入力"),
+    (View::Summary, "F3 集計"),
+];
+
+struct Tui<'a> {
+    client: &'a Client,
+    view: View,
+    journals: JournalsView,
+    entry: EntryForm,
+    summary: SummaryView,
+    // the result of the last action, or its error
+    status: Result<String, String>,
+    quit: bool,
+}
+
+// the text of a field as typed, true when the key was taken
+fn edit(text: &mut String, key: &KeyEvent) -> bool {
+    if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+        return false;
+    }
+    match key.code {
+        KeyCode::Char(c) => text.push(c),
+        KeyCode::Backspace => { text.pop(); },
+        _ => return false,
+    }
+    true
+}
+
+impl<'a> Tui<'a> {
+
+    async fn new(client: &'a Client) -> Result<Self, Error> {
+        let accounts = client.accounts().await?.into_iter()
+            .map(|a| a.account_name)
+            .collect();
+        let mut tui = Tui {
+            client,
+            view: View::Journals,
+            journals: JournalsView::new(),
+            entry: EntryForm::new(accounts),
+            summary: SummaryView::new(),
+            status: Ok(String::new()),
+            quit: false,
+        };
+        tui.journals.load(client).await?;
+        Ok(tui)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs, body, status] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ]).areas(frame.area());
+        let selected = VIEWS.iter().position(|(v, _)| *v == self.view);
+        frame.render_widget(
+            Tabs::new(VIEWS.iter().map(|(_, title)| *title))
+                .select(selected)
+                .highlight_style(Style::new().reversed()),
+            tabs,
+        );
+        let help = match self.view {
+            View::Journals => self.journals.draw(frame, body),
+            View::Entry => self.entry.draw(frame, body),
+            View::Summary => self.summary.draw(frame, body),
+        };
+        self.draw_status(frame, status, help);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect, help: &str) {
+        let status = match &self.status {
+            Ok(message) => Line::from(message.as_str()).fg(Color::Green),
+            Err(message) => Line::from(message.as_str()).fg(Color::Red),
+        };
+        frame.render_widget(
+            Paragraph::new(vec![
+                status,
+                Line::from(format!("{}  ^Q quit", help)).dim(),
+            ]),
+            area,
+        );
+    }
+
+    async fn switch(&mut self, view: View) -> Result<String, Error> {
+        self.view = view;
+        match view {
+            View::Journals => self.journals.load(self.client).await?,
+            View::Summary => self.summary.load(self.client).await?,
+            View::Entry => (),
+        }
+        Ok(String::new())
+    }
+
+    async fn handle(&mut self, key: KeyEvent) -> Result<String, Error> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('q' | 'c') if ctrl => {
+                self.quit = true;
+                return Ok(String::new());
+            },
+            KeyCode::F(1) => return self.switch(View::Journals).await,
+            KeyCode::F(2) => return self.switch(View::Entry).await,
+            KeyCode::F(3) => return self.switch(View::Summary).await,
+            _ => (),
+        }
+        match self.view {
+            View::Journals => self.journals.handle(self.client, key).await,
+            View::Summary => self.summary.handle(self.client, key).await,
+            View::Entry => {
+                let posted = self.entry.handle(self.client, key).await?;
+                // the month of the journal posted is shown on F1
+                if let Some(date) = posted {
+                    self.journals.show_month_of(date);
+                    return Ok(format!("posted the journal of {}", date));
+                }
+                Ok(String::new())
+            },
+        }
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press { continue; }
+            match self.handle(key).await {
+                Ok(message) if message.is_empty() => (),
+                Ok(message) => self.status = Ok(message),
+                Err(e) => self.status = Err(e.to_string()),
+            }
+        }
+        Ok(())
+    }
+
+}
+
+// the terminal is given back even when a request fails
+pub(crate) async fn run(client: &Client) -> Result<(), Error> {
+    let mut tui = Tui::new(client).await?;
+    let mut terminal = ratatui::init();
+    let result = tui.run(&mut terminal).await;
+    ratatui::restore();
+    result
+}
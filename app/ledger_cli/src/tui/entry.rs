@@ -0,0 +1,337 @@
+use chrono::{
+    Local,
+    NaiveDate,
+};
+use ratatui::{
+    crossterm::event::{
+        KeyCode,
+        KeyEvent,
+        KeyModifiers,
+    },
+    layout::{
+        Constraint,
+        Layout,
+        Rect,
+    },
+    style::{
+        Color,
+        Style,
+        Stylize,
+    },
+    text::{
+        Line,
+        Span,
+        Text,
+    },
+    widgets::{
+        Block,
+        Cell,
+        List,
+        Paragraph,
+        Row,
+        Table,
+    },
+    Frame,
+};
+
+use crate::Error;
+
+use crate::args::Stage;
+use crate::client::{
+    AccountAmount,
+    Client,
+    Journal,
+};
+use crate::output::amount;
+
+use super::edit;
+
+const STAGES: [(Stage, &str); 5] = [
+    (Stage::InTerm, "期中仕訳"),
+    (Stage::FromPrev, "前期繰越"),
+    (Stage::Kessan, "決算整理"),
+    (Stage::Soneki, "損益振替"),
+    (Stage::ToNext, "次期繰越"),
+];
+
+// the date, the type, the description and the partner come before the
+// account and the amount of each line
+const HEAD_FIELDS: usize = 4;
+
+struct EntryLine {
+    debit: bool,
+    account: String,
+    amount: String,
+}
+
+impl EntryLine {
+
+    fn new(debit: bool) -> Self {
+        EntryLine { debit, account: String::new(), amount: String::new() }
+    }
+
+    fn is_blank(&self) -> bool {
+        self.account.trim().is_empty() && self.amount.trim().is_empty()
+    }
+
+    // 0 until the amount can be read, so that the totals follow the typing
+    fn value(&self) -> f32 {
+        self.amount.replace(',', "").trim().parse::<f32>().unwrap_or(0_f32)
+    }
+
+}
+
+pub(super) struct EntryForm {
+    accounts: Vec<String>,
+    date: String,
+    stage: usize,
+    desc: String,
+    partner: String,
+    lines: Vec<EntryLine>,
+    focus: usize,
+}
+
+impl EntryForm {
+
+    pub fn new(accounts: Vec<String>) -> Self {
+        EntryForm {
+            accounts,
+            date: Local::now().date_naive().to_string(),
+            stage: 0,
+            desc: String::new(),
+            partner: String::new(),
+            lines: vec![EntryLine::new(true), EntryLine::new(false)],
+            focus: 2,
+        }
+    }
+
+    fn field_count(&self) -> usize {
+        HEAD_FIELDS + self.lines.len() * 2
+    }
+
+    // the line of the focused field and whether it is the account
+    fn focused_line(&self) -> Option<(usize, bool)> {
+        let i = self.focus.checked_sub(HEAD_FIELDS)?;
+        Some((i / 2, i % 2 == 0))
+    }
+
+    fn totals(&self) -> (f32, f32) {
+        self.lines.iter().fold((0_f32, 0_f32), |(d, c), line| match line.debit {
+            true => (d + line.value(), c),
+            false => (d, c + line.value()),
+        })
+    }
+
+    // the accounts holding the text, those beginning with it first
+    fn candidates(&self) -> Vec<&str> {
+        let Some((i, true)) = self.focused_line() else { return Vec::new() };
+        let text = self.lines[i].account.trim();
+        let mut candidates = self.accounts.iter()
+            .map(|a| a.as_str())
+            .filter(|a| a.contains(text))
+            .collect::<Vec<&str>>();
+        candidates.sort_by_key(|a| !a.starts_with(text));
+        candidates
+    }
+
+    // Tab on an account not yet known takes the first candidate
+    fn complete(&mut self) -> bool {
+        let Some((i, true)) = self.focused_line() else { return false };
+        if self.accounts.contains(&self.lines[i].account) { return false; }
+        let Some(first) = self.candidates().first().map(|c| c.to_string())
+            else { return false };
+        self.lines[i].account = first;
+        true
+    }
+
+    fn add_line(&mut self, debit: bool) {
+        // the debit lines stay above the credit lines
+        let at = match debit {
+            true => self.lines.iter().take_while(|l| l.debit).count(),
+            false => self.lines.len(),
+        };
+        self.lines.insert(at, EntryLine::new(debit));
+        self.focus = HEAD_FIELDS + at * 2;
+    }
+
+    fn remove_line(&mut self) {
+        let Some((i, _)) = self.focused_line() else { return };
+        let debit = self.lines[i].debit;
+        if self.lines.iter().filter(|l| l.debit == debit).count() > 1 {
+            self.lines.remove(i);
+            self.focus = self.focus.min(self.field_count() - 1);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.desc.clear();
+        self.partner.clear();
+        self.lines = vec![EntryLine::new(true), EntryLine::new(false)];
+        self.focus = 2;
+    }
+
+    fn journal(&self) -> Result<Journal, Error> {
+        let date = NaiveDate::parse_from_str(self.date.trim(), "%Y-%m-%d")
+            .map_err(|_| Error::DateTimeError(self.date.clone()))?;
+        let mut debit = Vec::new();
+        let mut credit = Vec::new();
+        for line in self.lines.iter().filter(|l| !l.is_blank()) {
+            let account = line.account.trim().to_string();
+            if !self.accounts.contains(&account) {
+                return Err(Error::AccountNotFound(account));
+            }
+            let amount = AccountAmount { account, amount: line.value() };
+            match line.debit {
+                true => debit.push(amount),
+                false => credit.push(amount),
+            }
+        }
+        let (d, c) = self.totals();
+        if debit.is_empty() || credit.is_empty() || (d - c).abs() > 0.005 {
+            return Err(Error::UnbalancedError(d, c));
+        }
+        Ok(Journal {
+            transaction_type: STAGES[self.stage].0.transaction_type().to_string(),
+            date,
+            debit,
+            credit,
+            desc: self.desc.trim().to_string(),
+            partner: Some(self.partner.trim().to_string())
+                .filter(|p| !p.is_empty()),
+        })
+    }
+
+    fn text_of(&mut self, field: usize) -> Option<&mut String> {
+        match field {
+            0 => Some(&mut self.date),
+            1 => None,
+            2 => Some(&mut self.desc),
+            3 => Some(&mut self.partner),
+            _ => {
+                let i = field - HEAD_FIELDS;
+                let line = self.lines.get_mut(i / 2)?;
+                match i % 2 {
+                    0 => Some(&mut line.account),
+                    _ => Some(&mut line.amount),
+                }
+            },
+        }
+    }
+
+    // the date of the journal once it is posted
+    pub async fn handle(
+        &mut self,
+        client: &Client,
+        key: KeyEvent,
+    ) -> Result<Option<NaiveDate>, Error> {
+        let count = self.field_count();
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('s') => {
+                    let journal = self.journal()?;
+                    client.add_journal(&journal).await?;
+                    self.clear();
+                    return Ok(Some(journal.date));
+                },
+                KeyCode::Char('d') => self.add_line(true),
+                KeyCode::Char('k') => self.add_line(false),
+                KeyCode::Char('x') => self.remove_line(),
+                _ => (),
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Tab if self.complete() => (),
+            KeyCode::Tab | KeyCode::Down | KeyCode::Enter
+                => self.focus = (self.focus + 1) % count,
+            KeyCode::BackTab | KeyCode::Up
+                => self.focus = (self.focus + count - 1) % count,
+            KeyCode::Esc => self.clear(),
+            KeyCode::Left if self.focus == 1
+                => self.stage = (self.stage + STAGES.len() - 1) % STAGES.len(),
+            KeyCode::Right | KeyCode::Char(' ') if self.focus == 1
+                => self.stage = (self.stage + 1) % STAGES.len(),
+            _ => {
+                let focus = self.focus;
+                if let Some(text) = self.text_of(focus) {
+                    edit(text, &key);
+                }
+            },
+        }
+        Ok(None)
+    }
+
+    fn field_span(&self, field: usize, text: &str) -> Span<'static> {
+        match self.focus == field {
+            true => Span::styled(format!("{}_", text), Style::new().reversed()),
+            false => Span::raw(text.to_string()),
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) -> &'static str {
+        let [form, completion] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(28),
+        ]).areas(area);
+        let [head, lines, totals] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ]).areas(form);
+
+        let head_lines = [
+            ("日付", self.date.as_str()),
+            ("種別", STAGES[self.stage].1),
+            ("摘要", self.desc.as_str()),
+            ("取引先", self.partner.as_str()),
+        ].iter().enumerate()
+            .map(|(field, (label, text))| Line::from(vec![
+                Span::raw(format!("{:　<3} ", label)).bold(),
+                self.field_span(field, text),
+            ]))
+            .collect::<Vec<Line>>();
+        frame.render_widget(
+            Paragraph::new(head_lines).block(Block::bordered().title(" 仕訳 ")),
+            head,
+        );
+
+        let rows = self.lines.iter().enumerate()
+            .map(|(i, line)| {
+                let field = HEAD_FIELDS + i * 2;
+                Row::new(vec![
+                    Cell::from(match line.debit { true => "借方", false => "貸方" }),
+                    Cell::from(self.field_span(field, &line.account)),
+                    Cell::from(
+                        Text::from(self.field_span(field + 1, &line.amount))
+                            .right_aligned()
+                    ),
+                ])
+            })
+            .collect::<Vec<Row>>();
+        frame.render_widget(
+            Table::new(rows, [
+                Constraint::Length(4),
+                Constraint::Fill(1),
+                Constraint::Length(16),
+            ]).block(Block::bordered()),
+            lines,
+        );
+
+        let (d, c) = self.totals();
+        let balanced = d > 0_f32 && (d - c).abs() <= 0.005;
+        let color = match balanced { true => Color::Green, false => Color::Red };
+        frame.render_widget(
+            Paragraph::new(Line::from(format!(
+                "借方計 {}  貸方計 {}  差額 {}", amount(d), amount(c), amount(d - c),
+            )).fg(color)).block(Block::bordered()),
+            totals,
+        );
+
+        frame.render_widget(
+            List::new(self.candidates()).block(Block::bordered().title(" 勘定科目 ")),
+            completion,
+        );
+        "Tab next/complete  ←→ type  ^D +debit  ^K +credit  ^X -line  ^S post  Esc clear"
+    }
+
+}
@@ -0,0 +1,169 @@
+use chrono::{
+    Datelike,
+    Local,
+    Months,
+    NaiveDate,
+};
+use ratatui::{
+    crossterm::event::{
+        KeyCode,
+        KeyEvent,
+    },
+    layout::{
+        Constraint,
+        Layout,
+        Rect,
+    },
+    style::{
+        Style,
+        Stylize,
+    },
+    widgets::{
+        Block,
+        Row,
+        Table,
+        TableState,
+    },
+    Frame,
+};
+
+use crate::Error;
+
+use crate::client::{
+    AccountAmount,
+    Client,
+    Journal,
+};
+use crate::output::amount;
+
+// the journals of a month as Transaction::by_month lists them
+pub(super) struct JournalsView {
+    month: NaiveDate,
+    journals: Vec<Journal>,
+    state: TableState,
+}
+
+fn amounts_text(amounts: &[AccountAmount]) -> String {
+    amounts.iter()
+        .map(|a| format!("{} {}", a.account, amount(a.amount)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl JournalsView {
+
+    pub fn new() -> Self {
+        let today = Local::now().date_naive();
+        JournalsView {
+            month: today.with_day(1).unwrap_or(today),
+            journals: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn show_month_of(&mut self, date: NaiveDate) {
+        self.month = date.with_day(1).unwrap_or(date);
+    }
+
+    pub async fn load(&mut self, client: &Client) -> Result<(), Error> {
+        self.journals = client
+            .journals(self.month.year(), self.month.month())
+            .await?;
+        self.state.select(match self.journals.is_empty() {
+            true => None,
+            false => Some(0),
+        });
+        Ok(())
+    }
+
+    pub async fn handle(
+        &mut self,
+        client: &Client,
+        key: KeyEvent,
+    ) -> Result<String, Error> {
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.month = self.month - Months::new(1);
+                self.load(client).await?;
+            },
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.month = self.month + Months::new(1);
+                self.load(client).await?;
+            },
+            KeyCode::Char('r') => self.load(client).await?,
+            KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+            _ => (),
+        }
+        Ok(String::new())
+    }
+
+    // the list of the month above the lines of the selected journal
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) -> &'static str {
+        let [list, detail] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(8),
+        ]).areas(area);
+        let rows = self.journals.iter()
+            .map(|j| Row::new(vec![
+                j.date.to_string(),
+                j.transaction_type.clone(),
+                j.desc.clone(),
+                amounts_text(&j.debit),
+                amounts_text(&j.credit),
+            ]))
+            .collect::<Vec<Row>>();
+        let table = Table::new(rows, [
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Fill(2),
+            Constraint::Fill(3),
+            Constraint::Fill(3),
+        ])
+            .header(
+                Row::new(vec!["日付", "種別", "摘要", "借方", "貸方"]).bold()
+            )
+            .row_highlight_style(Style::new().reversed())
+            .block(Block::bordered().title(format!(
+                " {} ({} 件) ", self.month.format("%Y-%m"), self.journals.len(),
+            )));
+        frame.render_stateful_widget(table, list, &mut self.state);
+
+        let selected = self.state.selected()
+            .and_then(|i| self.journals.get(i));
+        let lines = selected
+            .map(|j| {
+                let count = j.debit.len().max(j.credit.len());
+                (0..count)
+                    .map(|i| {
+                        let debit = j.debit.get(i);
+                        let credit = j.credit.get(i);
+                        Row::new(vec![
+                            debit.map(|d| d.account.clone()).unwrap_or_default(),
+                            debit.map(|d| amount(d.amount)).unwrap_or_default(),
+                            credit.map(|c| c.account.clone()).unwrap_or_default(),
+                            credit.map(|c| amount(c.amount)).unwrap_or_default(),
+                        ])
+                    })
+                    .collect::<Vec<Row>>()
+            })
+            .unwrap_or_default();
+        let title = selected
+            .map(|j| match &j.partner {
+                Some(partner) => format!(" {} ({}) ", j.desc, partner),
+                None => format!(" {} ", j.desc),
+            })
+            .unwrap_or_default();
+        frame.render_widget(
+            Table::new(lines, [
+                Constraint::Fill(1),
+                Constraint::Length(14),
+                Constraint::Fill(1),
+                Constraint::Length(14),
+            ]).block(Block::bordered().title(title)),
+            detail,
+        );
+        "←→ month  ↑↓ select  r reload"
+    }
+
+}
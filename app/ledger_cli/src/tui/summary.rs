@@ -0,0 +1,154 @@
+use chrono::{
+    Datelike,
+    Local,
+};
+use ratatui::{
+    crossterm::event::{
+        KeyCode,
+        KeyEvent,
+    },
+    layout::{
+        Constraint,
+        Rect,
+    },
+    style::Stylize,
+    text::Text,
+    widgets::{
+        Block,
+        Cell,
+        Row,
+        Table,
+    },
+    Frame,
+};
+
+use crate::Error;
+
+use crate::args::{
+    Period,
+    Stage,
+};
+use crate::client::{
+    Client,
+    Summary,
+};
+use crate::output::amount;
+
+const STAGES: [Stage; 5] = [
+    Stage::FromPrev,
+    Stage::InTerm,
+    Stage::Kessan,
+    Stage::Soneki,
+    Stage::ToNext,
+];
+
+// the balances of a year or a month at one stage
+pub(super) struct SummaryView {
+    year: i32,
+    // None for the whole year
+    month: Option<u32>,
+    stage: usize,
+    summary: Vec<Summary>,
+}
+
+fn right(text: String) -> Cell<'static> {
+    Cell::from(Text::from(text).right_aligned())
+}
+
+impl SummaryView {
+
+    pub fn new() -> Self {
+        SummaryView {
+            year: Local::now().year(),
+            month: None,
+            stage: 1,
+            summary: Vec::new(),
+        }
+    }
+
+    fn period(&self) -> Period {
+        match self.month {
+            Some(m) => Period::Month(self.year, m),
+            None => Period::Year(self.year),
+        }
+    }
+
+    pub async fn load(&mut self, client: &Client) -> Result<(), Error> {
+        self.summary = client.summary(self.period(), STAGES[self.stage]).await?;
+        Ok(())
+    }
+
+    // a month before January is the December of the year before
+    fn step(&mut self, forward: bool) {
+        match (self.month, forward) {
+            (None, true) => self.year += 1,
+            (None, false) => self.year -= 1,
+            (Some(12), true) => (self.year, self.month) = (self.year + 1, Some(1)),
+            (Some(1), false) => (self.year, self.month) = (self.year - 1, Some(12)),
+            (Some(m), true) => self.month = Some(m + 1),
+            (Some(m), false) => self.month = Some(m - 1),
+        }
+    }
+
+    pub async fn handle(
+        &mut self,
+        client: &Client,
+        key: KeyEvent,
+    ) -> Result<String, Error> {
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => self.step(false),
+            KeyCode::Right | KeyCode::Char('l') => self.step(true),
+            KeyCode::Char('m') => {
+                self.month = match self.month {
+                    Some(_) => None,
+                    None => Some(Local::now().month()),
+                };
+            },
+            KeyCode::Tab | KeyCode::Char('s') => {
+                self.stage = (self.stage + 1) % STAGES.len();
+            },
+            KeyCode::BackTab => {
+                self.stage = (self.stage + STAGES.len() - 1) % STAGES.len();
+            },
+            KeyCode::Char('r') => (),
+            _ => return Ok(String::new()),
+        }
+        self.load(client).await?;
+        Ok(String::new())
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) -> &'static str {
+        let mut rows = self.summary.iter()
+            .map(|s| Row::new(vec![
+                Cell::from(s.account_name.clone()),
+                right(amount(s.debit)),
+                right(amount(s.credit)),
+            ]))
+            .collect::<Vec<Row>>();
+        rows.push(Row::new(vec![
+            Cell::from("合計"),
+            right(amount(self.summary.iter().map(|s| s.debit).sum())),
+            right(amount(self.summary.iter().map(|s| s.credit).sum())),
+        ]).bold());
+        let period = match self.month {
+            Some(m) => format!("{}-{:02}", self.year, m),
+            None => format!("{}", self.year),
+        };
+        let table = Table::new(rows, [
+            Constraint::Fill(1),
+            Constraint::Length(16),
+            Constraint::Length(16),
+        ])
+            .header(Row::new(vec![
+                Cell::from("勘定科目"),
+                right("借方".to_string()),
+                right("貸方".to_string()),
+            ]).bold())
+            .block(Block::bordered().title(format!(
+                " {} {} ", period, STAGES[self.stage].path(),
+            )));
+        frame.render_widget(table, area);
+        "←→ period  m year/month  Tab stage  r reload"
+    }
+
+}
@@ -1,5 +1,3 @@
-\connect ledger
-
 CREATE TABLE public.accounts (
     account_id SERIAL PRIMARY KEY,
     account_name VARCHAR(255) NOT NULL,
@@ -1,7 +1,8 @@
-\connect ledger
-
+-- the accounts are there already in a database created by the db/init
+-- scripts, which is taken as being at the version before this step
 INSERT INTO public.accounts (account_name, account_type)
-VALUES
+SELECT v.account_name, v.account_type
+FROM (VALUES
 ('(前期繰越(借方勘定用))', 'UtilDebit'),
 ('(前期繰越(貸方勘定用))', 'UtilCredit'),
 ('(次期繰越(借方勘定用))', 'UtilDebit'),
@@ -29,5 +30,8 @@ VALUES
 ('地代家賃', 'Expense'),
 ('支払手数料', 'Expense'),
 ('損益', 'Income')
+) AS v (account_name, account_type)
+WHERE NOT EXISTS (
+    SELECT 1 FROM public.accounts a WHERE a.account_name = v.account_name
+)
 ;
-
@@ -0,0 +1,14 @@
+CREATE TABLE public.budgets (
+    budget_id SERIAL PRIMARY KEY,
+    account_id INT REFERENCES accounts(account_id) ON DELETE CASCADE,
+    fiscal_year INT NOT NULL,
+    budget_month INT NOT NULL,
+    amount DECIMAL(18, 2) DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (account_id, fiscal_year, budget_month),
+    CHECK (budget_month >= 1 AND budget_month <= 12)
+);
+
+CREATE INDEX idx_budgets_fiscal_year ON budgets(fiscal_year);
+
+ALTER TABLE public.budgets OWNER TO postgres;
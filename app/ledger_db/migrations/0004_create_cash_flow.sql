@@ -0,0 +1,24 @@
+CREATE TABLE public.account_cash_flows (
+    account_id INT PRIMARY KEY REFERENCES accounts(account_id) ON DELETE CASCADE,
+    cash_flow_type VARCHAR(50) NOT NULL,  -- E.g., 'Cash', 'Operating', 'Investing', 'Financing', 'Excluded'
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.account_cash_flows OWNER TO postgres;
+
+
+-- cash on hand, which the first chart of accounts lacked
+INSERT INTO public.accounts (account_name, account_type)
+SELECT '現金', 'Asset'
+WHERE NOT EXISTS (
+    SELECT 1 FROM public.accounts WHERE account_name = '現金'
+);
+
+-- cash and the demand deposits, whichever of them the chart holds
+INSERT INTO public.account_cash_flows (account_id, cash_flow_type)
+SELECT account_id, 'Cash' FROM public.accounts
+WHERE account_name IN ('現金', '小口現金', '普通預金', '当座預金', '通知預金')
+UNION ALL
+SELECT account_id, 'Financing' FROM public.accounts
+WHERE account_name IN ('事業主貸', '事業主借', '資本金')
+;
@@ -0,0 +1,38 @@
+CREATE TABLE public.csv_mappings (
+    mapping_id SERIAL PRIMARY KEY,
+    mapping_name VARCHAR(255) NOT NULL UNIQUE,
+    encoding VARCHAR(50) NOT NULL DEFAULT 'UTF-8',  -- E.g., 'UTF-8', 'Shift_JIS'
+    skip_rows INT NOT NULL DEFAULT 1,
+    date_column INT NOT NULL,  -- 0 based column index
+    date_format VARCHAR(50) NOT NULL DEFAULT '%Y/%m/%d',
+    description_column INT NOT NULL,
+    withdrawal_column INT,  -- same as deposit_column for a signed amount column
+    deposit_column INT,
+    balance_column INT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.csv_mappings OWNER TO postgres;
+
+
+CREATE TABLE public.staged_lines (
+    staged_line_id SERIAL PRIMARY KEY,
+    import_source VARCHAR(50) NOT NULL,  -- E.g., 'BankCsv'
+    account_id INT REFERENCES accounts(account_id) ON DELETE RESTRICT,
+    line_date DATE NOT NULL,
+    description VARCHAR(255),
+    withdrawal_amount DECIMAL(18, 2) DEFAULT 0,
+    deposit_amount DECIMAL(18, 2) DEFAULT 0,
+    balance_amount DECIMAL(18, 2),
+    counter_account_id INT REFERENCES accounts(account_id) ON DELETE RESTRICT,
+    memo VARCHAR(255),
+    line_status VARCHAR(50) NOT NULL DEFAULT 'Draft',  -- E.g., 'Draft', 'Posted', 'Discarded'
+    transaction_id INT REFERENCES transactions(transaction_id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (withdrawal_amount = 0 OR deposit_amount = 0)
+);
+
+CREATE INDEX idx_staged_lines_account_id ON staged_lines(account_id);
+CREATE INDEX idx_staged_lines_status ON staged_lines(line_status);
+
+ALTER TABLE public.staged_lines OWNER TO postgres;
@@ -0,0 +1,16 @@
+ALTER TABLE public.staged_lines
+    ADD COLUMN external_id VARCHAR(512);  -- id of the line in its statement, used to skip duplicates
+
+CREATE UNIQUE INDEX idx_staged_lines_external_id ON staged_lines(account_id, external_id);
+
+
+CREATE TABLE public.credit_cards (
+    card_id SERIAL PRIMARY KEY,
+    card_name VARCHAR(255) NOT NULL UNIQUE,
+    account_id INT REFERENCES accounts(account_id) ON DELETE RESTRICT,  -- 未払金 sub-account of the card
+    bank_account_id INT REFERENCES accounts(account_id) ON DELETE RESTRICT,  -- account the monthly payment is debited from
+    mapping_id INT REFERENCES csv_mappings(mapping_id) ON DELETE RESTRICT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.credit_cards OWNER TO postgres;
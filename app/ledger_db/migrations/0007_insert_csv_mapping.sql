@@ -0,0 +1,9 @@
+-- statement layouts of the major card issuers at the time of writing,
+-- update through /import/bank/mapping when an issuer changes its layout.
+INSERT INTO public.csv_mappings
+    (mapping_name, encoding, skip_rows, date_column, date_format,
+    description_column, withdrawal_column, deposit_column, balance_column)
+VALUES
+('楽天カード', 'UTF-8', 1, 0, '%Y/%m/%d', 1, 4, NULL, NULL),
+('三井住友カード', 'Shift_JIS', 1, 0, '%Y/%m/%d', 1, 2, NULL, NULL)
+;
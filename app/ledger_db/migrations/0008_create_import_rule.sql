@@ -0,0 +1,38 @@
+CREATE TABLE public.partners (
+    partner_id SERIAL PRIMARY KEY,
+    partner_name VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.partners OWNER TO postgres;
+
+
+ALTER TABLE public.transactions
+    ADD COLUMN partner_id INT REFERENCES partners(partner_id) ON DELETE RESTRICT;
+
+ALTER TABLE public.staged_lines
+    ADD COLUMN tax_code VARCHAR(50),  -- E.g., 'Taxable10', 'Taxable8', 'NonTaxable', 'OutOfScope'
+    ADD COLUMN partner_id INT REFERENCES partners(partner_id) ON DELETE SET NULL,
+    ADD COLUMN rule_id INT;  -- rule that proposed the assignment
+
+
+CREATE TABLE public.import_rules (
+    rule_id SERIAL PRIMARY KEY,
+    rule_name VARCHAR(255) NOT NULL,
+    priority INT NOT NULL DEFAULT 100,  -- smaller fires first
+    match_type VARCHAR(50) NOT NULL DEFAULT 'Substring',  -- E.g., 'Substring', 'Regex'
+    pattern VARCHAR(255),
+    min_amount DECIMAL(18, 2),
+    max_amount DECIMAL(18, 2),
+    source_account_id INT REFERENCES accounts(account_id) ON DELETE CASCADE,
+    partner_id INT REFERENCES partners(partner_id) ON DELETE CASCADE,
+    counter_account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    tax_code VARCHAR(50),
+    description VARCHAR(255),
+    learned BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_import_rules_priority ON import_rules(priority);
+
+ALTER TABLE public.import_rules OWNER TO postgres;
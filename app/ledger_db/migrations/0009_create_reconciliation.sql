@@ -0,0 +1,21 @@
+CREATE TABLE public.reconciliations (
+    reconciliation_id SERIAL PRIMARY KEY,
+    account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    statement_balance DECIMAL(18, 2) NOT NULL,  -- ending balance on the bank statement
+    reconcile_status VARCHAR(50) NOT NULL DEFAULT 'Open',  -- E.g., 'Open', 'Closed'
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (account_id, period_end),
+    CHECK (period_start <= period_end)
+);
+
+ALTER TABLE public.reconciliations OWNER TO postgres;
+
+
+ALTER TABLE public.transaction_details
+    ADD COLUMN reconciled BOOLEAN NOT NULL DEFAULT FALSE,  -- ticked off against the bank statement
+    ADD COLUMN reconciliation_id INT REFERENCES reconciliations(reconciliation_id) ON DELETE SET NULL;
+
+ALTER TABLE public.staged_lines
+    ADD COLUMN transaction_detail_id INT REFERENCES transaction_details(transaction_detail_id) ON DELETE SET NULL;  -- book line the statement line is reconciled with
@@ -0,0 +1,56 @@
+ALTER TABLE public.partners
+    ADD COLUMN bank_code CHAR(4),  -- bank account transfers to the partner are paid into
+    ADD COLUMN bank_name VARCHAR(15),  -- half-width kana as in the Zengin records
+    ADD COLUMN branch_code CHAR(3),
+    ADD COLUMN branch_name VARCHAR(15),
+    ADD COLUMN deposit_kind VARCHAR(50),  -- E.g., 'Ordinary', 'Current', 'Savings'
+    ADD COLUMN account_number VARCHAR(7),
+    ADD COLUMN account_holder VARCHAR(30),
+    ADD COLUMN fee_bearer VARCHAR(50) NOT NULL DEFAULT 'Payer';  -- E.g., 'Payer', 'Payee'
+
+
+CREATE TABLE public.transfer_sources (
+    source_id SERIAL PRIMARY KEY,
+    account_id INT NOT NULL UNIQUE REFERENCES accounts(account_id) ON DELETE CASCADE,
+    requester_code VARCHAR(10) NOT NULL,  -- 委託者コード given by the bank
+    requester_name VARCHAR(40) NOT NULL,
+    bank_code CHAR(4) NOT NULL,
+    bank_name VARCHAR(15) NOT NULL,
+    branch_code CHAR(3) NOT NULL,
+    branch_name VARCHAR(15) NOT NULL,
+    deposit_kind VARCHAR(50) NOT NULL,
+    account_number VARCHAR(7) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.transfer_sources OWNER TO postgres;
+
+
+CREATE TABLE public.payments (
+    payment_id SERIAL PRIMARY KEY,
+    source_id INT NOT NULL REFERENCES transfer_sources(source_id) ON DELETE RESTRICT,
+    transfer_date DATE NOT NULL,
+    fee_amount DECIMAL(18, 2) NOT NULL DEFAULT 0,  -- bank fee per transfer
+    payment_status VARCHAR(50) NOT NULL DEFAULT 'Pending',  -- E.g., 'Pending', 'Executed', 'Cancelled'
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.payments OWNER TO postgres;
+
+
+CREATE TABLE public.payment_items (
+    payment_item_id SERIAL PRIMARY KEY,
+    payment_id INT NOT NULL REFERENCES payments(payment_id) ON DELETE CASCADE,
+    transaction_detail_id INT NOT NULL REFERENCES transaction_details(transaction_detail_id) ON DELETE RESTRICT,  -- the payable paid
+    partner_id INT NOT NULL REFERENCES partners(partner_id) ON DELETE RESTRICT,
+    amount DECIMAL(18, 2) NOT NULL,
+    transaction_id INT REFERENCES transactions(transaction_id) ON DELETE SET NULL,  -- payment journal once executed
+    cancelled BOOLEAN NOT NULL DEFAULT FALSE,  -- follows the payment's cancellation
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_payment_items_detail ON payment_items(transaction_detail_id);
+-- a payable is paid by one payment at most, unless it is cancelled
+CREATE UNIQUE INDEX idx_payment_items_paid ON payment_items(transaction_detail_id) WHERE NOT cancelled;
+
+ALTER TABLE public.payment_items OWNER TO postgres;
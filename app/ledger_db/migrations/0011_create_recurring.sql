@@ -0,0 +1,48 @@
+CREATE TABLE public.recurring_entries (
+    recurring_id SERIAL PRIMARY KEY,
+    recurring_name VARCHAR(255) NOT NULL UNIQUE,
+    description VARCHAR(255),
+    partner_id INT REFERENCES partners(partner_id) ON DELETE SET NULL,
+    frequency VARCHAR(50) NOT NULL DEFAULT 'Monthly',  -- E.g., 'Monthly', 'Quarterly', 'HalfYearly', 'Yearly'
+    day_of_month INT NOT NULL CHECK (day_of_month BETWEEN 1 AND 31),  -- 31 is the end of the month
+    business_day VARCHAR(50) NOT NULL DEFAULT 'Following',  -- E.g., 'AsIs', 'Following', 'Preceding', 'ModifiedFollowing'
+    start_date DATE NOT NULL,
+    end_date DATE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.recurring_entries OWNER TO postgres;
+
+
+CREATE TABLE public.recurring_lines (
+    recurring_line_id SERIAL PRIMARY KEY,
+    recurring_id INT NOT NULL REFERENCES recurring_entries(recurring_id) ON DELETE CASCADE,
+    account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE RESTRICT,
+    debit_amount DECIMAL(18, 2) DEFAULT 0,
+    credit_amount DECIMAL(18, 2) DEFAULT 0,
+    CHECK (debit_amount = 0 OR credit_amount = 0)
+);
+
+ALTER TABLE public.recurring_lines OWNER TO postgres;
+
+
+CREATE TABLE public.recurring_postings (
+    recurring_posting_id SERIAL PRIMARY KEY,
+    recurring_id INT NOT NULL REFERENCES recurring_entries(recurring_id) ON DELETE CASCADE,
+    scheduled_date DATE NOT NULL,  -- the occurrence before the business day adjustment
+    transaction_id INT REFERENCES transactions(transaction_id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (recurring_id, scheduled_date)
+);
+
+ALTER TABLE public.recurring_postings OWNER TO postgres;
+
+
+CREATE TABLE public.holidays (
+    holiday_date DATE PRIMARY KEY,  -- bank holidays besides Saturdays and Sundays
+    holiday_name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.holidays OWNER TO postgres;
@@ -0,0 +1,23 @@
+CREATE TABLE public.journal_templates (
+    template_id SERIAL PRIMARY KEY,
+    template_name VARCHAR(255) NOT NULL UNIQUE,
+    transaction_type VARCHAR(50) NOT NULL DEFAULT 'InTerm',
+    description VARCHAR(255),
+    tax_code VARCHAR(50),  -- splits the tax off the total when none is given
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.journal_templates OWNER TO postgres;
+
+
+CREATE TABLE public.journal_template_lines (
+    template_line_id SERIAL PRIMARY KEY,
+    template_id INT NOT NULL REFERENCES journal_templates(template_id) ON DELETE CASCADE,
+    side VARCHAR(50) NOT NULL,  -- E.g., 'Debit', 'Credit'
+    account_id INT REFERENCES accounts(account_id) ON DELETE RESTRICT,  -- a fixed account
+    account_param VARCHAR(255),  -- or the name of the input giving the account
+    amount_source VARCHAR(50) NOT NULL DEFAULT 'Total',  -- E.g., 'Total', 'TotalExTax', 'Tax'
+    CHECK (account_id IS NOT NULL OR account_param IS NOT NULL)
+);
+
+ALTER TABLE public.journal_template_lines OWNER TO postgres;
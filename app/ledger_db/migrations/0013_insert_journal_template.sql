@@ -0,0 +1,96 @@
+-- the shortcuts of /journal, posted through /journal/template/{name}.
+-- 'account' lines take the account of the input.
+INSERT INTO public.journal_templates
+    (template_name, transaction_type, description)
+VALUES
+('buy_by_owner', 'InTerm', '事業主借で購入'),
+('buy_by_bank', 'InTerm', '普通預金で購入'),
+('buy_by_kaikakekin', 'InTerm', '買掛金で購入'),
+('buy_by_maebaraikin', 'InTerm', '前払金で購入'),
+('sell_by_bank', 'InTerm', '普通預金で売上'),
+('sell_by_urikakekin', 'InTerm', '売掛金で売上'),
+('sell_by_maeukekin', 'InTerm', '前受金で売上'),
+('bank_to_owner', 'InTerm', '普通預金から事業主貸'),
+('bank_from_owner', 'InTerm', '事業主借から普通預金'),
+('kessan_to_misyuukin', 'Kessan', '未収金の計上'),
+('kessan_to_mibaraikin', 'Kessan', '未払金の計上'),
+('kessan_sousai_syouhizei', 'Kessan', '消費税の相殺'),
+('kessan_sousai_owner', 'Kessan', '事業主貸借の相殺'),
+('from_prev_debit', 'FromPrev', '前期繰越(借方)'),
+('from_prev_credit', 'FromPrev', '前期繰越(貸方)'),
+('soneki_income', 'Soneki', '収益の損益振替'),
+('soneki_expense', 'Soneki', '費用の損益振替'),
+('to_next_to_shihonkin_plus', 'ToNext', '資本金への振替(増加)'),
+('to_next_to_shihonkin_minus', 'ToNext', '資本金への振替(減少)'),
+('to_next_debit', 'ToNext', '次期繰越(借方)'),
+('to_next_credit', 'ToNext', '次期繰越(貸方)')
+;
+
+INSERT INTO public.journal_template_lines
+    (template_id, side, account_id, account_param, amount_source)
+SELECT
+    t.template_id,
+    v.side,
+    a.account_id,
+    v.account_param,
+    v.amount_source
+FROM (
+    VALUES
+    ('buy_by_owner', 1, 'Debit', NULL, 'account', 'TotalExTax'),
+    ('buy_by_owner', 2, 'Debit', '仮払消費税', NULL, 'Tax'),
+    ('buy_by_owner', 3, 'Credit', '事業主借', NULL, 'Total'),
+    ('buy_by_bank', 1, 'Debit', NULL, 'account', 'TotalExTax'),
+    ('buy_by_bank', 2, 'Debit', '仮払消費税', NULL, 'Tax'),
+    ('buy_by_bank', 3, 'Credit', '普通預金', NULL, 'Total'),
+    ('buy_by_kaikakekin', 1, 'Debit', NULL, 'account', 'TotalExTax'),
+    ('buy_by_kaikakekin', 2, 'Debit', '仮払消費税', NULL, 'Tax'),
+    ('buy_by_kaikakekin', 3, 'Credit', '買掛金', NULL, 'Total'),
+    ('buy_by_maebaraikin', 1, 'Debit', NULL, 'account', 'TotalExTax'),
+    ('buy_by_maebaraikin', 2, 'Debit', '仮払消費税', NULL, 'Tax'),
+    ('buy_by_maebaraikin', 3, 'Credit', '前払金', NULL, 'Total'),
+    ('sell_by_bank', 1, 'Debit', '普通預金', NULL, 'Total'),
+    ('sell_by_bank', 2, 'Credit', NULL, 'account', 'TotalExTax'),
+    ('sell_by_bank', 3, 'Credit', '仮受消費税', NULL, 'Tax'),
+    ('sell_by_urikakekin', 1, 'Debit', '売掛金', NULL, 'Total'),
+    ('sell_by_urikakekin', 2, 'Credit', NULL, 'account', 'TotalExTax'),
+    ('sell_by_urikakekin', 3, 'Credit', '仮受消費税', NULL, 'Tax'),
+    ('sell_by_maeukekin', 1, 'Debit', '前受金', NULL, 'Total'),
+    ('sell_by_maeukekin', 2, 'Credit', NULL, 'account', 'TotalExTax'),
+    ('sell_by_maeukekin', 3, 'Credit', '仮受消費税', NULL, 'Tax'),
+    ('bank_to_owner', 1, 'Debit', '事業主貸', NULL, 'Total'),
+    ('bank_to_owner', 2, 'Credit', '普通預金', NULL, 'Total'),
+    ('bank_from_owner', 1, 'Debit', '普通預金', NULL, 'Total'),
+    ('bank_from_owner', 2, 'Credit', '事業主借', NULL, 'Total'),
+    ('kessan_to_misyuukin', 1, 'Debit', '未収金', NULL, 'Total'),
+    ('kessan_to_misyuukin', 2, 'Credit', NULL, 'account', 'Total'),
+    ('kessan_to_mibaraikin', 1, 'Debit', NULL, 'account', 'Total'),
+    ('kessan_to_mibaraikin', 2, 'Credit', '未払金', NULL, 'Total'),
+    ('kessan_sousai_syouhizei', 1, 'Debit', '仮受消費税', NULL, 'Total'),
+    ('kessan_sousai_syouhizei', 2, 'Credit', '仮払消費税', NULL, 'Total'),
+    ('kessan_sousai_owner', 1, 'Debit', '事業主借', NULL, 'Total'),
+    ('kessan_sousai_owner', 2, 'Credit', '事業主貸', NULL, 'Total'),
+    ('from_prev_debit', 1, 'Debit', NULL, 'account', 'Total'),
+    ('from_prev_debit', 2, 'Credit', '(前期繰越(借方勘定用))', NULL, 'Total'),
+    ('from_prev_credit', 1, 'Debit', '(前期繰越(貸方勘定用))', NULL, 'Total'),
+    ('from_prev_credit', 2, 'Credit', NULL, 'account', 'Total'),
+    ('soneki_income', 1, 'Debit', NULL, 'account', 'Total'),
+    ('soneki_income', 2, 'Credit', '損益', NULL, 'Total'),
+    ('soneki_expense', 1, 'Debit', '損益', NULL, 'Total'),
+    ('soneki_expense', 2, 'Credit', NULL, 'account', 'Total'),
+    ('to_next_to_shihonkin_plus', 1, 'Debit', NULL, 'account', 'Total'),
+    ('to_next_to_shihonkin_plus', 2, 'Credit', '資本金', NULL, 'Total'),
+    ('to_next_to_shihonkin_minus', 1, 'Debit', '資本金', NULL, 'Total'),
+    ('to_next_to_shihonkin_minus', 2, 'Credit', NULL, 'account', 'Total'),
+    ('to_next_debit', 1, 'Debit', '(次期繰越(借方勘定用))', NULL, 'Total'),
+    ('to_next_debit', 2, 'Credit', NULL, 'account', 'Total'),
+    ('to_next_credit', 1, 'Debit', NULL, 'account', 'Total'),
+    ('to_next_credit', 2, 'Credit', '(次期繰越(貸方勘定用))', NULL, 'Total')
+) AS v (template_name, line_order, side, account_name, account_param, amount_source)
+    INNER JOIN public.journal_templates t
+    ON v.template_name = t.template_name
+    LEFT JOIN public.accounts a
+    ON v.account_name = a.account_name
+ORDER BY
+    t.template_id ASC,
+    v.line_order ASC
+;
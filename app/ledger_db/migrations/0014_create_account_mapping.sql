@@ -0,0 +1,12 @@
+CREATE TABLE public.account_mappings (
+    account_mapping_id SERIAL PRIMARY KEY,
+    external_system VARCHAR(50) NOT NULL,  -- 'Yayoi', 'Freee' or 'MoneyForward'
+    account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    external_account VARCHAR(255) NOT NULL,  -- 勘定科目 of the other system
+    external_sub_account VARCHAR(255),  -- 補助科目
+    tax_category VARCHAR(255),  -- 税区分
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (external_system, account_id)
+);
+
+ALTER TABLE public.account_mappings OWNER TO postgres;
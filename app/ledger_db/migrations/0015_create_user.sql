@@ -0,0 +1,27 @@
+CREATE TABLE public.users (
+    user_id SERIAL PRIMARY KEY,
+    user_name VARCHAR(255) NOT NULL UNIQUE,
+    password_hash VARCHAR(255) NOT NULL,  -- argon2 in the PHC string format
+    user_role VARCHAR(50) NOT NULL,  -- E.g., 'Owner', 'Bookkeeper', 'Accountant'
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.users OWNER TO postgres;
+
+
+CREATE TABLE public.api_tokens (
+    api_token_id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    token_name VARCHAR(255) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,  -- SHA-256 of the token in hex, the token itself is not kept
+    last_used_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.api_tokens OWNER TO postgres;
+
+
+-- the user the journal was posted by, NULL for the scheduler and for
+-- journals posted before users existed
+ALTER TABLE public.transactions
+    ADD COLUMN created_by INT REFERENCES users(user_id) ON DELETE SET NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE public.share_links (
+    share_link_id SERIAL PRIMARY KEY,
+    share_name VARCHAR(255) NOT NULL,  -- who the link was given to
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    scopes VARCHAR(255) NOT NULL,  -- E.g., 'journals,general_ledger,trial_balance,financial_statements'
+    expires_on DATE NOT NULL,  -- the last day the link works
+    revoked_at TIMESTAMP,
+    created_by INT REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (period_start <= period_end)
+);
+
+ALTER TABLE public.share_links OWNER TO postgres;
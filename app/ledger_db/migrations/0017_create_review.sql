@@ -0,0 +1,18 @@
+-- NULL while nobody has asked about the transaction
+ALTER TABLE public.transactions
+    ADD COLUMN review_status VARCHAR(16)
+    CHECK (review_status IN ('needs_review', 'reviewed'));
+
+CREATE TABLE public.transaction_comments (
+    transaction_comment_id SERIAL PRIMARY KEY,
+    transaction_id INT NOT NULL REFERENCES transactions(transaction_id) ON DELETE CASCADE,
+    comment_text TEXT NOT NULL,
+    resolved BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by INT REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE public.transaction_comments OWNER TO postgres;
+
+CREATE INDEX transaction_comments_transaction_id_idx
+    ON public.transaction_comments (transaction_id);
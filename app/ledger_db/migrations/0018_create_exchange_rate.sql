@@ -0,0 +1,29 @@
+-- the amount in the currency of the invoice and the rate the yen
+-- amount was converted at, NULL for the lines in yen
+ALTER TABLE public.transaction_details
+    ADD COLUMN currency CHAR(3),
+    ADD COLUMN foreign_amount DECIMAL(18, 2),
+    ADD COLUMN exchange_rate DECIMAL(18, 6),
+    ADD CHECK (
+        (currency IS NULL AND foreign_amount IS NULL AND exchange_rate IS NULL)
+        OR (currency IS NOT NULL AND foreign_amount IS NOT NULL AND exchange_rate IS NOT NULL)
+    );
+
+-- TTM of a currency in yen, e.g., 'USD', 2026-03-31, 149.52
+CREATE TABLE public.exchange_rates (
+    currency CHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate DECIMAL(18, 6) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (currency, rate_date),
+    CHECK (rate > 0)
+);
+
+ALTER TABLE public.exchange_rates OWNER TO postgres;
+
+-- the gains and the losses of the year-end revaluation
+INSERT INTO public.accounts (account_name, account_type)
+SELECT '為替差損益', 'Income'
+WHERE NOT EXISTS (
+    SELECT 1 FROM public.accounts WHERE account_name = '為替差損益'
+);
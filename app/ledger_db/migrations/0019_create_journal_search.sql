@@ -0,0 +1,11 @@
+-- trigrams find a part of a description in japanese, which has no
+-- spaces between the words for a full-text search to split on
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE public.transactions
+    ADD COLUMN tags VARCHAR(50)[] NOT NULL DEFAULT '{}';  -- E.g., '{出張,2026春}'
+
+CREATE INDEX idx_transactions_description_trgm
+    ON transactions USING GIN (description gin_trgm_ops);
+CREATE INDEX idx_transactions_tags ON transactions USING GIN (tags);
+CREATE INDEX idx_transactions_partner_id ON transactions(partner_id);
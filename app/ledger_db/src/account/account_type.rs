@@ -1,7 +1,16 @@
 use std::convert::From;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
 pub enum AmountSide {
     Debit,  // 借方
     Credit,  // 貸方
@@ -24,6 +33,12 @@ impl AmountSide {
         }
     }
 
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        AmountSide::from_str(value).ok()
+            .or_else(|| AmountSide::from_japanese(value))
+    }
+
 }
 
 #[derive(
@@ -101,3 +116,17 @@ impl From<&String> for AccountType {
 
 }
 
+
+impl From<&String> for AmountSide {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        AmountSide::from_str(value)
+        .unwrap_or_else(|_| {
+            AmountSide::from_japanese(value)
+            .unwrap_or(AmountSide::Debit)
+        })
+    }
+
+}
@@ -0,0 +1,17 @@
+mod account_mapping_type;
+mod insert;
+mod select;
+mod delete;
+
+pub use account_mapping_type::*;
+
+// the name an account goes by in another accounting system, for the
+// accounts whose names differ between the two
+#[derive(Debug)]
+pub struct AccountMapping {
+    pub external_system: ExternalSystem,
+    pub account_name: String,
+    pub external_account: String,
+    pub external_sub_account: Option<String>,
+    pub tax_category: Option<String>,
+}
@@ -0,0 +1,55 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum ExternalSystem {
+    Yayoi,  // 弥生会計
+    Freee,  // freee会計
+    MoneyForward,  // マネーフォワード クラウド会計
+}
+
+impl ExternalSystem {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            ExternalSystem::Yayoi => "弥生会計".to_string(),
+            ExternalSystem::Freee => "freee会計".to_string(),
+            ExternalSystem::MoneyForward
+                => "マネーフォワード クラウド会計".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "弥生会計" | "弥生" => Some(ExternalSystem::Yayoi),
+            "freee会計" | "freee" => Some(ExternalSystem::Freee),
+            "マネーフォワード クラウド会計" | "マネーフォワード" | "MF"
+                => Some(ExternalSystem::MoneyForward),
+            _ => None,
+        }
+    }
+
+}
+
+impl From<&String> for ExternalSystem {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        ExternalSystem::from_str(value)
+        .unwrap_or_else(|_| {
+            ExternalSystem::from_japanese(value)
+            .unwrap_or(ExternalSystem::Yayoi)
+        })
+    }
+
+}
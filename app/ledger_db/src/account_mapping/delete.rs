@@ -0,0 +1,39 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    AccountMapping,
+    ExternalSystem,
+};
+
+impl AccountMapping {
+
+    pub async fn delete(
+        db: &Db,
+        external_system: &ExternalSystem,
+        account_name: &str,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM account_mappings
+            WHERE
+                external_system = $1
+                AND account_id = (
+                    SELECT account_id FROM accounts WHERE account_name = $2
+                )
+            "#
+        )
+        .bind(external_system.to_string())
+        .bind(account_name)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -0,0 +1,40 @@
+use crate::{
+    Db,
+    Error,
+    staged_line::account_id_of,
+};
+
+use super::AccountMapping;
+
+impl AccountMapping {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<(), Error> {
+        let account_id = account_id_of(db, &self.account_name).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO account_mappings
+                (external_system, account_id, external_account,
+                external_sub_account, tax_category)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (external_system, account_id)
+            DO UPDATE SET
+                external_account = EXCLUDED.external_account,
+                external_sub_account = EXCLUDED.external_sub_account,
+                tax_category = EXCLUDED.tax_category
+            "#
+        )
+        .bind(self.external_system.to_string())
+        .bind(account_id)
+        .bind(&self.external_account)
+        .bind(&self.external_sub_account)
+        .bind(&self.tax_category)
+        .execute(&db.conn)
+        .await?;
+
+        Ok(())
+    }
+
+}
@@ -0,0 +1,66 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    AccountMapping,
+    ExternalSystem,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct AccountMappingSelectResult {
+    external_system: String,
+    account_name: String,
+    external_account: String,
+    external_sub_account: Option<String>,
+    tax_category: Option<String>,
+}
+
+impl AccountMapping {
+
+    pub async fn by_system(
+        db: &Db,
+        external_system: &ExternalSystem,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, AccountMappingSelectResult>(
+            r#"
+            SELECT
+                m.external_system,
+                a.account_name,
+                m.external_account,
+                m.external_sub_account,
+                m.tax_category
+            FROM account_mappings m
+                INNER JOIN accounts a
+                ON m.account_id = a.account_id
+            WHERE m.external_system = $1
+            ORDER BY
+                m.account_id ASC
+            "#
+        )
+        .bind(external_system.to_string());
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<AccountMapping>>())
+    }
+
+}
+
+impl From<&AccountMappingSelectResult> for AccountMapping {
+
+    fn from(
+        value: &AccountMappingSelectResult,
+    ) -> Self {
+        AccountMapping {
+            external_system: (&value.external_system).into(),
+            account_name: value.account_name.clone(),
+            external_account: value.external_account.clone(),
+            external_sub_account: value.external_sub_account.clone(),
+            tax_category: value.tax_category.clone(),
+        }
+    }
+
+}
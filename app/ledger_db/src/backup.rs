@@ -0,0 +1,20 @@
+mod backup_file;
+mod select;
+mod restore;
+
+pub use backup_file::*;
+
+// The rows of a table as a JSON array, one object per row. Every table
+// of the schema is dumped, so tables added later are backed up without
+// changes here.
+#[derive(Debug)]
+pub struct TableRows {
+    pub table_name: String,
+    pub rows: String,
+}
+
+#[derive(Debug)]
+pub struct TableCount {
+    pub table_name: String,
+    pub row_count: u64,
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::value::RawValue;
+
+use crate::{
+    Db,
+    Error,
+    Migration,
+};
+
+use super::TableRows;
+
+pub const BACKUP_FORMAT: &str = "ledger-backup";
+// raised whenever the set of tables changes
+pub const BACKUP_VERSION: u32 = 2;
+
+// The rows of each table as JSON objects, keyed by the table name, of
+// /admin/backup and `ledger backup` alike. Restoring checks the format
+// and the version before anything else. A backup of an older schema is
+// restored with the defaults of the columns added since.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub schema_version: i64,
+    pub created_at: DateTime<FixedOffset>,
+    pub tables: BTreeMap<String, Box<RawValue>>,
+}
+
+impl Backup {
+
+    pub async fn dump(
+        db: &Db,
+        created_at: DateTime<FixedOffset>,
+    ) -> Result<Self, Error> {
+        let schema_version = Migration::current_version(db).await?;
+        let mut tables = BTreeMap::new();
+        for table in TableRows::dump(db).await? {
+            let rows = RawValue::from_string(table.rows)
+                .map_err(|e| Error::InvalidBackup(e.to_string()))?;
+            tables.insert(table.table_name, rows);
+        }
+        Ok(Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            schema_version,
+            created_at,
+            tables,
+        })
+    }
+
+    // the tables to restore into a database at schema_version
+    pub fn table_rows(
+        &self,
+        schema_version: i64,
+    ) -> Result<Vec<TableRows>, Error> {
+        if self.format != BACKUP_FORMAT {
+            return Err(Error::InvalidBackup(
+                format!("'{}' is not a backup of ledger", self.format)
+            ));
+        }
+        if self.version > BACKUP_VERSION {
+            return Err(Error::InvalidBackup(format!(
+                "version {} is newer than {}", self.version, BACKUP_VERSION,
+            )));
+        }
+        if self.schema_version > schema_version {
+            return Err(Error::InvalidBackup(format!(
+                "schema version {} is newer than {}",
+                self.schema_version, schema_version,
+            )));
+        }
+        Ok(self.tables.iter()
+            .map(|(table_name, rows)| TableRows {
+                table_name: table_name.clone(),
+                rows: rows.get().to_string(),
+            })
+            .collect())
+    }
+
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use sqlx::PgConnection;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    select::tables_in_order,
+    TableCount,
+    TableRows,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct SerialSelectResult {
+    table_name: String,
+    column_name: String,
+    sequence_name: String,
+}
+
+async fn columns_of(
+    conn: &mut PgConnection,
+    table_name: &str,
+) -> Result<Vec<String>, Error> {
+    let columns = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT column_name::TEXT
+        FROM information_schema.columns
+        WHERE table_schema = 'public' AND table_name = $1
+        ORDER BY ordinal_position
+        "#
+    )
+    .bind(table_name)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(columns)
+}
+
+// Only the columns in the backup are inserted, so columns added since
+// it was taken get their defaults.
+async fn insert_rows(
+    conn: &mut PgConnection,
+    table: &TableRows,
+) -> Result<u64, Error> {
+    let keys = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT DISTINCT JSON_OBJECT_KEYS(e)
+        FROM JSON_ARRAY_ELEMENTS($1::JSON) e
+        "#
+    )
+    .bind(&table.rows)
+    .fetch_all(&mut *conn)
+    .await?;
+    if keys.is_empty() { return Ok(0); }
+
+    let columns = columns_of(&mut *conn, &table.table_name).await?;
+    if let Some(key) = keys.iter().find(|k| !columns.contains(k)) {
+        return Err(Error::InvalidBackup(format!(
+            "column '{}' of '{}' does not exist", key, table.table_name,
+        )));
+    }
+    let column_list = columns.iter()
+        .filter(|c| keys.contains(c))
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let result = sqlx::query(&format!(
+        r#"
+        INSERT INTO public."{0}" ({1})
+        SELECT {1}
+        FROM JSON_POPULATE_RECORDSET(NULL::public."{0}", $1::JSON)
+        "#,
+        table.table_name, column_list,
+    ))
+    .bind(&table.rows)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+// serial columns continue after the largest restored id
+async fn reset_sequences(
+    conn: &mut PgConnection,
+) -> Result<(), Error> {
+    let serials = sqlx::query_as::<_, SerialSelectResult>(
+        r#"
+        SELECT
+            table_name::TEXT AS table_name,
+            column_name::TEXT AS column_name,
+            PG_GET_SERIAL_SEQUENCE(
+                FORMAT('public.%I', table_name), column_name
+            ) AS sequence_name
+        FROM information_schema.columns
+        WHERE table_schema = 'public'
+            AND column_default LIKE 'nextval%'
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for serial in &serials {
+        sqlx::query(&format!(
+            r#"
+            SELECT SETVAL(
+                $1, COALESCE((SELECT MAX("{}") FROM public."{}"), 0) + 1, FALSE
+            )
+            "#,
+            serial.column_name, serial.table_name,
+        ))
+        .bind(&serial.sequence_name)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+// The tables left out of the backup that refer, at any depth, to one of
+// the restored tables. Their rows would go with the truncation.
+async fn dependents_of(
+    conn: &mut PgConnection,
+    table_names: &[String],
+) -> Result<Vec<String>, Error> {
+    let dependents = sqlx::query_scalar::<_, String>(
+        r#"
+        WITH RECURSIVE refs (table_name) AS (
+            SELECT UNNEST($1::TEXT[])
+            UNION
+            SELECT c.relname::TEXT COLLATE "default"
+            FROM refs
+            JOIN pg_class r ON r.relname = refs.table_name
+            JOIN pg_constraint f ON f.confrelid = r.oid AND f.contype = 'f'
+            JOIN pg_class c ON c.oid = f.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = 'public'
+        )
+        SELECT table_name
+        FROM refs
+        WHERE table_name <> ALL($1)
+        ORDER BY table_name
+        "#
+    )
+    .bind(table_names)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(dependents)
+}
+
+async fn validate(
+    conn: &mut PgConnection,
+) -> Result<(), Error> {
+    let unbalanced = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT transaction_id
+        FROM transaction_details
+        GROUP BY transaction_id
+        HAVING SUM(debit_amount) <> SUM(credit_amount)
+        ORDER BY transaction_id
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    match unbalanced {
+        Some(id) => Err(Error::InvalidBackup(
+            format!("transaction '{}' does not balance", id)
+        )),
+        None => Ok(()),
+    }
+}
+
+impl TableRows {
+
+    // Replaces the rows of the tables in the backup in one transaction.
+    // The tables it leaves out are kept, unless they hold rows referring
+    // to a restored table. Nothing is kept when dry_run is set, which
+    // leaves only the validation.
+    pub async fn restore(
+        db: &Db,
+        tables: &[TableRows],
+        dry_run: bool,
+    ) -> Result<Vec<TableCount>, Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let order = tables_in_order(&mut tx).await?;
+        let by_name = tables.iter()
+            .map(|t| (t.table_name.as_str(), t))
+            .collect::<HashMap<&str, &TableRows>>();
+        if let Some(unknown) = tables.iter()
+            .find(|t| !order.contains(&t.table_name))
+        {
+            return Err(Error::InvalidBackup(
+                format!("table '{}' does not exist", unknown.table_name)
+            ));
+        }
+
+        let present = order.iter()
+            .filter(|t| by_name.contains_key(t.as_str()))
+            .cloned()
+            .collect::<Vec<String>>();
+        for dependent in dependents_of(&mut tx, &present).await? {
+            let holds = sqlx::query_scalar::<_, bool>(&format!(
+                r#"
+                SELECT EXISTS (SELECT 1 FROM public."{}")
+                "#,
+                dependent,
+            ))
+            .fetch_one(&mut *tx)
+            .await?;
+            if holds {
+                return Err(Error::InvalidBackup(format!(
+                    "table '{}' is not in the backup", dependent,
+                )));
+            }
+        }
+
+        let table_list = present.iter()
+            .map(|t| format!("public.\"{}\"", t))
+            .collect::<Vec<String>>()
+            .join(", ");
+        sqlx::query(&format!("TRUNCATE TABLE {} CASCADE", table_list))
+            .execute(&mut *tx)
+            .await?;
+
+        let mut counts = Vec::new();
+        for table_name in &present {
+            let table = by_name[table_name.as_str()];
+            counts.push(TableCount {
+                table_name: table_name.clone(),
+                row_count: insert_rows(&mut tx, table).await?,
+            });
+        }
+        reset_sequences(&mut tx).await?;
+        validate(&mut tx).await?;
+
+        match dry_run {
+            true => tx.rollback().await?,
+            false => tx.commit().await?,
+        }
+        Ok(counts)
+    }
+
+}
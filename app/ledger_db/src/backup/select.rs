@@ -0,0 +1,120 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use sqlx::PgConnection;
+
+use crate::{
+    Db,
+    Error,
+    MIGRATION_TABLE,
+};
+
+use super::TableRows;
+
+#[derive(Debug, sqlx::FromRow)]
+struct TableSelectResult {
+    table_name: String,
+    parents: Vec<String>,
+}
+
+// The tables of the books, each after the tables it refers to, so that
+// the rows can be inserted in this order.
+pub(super) async fn tables_in_order(
+    conn: &mut PgConnection,
+) -> Result<Vec<String>, Error> {
+    let tables = sqlx::query_as::<_, TableSelectResult>(
+        r#"
+        SELECT
+            c.relname::TEXT AS table_name,
+            COALESCE(
+                ARRAY_AGG(r.relname::TEXT) FILTER (
+                    WHERE r.relname IS NOT NULL AND r.relname <> c.relname
+                ),
+                '{}'
+            ) AS parents
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_constraint f
+            ON f.conrelid = c.oid AND f.contype = 'f'
+        LEFT JOIN pg_class r ON r.oid = f.confrelid
+        WHERE n.nspname = 'public' AND c.relkind = 'r'
+            AND c.relname <> $1
+        GROUP BY c.relname
+        ORDER BY c.relname
+        "#
+    )
+    .bind(MIGRATION_TABLE)
+    .fetch_all(conn)
+    .await?;
+
+    let mut waiting = tables.into_iter()
+        .map(|t| (t.table_name, t.parents.into_iter().collect()))
+        .collect::<BTreeMap<String, BTreeSet<String>>>();
+    let mut ordered = Vec::new();
+    while !waiting.is_empty() {
+        let ready = waiting.iter()
+            .filter(|(_, parents)| parents.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<String>>();
+        // tables referring to each other are left in name order
+        let ready = match ready.is_empty() {
+            true => waiting.keys().cloned().collect(),
+            false => ready,
+        };
+        for name in &ready {
+            waiting.remove(name);
+        }
+        for parents in waiting.values_mut() {
+            ready.iter().for_each(|name| { parents.remove(name); });
+        }
+        ordered.extend(ready);
+    }
+    Ok(ordered)
+}
+
+impl TableRows {
+
+    // every table read in one snapshot
+    pub async fn dump(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let mut tx = db.conn.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await?;
+
+        let mut tables = Vec::new();
+        for table_name in tables_in_order(&mut tx).await? {
+            let rows = sqlx::query_scalar::<_, String>(&format!(
+                r#"
+                SELECT COALESCE(JSON_AGG(t), '[]')::TEXT
+                FROM public."{}" t
+                "#,
+                table_name,
+            ))
+            .fetch_one(&mut *tx)
+            .await?;
+            tables.push(TableRows { table_name, rows });
+        }
+        tx.commit().await?;
+
+        Ok(tables)
+    }
+
+    pub async fn holds_journals(
+        db: &Db,
+    ) -> Result<bool, Error> {
+        let holds = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM transactions)
+            "#
+        )
+        .fetch_one(&db.conn)
+        .await?;
+
+        Ok(holds)
+    }
+
+}
@@ -0,0 +1,19 @@
+mod insert;
+mod select;
+mod delete;
+
+use crate::account::AccountType;
+
+// fiscal_year is the year the fiscal year begins in, as the summaries
+// count it, month is 1 to 12 and falls in the next calendar year when
+// it is before the start month.
+#[derive(Debug)]
+pub struct Budget {
+    pub budget_id: i32,
+    pub account_id: i32,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub fiscal_year: i32,
+    pub month: u32,
+    pub amount: f32,
+}
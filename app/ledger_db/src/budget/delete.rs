@@ -0,0 +1,34 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Budget;
+
+impl Budget {
+
+    pub async fn delete(
+        db: &Db,
+        account_name: &str,
+        fiscal_year: i32,
+        month: u32,
+    ) -> Result<u64, Error> {
+        let query = sqlx::query(
+            r#"
+            DELETE FROM budgets b
+            USING accounts a
+            WHERE
+                b.account_id = a.account_id
+                AND a.account_name = $1
+                AND b.fiscal_year = $2
+                AND b.budget_month = $3
+            "#
+        )
+        .bind(account_name)
+        .bind(fiscal_year)
+        .bind(month as i32);
+
+        Ok(query.execute(&db.conn).await?.rows_affected())
+    }
+
+}
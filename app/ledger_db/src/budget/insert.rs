@@ -0,0 +1,56 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    Db,
+    Error,
+    account::Account,
+};
+
+use super::Budget;
+
+impl Budget {
+
+    // all or none of the budgets are stored
+    pub async fn upsert(
+        db: &Db,
+        budgets: &[Budget],
+    ) -> Result<u64, Error> {
+        let mut rows = Vec::with_capacity(budgets.len());
+        for b in budgets {
+            let acc = match Account::by_name(db, &b.account_name).await {
+                Ok(acc) => acc,
+                Err(Error::RowNotFound) => return Err(Error::AccountNotFound),
+                Err(err) => return Err(err),
+            };
+            let amount = match Decimal::from_f32_retain(b.amount) {
+                Some(a) => a,
+                None => return Err(Error::DecimalConvError(b.amount)),
+            };
+            rows.push((acc.account_id, b.fiscal_year, b.month as i32, amount));
+        }
+
+        let mut tx = db.conn.begin().await?;
+        let mut count = 0;
+        for (account_id, fiscal_year, month, amount) in rows {
+            count += sqlx::query(
+                r#"
+                INSERT INTO budgets
+                    (account_id, fiscal_year, budget_month, amount)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (account_id, fiscal_year, budget_month)
+                DO UPDATE SET amount = EXCLUDED.amount
+                "#
+            )
+            .bind(account_id)
+            .bind(fiscal_year)
+            .bind(month)
+            .bind(amount)
+            .execute(&mut *tx)
+            .await?.rows_affected();
+        }
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+}
@@ -0,0 +1,86 @@
+use std::convert::From;
+
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Budget;
+
+#[derive(Debug, sqlx::FromRow)]
+struct BudgetSelectResult {
+    budget_id: i32,
+    account_id: i32,
+    account_name: String,
+    account_type: String,
+    fiscal_year: i32,
+    budget_month: i32,
+    amount: Decimal,
+}
+
+impl Budget {
+
+    pub async fn by_year(
+        db: &Db,
+        fiscal_year: i32,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, BudgetSelectResult>(
+            r#"
+            SELECT
+                b.budget_id,
+                a.account_id,
+                a.account_name,
+                a.account_type,
+                b.fiscal_year,
+                b.budget_month,
+                b.amount
+            FROM budgets b
+                INNER JOIN accounts a
+                ON b.account_id = a.account_id
+            WHERE b.fiscal_year = $1
+            ORDER BY
+                a.account_type ASC, a.account_id ASC, b.budget_month ASC
+            "#
+        )
+        .bind(fiscal_year);
+
+        let mut budgets = query.fetch_all(&db.conn).await?
+            .iter().map(Budget::from).collect::<Vec<Budget>>();
+        budgets.sort_by(|b1, b2| b1.account_type.cmp(&b2.account_type));
+        Ok(budgets)
+    }
+
+    pub async fn by_month(
+        db: &Db,
+        fiscal_year: i32,
+        month: u32,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(Budget::by_year(db, fiscal_year).await?
+            .into_iter().filter(|b| b.month == month)
+            .collect::<Vec<Budget>>())
+    }
+
+}
+
+impl From<&BudgetSelectResult> for Budget {
+
+    fn from(
+        value: &BudgetSelectResult,
+    ) -> Self {
+        Budget {
+            budget_id: value.budget_id,
+            account_id: value.account_id,
+            account_name: value.account_name.clone(),
+            account_type: (&value.account_type).into(),
+            fiscal_year: value.fiscal_year,
+            month: value.budget_month as u32,
+            amount: value.amount.to_f32().unwrap_or(0_f32),
+        }
+    }
+
+}
@@ -0,0 +1,15 @@
+mod cash_flow_type;
+mod insert;
+mod select;
+
+use crate::account::AccountType;
+
+pub use cash_flow_type::*;
+
+#[derive(Debug)]
+pub struct AccountCashFlow {
+    pub account_id: i32,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub cash_flow_type: CashFlowType,
+}
@@ -0,0 +1,77 @@
+use std::convert::From;
+use std::str::FromStr;
+
+use crate::account::AccountType;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum CashFlowType {
+    Cash,  // 資金
+    Operating,  // 営業活動
+    Investing,  // 投資活動
+    Financing,  // 財務活動
+    Excluded,  // 対象外
+}
+
+impl CashFlowType {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            CashFlowType::Cash => "資金".to_string(),
+            CashFlowType::Operating => "営業活動".to_string(),
+            CashFlowType::Investing => "投資活動".to_string(),
+            CashFlowType::Financing => "財務活動".to_string(),
+            CashFlowType::Excluded => "対象外".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "資金" | "現金" => Some(CashFlowType::Cash),
+            "営業活動" | "営業" => Some(CashFlowType::Operating),
+            "投資活動" | "投資" => Some(CashFlowType::Investing),
+            "財務活動" | "財務" => Some(CashFlowType::Financing),
+            "対象外" => Some(CashFlowType::Excluded),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        CashFlowType::from_str(value).ok()
+            .or_else(|| CashFlowType::from_japanese(value))
+    }
+
+    // used for accounts without an explicit classification
+    pub fn default_for(account_type: &AccountType) -> Self {
+        match account_type {
+            AccountType::Asset => CashFlowType::Operating,
+            AccountType::Liability => CashFlowType::Operating,
+            AccountType::Equity => CashFlowType::Financing,
+            _ => CashFlowType::Excluded,
+        }
+    }
+
+}
+
+impl From<&String> for CashFlowType {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        CashFlowType::from_str(value)
+        .unwrap_or_else(|_| {
+            CashFlowType::from_japanese(value)
+            .unwrap_or(CashFlowType::Excluded)
+        })
+    }
+
+}
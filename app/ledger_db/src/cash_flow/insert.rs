@@ -0,0 +1,37 @@
+use crate::{
+    Db,
+    Error,
+    account::Account,
+};
+
+use super::AccountCashFlow;
+
+impl AccountCashFlow {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<(), Error> {
+        let acc = match Account::by_name(db, &self.account_name).await {
+            Ok(acc) => acc,
+            Err(Error::RowNotFound) => return Err(Error::AccountNotFound),
+            Err(err) => return Err(err),
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO account_cash_flows
+                (account_id, cash_flow_type)
+            VALUES ($1, $2)
+            ON CONFLICT (account_id)
+            DO UPDATE SET cash_flow_type = EXCLUDED.cash_flow_type
+            "#
+        )
+        .bind(acc.account_id)
+        .bind(self.cash_flow_type.to_string())
+        .execute(&db.conn)
+        .await?;
+
+        Ok(())
+    }
+
+}
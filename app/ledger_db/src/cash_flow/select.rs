@@ -0,0 +1,70 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    AccountCashFlow,
+    CashFlowType,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct AccountCashFlowSelectResult {
+    account_id: i32,
+    account_name: String,
+    account_type: String,
+    cash_flow_type: Option<String>,
+}
+
+impl AccountCashFlow {
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, AccountCashFlowSelectResult>(
+            r#"
+            SELECT
+                a.account_id,
+                a.account_name,
+                a.account_type,
+                cf.cash_flow_type
+            FROM accounts a
+                LEFT OUTER JOIN account_cash_flows cf
+                ON a.account_id = cf.account_id
+            ORDER BY a.account_type ASC, a.account_id ASC
+            "#
+        );
+
+        let mut acc = query.fetch_all(&db.conn).await?
+            .iter().map(AccountCashFlow::from)
+            .collect::<Vec<AccountCashFlow>>();
+        acc.sort_by(|a1, a2| {
+            a1.account_type.cmp(&a2.account_type)
+            .then(a1.account_id.cmp(&a2.account_id))
+        });
+        Ok(acc)
+    }
+
+}
+
+impl From<&AccountCashFlowSelectResult> for AccountCashFlow {
+
+    fn from(
+        value: &AccountCashFlowSelectResult,
+    ) -> Self {
+        let account_type = (&value.account_type).into();
+        let cash_flow_type = match &value.cash_flow_type {
+            Some(cft) => cft.into(),
+            None => CashFlowType::default_for(&account_type),
+        };
+        AccountCashFlow {
+            account_id: value.account_id,
+            account_name: value.account_name.clone(),
+            account_type,
+            cash_flow_type,
+        }
+    }
+
+}
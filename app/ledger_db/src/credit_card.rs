@@ -0,0 +1,13 @@
+mod insert;
+mod select;
+
+// account_name is the 未払金 sub-account the charges are credited to,
+// bank_account_name is the account the monthly payment is debited from.
+#[derive(Debug)]
+pub struct CreditCard {
+    pub card_id: i32,
+    pub card_name: String,
+    pub account_name: String,
+    pub bank_account_name: String,
+    pub mapping_name: String,
+}
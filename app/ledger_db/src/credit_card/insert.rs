@@ -0,0 +1,48 @@
+use crate::{
+    Db,
+    Error,
+    csv_mapping::CsvMapping,
+    staged_line::account_id_of,
+};
+
+use super::CreditCard;
+
+#[derive(Debug, sqlx::FromRow)]
+struct CreditCardInsertResult {
+    card_id: i32,
+}
+
+impl CreditCard {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let account_id = account_id_of(db, &self.account_name).await?;
+        let bank_account_id
+            = account_id_of(db, &self.bank_account_name).await?;
+        let mapping_id
+            = CsvMapping::by_name(db, &self.mapping_name).await?.mapping_id;
+        let query = sqlx::query_as::<_, CreditCardInsertResult>(
+            r#"
+            INSERT INTO credit_cards
+                (card_name, account_id, bank_account_id, mapping_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (card_name)
+            DO UPDATE SET
+                account_id = EXCLUDED.account_id,
+                bank_account_id = EXCLUDED.bank_account_id,
+                mapping_id = EXCLUDED.mapping_id
+            RETURNING
+                card_id
+            "#
+        )
+        .bind(&self.card_name)
+        .bind(account_id)
+        .bind(bank_account_id)
+        .bind(mapping_id);
+
+        Ok(query.fetch_one(&db.conn).await?.card_id)
+    }
+
+}
@@ -0,0 +1,90 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::CreditCard;
+
+#[derive(Debug, sqlx::FromRow)]
+struct CreditCardSelectResult {
+    card_id: i32,
+    card_name: String,
+    account_name: String,
+    bank_account_name: String,
+    mapping_name: String,
+}
+
+impl CreditCard {
+
+    pub async fn by_name(
+        db: &Db,
+        card_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, CreditCardSelectResult>(
+            r#"
+            SELECT
+                c.card_id,
+                c.card_name,
+                a.account_name,
+                b.account_name AS bank_account_name,
+                m.mapping_name
+            FROM credit_cards c
+                INNER JOIN accounts a
+                ON c.account_id = a.account_id
+                INNER JOIN accounts b
+                ON c.bank_account_id = b.account_id
+                INNER JOIN csv_mappings m
+                ON c.mapping_id = m.mapping_id
+            WHERE c.card_name = $1
+            "#
+        )
+        .bind(card_name);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, CreditCardSelectResult>(
+            r#"
+            SELECT
+                c.card_id,
+                c.card_name,
+                a.account_name,
+                b.account_name AS bank_account_name,
+                m.mapping_name
+            FROM credit_cards c
+                INNER JOIN accounts a
+                ON c.account_id = a.account_id
+                INNER JOIN accounts b
+                ON c.bank_account_id = b.account_id
+                INNER JOIN csv_mappings m
+                ON c.mapping_id = m.mapping_id
+            ORDER BY c.card_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<CreditCard>>())
+    }
+
+}
+
+impl From<&CreditCardSelectResult> for CreditCard {
+
+    fn from(
+        value: &CreditCardSelectResult,
+    ) -> Self {
+        CreditCard {
+            card_id: value.card_id,
+            card_name: value.card_name.clone(),
+            account_name: value.account_name.clone(),
+            bank_account_name: value.bank_account_name.clone(),
+            mapping_name: value.mapping_name.clone(),
+        }
+    }
+
+}
@@ -0,0 +1,19 @@
+mod insert;
+mod select;
+
+// column indexes are 0 based. a signed amount column is described by
+// setting withdrawal_column and deposit_column to the same index,
+// positive values are deposits.
+#[derive(Debug)]
+pub struct CsvMapping {
+    pub mapping_id: i32,
+    pub mapping_name: String,
+    pub encoding: String,
+    pub skip_rows: i32,
+    pub date_column: i32,
+    pub date_format: String,
+    pub description_column: i32,
+    pub withdrawal_column: Option<i32>,
+    pub deposit_column: Option<i32>,
+    pub balance_column: Option<i32>,
+}
@@ -0,0 +1,53 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::CsvMapping;
+
+#[derive(Debug, sqlx::FromRow)]
+struct CsvMappingInsertResult {
+    mapping_id: i32,
+}
+
+impl CsvMapping {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let query = sqlx::query_as::<_, CsvMappingInsertResult>(
+            r#"
+            INSERT INTO csv_mappings
+                (mapping_name, encoding, skip_rows,
+                date_column, date_format, description_column,
+                withdrawal_column, deposit_column, balance_column)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (mapping_name)
+            DO UPDATE SET
+                encoding = EXCLUDED.encoding,
+                skip_rows = EXCLUDED.skip_rows,
+                date_column = EXCLUDED.date_column,
+                date_format = EXCLUDED.date_format,
+                description_column = EXCLUDED.description_column,
+                withdrawal_column = EXCLUDED.withdrawal_column,
+                deposit_column = EXCLUDED.deposit_column,
+                balance_column = EXCLUDED.balance_column
+            RETURNING
+                mapping_id
+            "#
+        )
+        .bind(&self.mapping_name)
+        .bind(&self.encoding)
+        .bind(self.skip_rows)
+        .bind(self.date_column)
+        .bind(&self.date_format)
+        .bind(self.description_column)
+        .bind(self.withdrawal_column)
+        .bind(self.deposit_column)
+        .bind(self.balance_column);
+
+        Ok(query.fetch_one(&db.conn).await?.mapping_id)
+    }
+
+}
@@ -0,0 +1,84 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::CsvMapping;
+
+#[derive(Debug, sqlx::FromRow)]
+struct CsvMappingSelectResult {
+    mapping_id: i32,
+    mapping_name: String,
+    encoding: String,
+    skip_rows: i32,
+    date_column: i32,
+    date_format: String,
+    description_column: i32,
+    withdrawal_column: Option<i32>,
+    deposit_column: Option<i32>,
+    balance_column: Option<i32>,
+}
+
+impl CsvMapping {
+
+    pub async fn by_name(
+        db: &Db,
+        mapping_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, CsvMappingSelectResult>(
+            r#"
+            SELECT
+                mapping_id, mapping_name, encoding, skip_rows,
+                date_column, date_format, description_column,
+                withdrawal_column, deposit_column, balance_column
+            FROM csv_mappings
+            WHERE mapping_name = $1
+            "#
+        )
+        .bind(mapping_name);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, CsvMappingSelectResult>(
+            r#"
+            SELECT
+                mapping_id, mapping_name, encoding, skip_rows,
+                date_column, date_format, description_column,
+                withdrawal_column, deposit_column, balance_column
+            FROM csv_mappings
+            ORDER BY mapping_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<CsvMapping>>())
+    }
+
+}
+
+impl From<&CsvMappingSelectResult> for CsvMapping {
+
+    fn from(
+        value: &CsvMappingSelectResult,
+    ) -> Self {
+        CsvMapping {
+            mapping_id: value.mapping_id,
+            mapping_name: value.mapping_name.clone(),
+            encoding: value.encoding.clone(),
+            skip_rows: value.skip_rows,
+            date_column: value.date_column,
+            date_format: value.date_format.clone(),
+            description_column: value.description_column,
+            withdrawal_column: value.withdrawal_column,
+            deposit_column: value.deposit_column,
+            balance_column: value.balance_column,
+        }
+    }
+
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use sqlx::postgres::{
     PgPool,
     PgPoolOptions,
@@ -15,19 +17,14 @@ impl Db {
     pub async fn connect(
         conn_str: &str,
         max_conn: u32,
+        acquire_timeout: Duration,
     ) -> Result<Self, Error> {
         let pool = PgPoolOptions::new()
             .max_connections(max_conn)
+            .acquire_timeout(acquire_timeout)
             .connect(conn_str).await?;
         Ok(Db { conn: pool })
     }
 
-    pub async fn connect_default() -> Result<Self, Error> {
-        Self::connect(
-            "postgres://postgres:postgres@db:5432/ledger",
-            5,
-        ).await
-    }
-
 }
 
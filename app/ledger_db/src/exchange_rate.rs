@@ -0,0 +1,25 @@
+mod insert;
+mod select;
+
+use chrono::NaiveDate;
+
+use crate::account::AccountType;
+
+// TTM of a currency in yen on a date
+#[derive(Debug, Clone)]
+pub struct ExchangeRate {
+    pub currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: f32,
+}
+
+// What an account holds in a currency at the end of a year, both in the
+// currency and in the yen it was booked at. Debit positive.
+#[derive(Debug)]
+pub struct ForeignBalance {
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub currency: String,
+    pub foreign_amount: f32,
+    pub booked_amount: f32,
+}
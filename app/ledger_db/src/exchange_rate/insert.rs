@@ -0,0 +1,45 @@
+use rust_decimal::{
+    prelude::FromPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::ExchangeRate;
+
+impl ExchangeRate {
+
+    // a rate of the same currency and date is replaced
+    pub async fn upsert(
+        db: &Db,
+        rates: &[ExchangeRate],
+    ) -> Result<u64, Error> {
+        let mut tx = db.conn.begin().await?;
+        let mut count = 0;
+        for r in rates {
+            let rate = Decimal::from_f32(r.rate)
+                .ok_or(Error::DecimalConvError(r.rate))?;
+            count += sqlx::query(
+                r#"
+                INSERT INTO exchange_rates
+                    (currency, rate_date, rate)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (currency, rate_date)
+                DO UPDATE SET rate = EXCLUDED.rate
+                "#
+            )
+            .bind(&r.currency)
+            .bind(r.rate_date)
+            .bind(rate)
+            .execute(&mut *tx)
+            .await?.rows_affected();
+        }
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+}
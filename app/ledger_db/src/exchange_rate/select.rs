@@ -0,0 +1,173 @@
+use std::convert::From;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+    transaction::TransactionType,
+};
+
+use super::{
+    ExchangeRate,
+    ForeignBalance,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct ExchangeRateSelectResult {
+    currency: String,
+    rate_date: NaiveDate,
+    rate: Decimal,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ForeignBalanceSelectResult {
+    account_name: String,
+    account_type: String,
+    currency: String,
+    foreign_amount: Decimal,
+    booked_amount: Decimal,
+}
+
+impl ExchangeRate {
+
+    // The rate of the date, or of the last day before it with one, as
+    // there is no TTM on holidays. RowNotFound when there is none.
+    pub async fn on(
+        db: &Db,
+        currency: &str,
+        date: NaiveDate,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, ExchangeRateSelectResult>(
+            r#"
+            SELECT
+                currency, rate_date, rate
+            FROM exchange_rates
+            WHERE currency = $1
+                AND rate_date <= $2
+            ORDER BY rate_date DESC
+            LIMIT 1
+            "#
+        )
+        .bind(currency)
+        .bind(date);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn by_currency(
+        db: &Db,
+        currency: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ExchangeRateSelectResult>(
+            r#"
+            SELECT
+                currency, rate_date, rate
+            FROM exchange_rates
+            WHERE currency = $1
+                AND rate_date >= $2
+                AND rate_date <= $3
+            ORDER BY rate_date ASC
+            "#
+        )
+        .bind(currency)
+        .bind(start_date)
+        .bind(end_date);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<ExchangeRate>>())
+    }
+
+}
+
+impl ForeignBalance {
+
+    // the lines in other currencies of the assets and the liabilities
+    // through the kessan stage, as Summary::upto_kessan takes them
+    pub async fn upto_kessan(
+        db: &Db,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ForeignBalanceSelectResult>(
+            r#"
+            SELECT
+                a.account_name,
+                a.account_type,
+                td.currency,
+                SUM(CASE WHEN td.credit_amount = 0
+                    THEN td.foreign_amount
+                    ELSE -td.foreign_amount END) AS foreign_amount,
+                SUM(td.debit_amount - td.credit_amount) AS booked_amount
+            FROM transactions t
+                INNER JOIN transaction_details td
+                ON t.transaction_id = td.transaction_id
+                INNER JOIN accounts a
+                ON td.account_id = a.account_id
+            WHERE
+                td.currency IS NOT NULL
+                AND a.account_type IN ('Asset', 'Liability')
+                AND (
+                    (t.transaction_date = $1
+                    AND t.transaction_type = $3)
+                    OR
+                    (t.transaction_date >= $1
+                    AND t.transaction_date <= $2
+                    AND t.transaction_type = $4)
+                    OR
+                    (t.transaction_date = $2
+                    AND t.transaction_type = $5)
+                )
+            GROUP BY
+                a.account_id, a.account_name, a.account_type, td.currency
+            ORDER BY
+                a.account_type ASC, a.account_id ASC, td.currency ASC
+            "#
+        )
+        .bind(start_date)
+        .bind(end_date)
+        .bind(TransactionType::FromPrev.to_string())
+        .bind(TransactionType::InTerm.to_string())
+        .bind(TransactionType::Kessan.to_string());
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<ForeignBalance>>())
+    }
+
+}
+
+impl From<&ExchangeRateSelectResult> for ExchangeRate {
+
+    fn from(
+        value: &ExchangeRateSelectResult,
+    ) -> Self {
+        ExchangeRate {
+            currency: value.currency.clone(),
+            rate_date: value.rate_date,
+            rate: value.rate.to_f32().unwrap_or(0_f32),
+        }
+    }
+
+}
+
+impl From<&ForeignBalanceSelectResult> for ForeignBalance {
+
+    fn from(
+        value: &ForeignBalanceSelectResult,
+    ) -> Self {
+        ForeignBalance {
+            account_name: value.account_name.clone(),
+            account_type: (&value.account_type).into(),
+            currency: value.currency.clone(),
+            foreign_amount: value.foreign_amount.to_f32().unwrap_or(0_f32),
+            booked_amount: value.booked_amount.to_f32().unwrap_or(0_f32),
+        }
+    }
+
+}
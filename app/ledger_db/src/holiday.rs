@@ -0,0 +1,12 @@
+mod insert;
+mod select;
+mod delete;
+
+use chrono::NaiveDate;
+
+// a day banks are closed on besides Saturdays and Sundays
+#[derive(Debug)]
+pub struct Holiday {
+    pub holiday_date: NaiveDate,
+    pub holiday_name: String,
+}
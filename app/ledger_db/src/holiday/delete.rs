@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Holiday;
+
+impl Holiday {
+
+    pub async fn delete(
+        db: &Db,
+        holiday_date: NaiveDate,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM holidays
+            WHERE holiday_date = $1
+            "#
+        )
+        .bind(holiday_date)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -0,0 +1,32 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Holiday;
+
+impl Holiday {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO holidays
+                (holiday_date, holiday_name)
+            VALUES ($1, $2)
+            ON CONFLICT (holiday_date)
+            DO UPDATE SET
+                holiday_name = EXCLUDED.holiday_name
+            "#
+        )
+        .bind(self.holiday_date)
+        .bind(&self.holiday_name)
+        .execute(&db.conn)
+        .await?;
+
+        Ok(())
+    }
+
+}
@@ -0,0 +1,51 @@
+use std::convert::From;
+
+use chrono::NaiveDate;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Holiday;
+
+#[derive(Debug, sqlx::FromRow)]
+struct HolidaySelectResult {
+    holiday_date: NaiveDate,
+    holiday_name: String,
+}
+
+impl Holiday {
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, HolidaySelectResult>(
+            r#"
+            SELECT
+                holiday_date,
+                holiday_name
+            FROM holidays
+            ORDER BY
+                holiday_date ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<Holiday>>())
+    }
+
+}
+
+impl From<&HolidaySelectResult> for Holiday {
+
+    fn from(
+        value: &HolidaySelectResult,
+    ) -> Self {
+        Holiday {
+            holiday_date: value.holiday_date,
+            holiday_name: value.holiday_name.clone(),
+        }
+    }
+
+}
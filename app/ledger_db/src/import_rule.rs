@@ -0,0 +1,29 @@
+mod match_type;
+mod insert;
+mod select;
+mod delete;
+
+use crate::transaction::TaxCode;
+
+pub use match_type::*;
+
+// Proposes the counter account, tax code and description of staged
+// lines. Every condition left None matches any line; the rule with the
+// smallest priority among the matching ones fires. learned rules were
+// created from a manual assignment rather than registered by hand.
+#[derive(Debug, Clone)]
+pub struct ImportRule {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub priority: i32,
+    pub match_type: MatchType,
+    pub pattern: Option<String>,
+    pub min_amount: Option<f32>,
+    pub max_amount: Option<f32>,
+    pub source_account_name: Option<String>,
+    pub partner_name: Option<String>,
+    pub counter_account_name: String,
+    pub tax_code: Option<TaxCode>,
+    pub description: Option<String>,
+    pub learned: bool,
+}
@@ -0,0 +1,30 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::ImportRule;
+
+impl ImportRule {
+
+    pub async fn delete(
+        db: &Db,
+        rule_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM import_rules
+            WHERE rule_id = $1
+            "#
+        )
+        .bind(rule_id)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -0,0 +1,96 @@
+use crate::{
+    Db,
+    Error,
+    partner::partner_id_of,
+    staged_line::{
+        account_id_of,
+        decimal_of,
+    },
+};
+
+use super::ImportRule;
+
+#[derive(Debug, sqlx::FromRow)]
+struct ImportRuleInsertResult {
+    rule_id: i32,
+}
+
+impl ImportRule {
+
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let source_account_id = match &self.source_account_name {
+            Some(name) => Some(account_id_of(db, name).await?),
+            None => None,
+        };
+        let partner_id = match &self.partner_name {
+            Some(name) => Some(partner_id_of(&db.conn, name).await?),
+            None => None,
+        };
+        let counter_account_id
+            = account_id_of(db, &self.counter_account_name).await?;
+        let min_amount = match self.min_amount {
+            Some(a) => Some(decimal_of(a)?),
+            None => None,
+        };
+        let max_amount = match self.max_amount {
+            Some(a) => Some(decimal_of(a)?),
+            None => None,
+        };
+        let query = sqlx::query_as::<_, ImportRuleInsertResult>(
+            r#"
+            INSERT INTO import_rules
+                (rule_name, priority, match_type, pattern,
+                min_amount, max_amount, source_account_id, partner_id,
+                counter_account_id, tax_code, description, learned)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING
+                rule_id
+            "#
+        )
+        .bind(&self.rule_name)
+        .bind(self.priority)
+        .bind(self.match_type.to_string())
+        .bind(&self.pattern)
+        .bind(min_amount)
+        .bind(max_amount)
+        .bind(source_account_id)
+        .bind(partner_id)
+        .bind(counter_account_id)
+        .bind(self.tax_code.as_ref().map(|t| t.to_string()))
+        .bind(&self.description)
+        .bind(self.learned);
+
+        Ok(query.fetch_one(&db.conn).await?.rule_id)
+    }
+
+    // a learned rule replaces the one learned earlier from the same
+    // description of the same account
+    pub async fn replace_learned(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let source_account_id = match &self.source_account_name {
+            Some(name) => Some(account_id_of(db, name).await?),
+            None => None,
+        };
+        sqlx::query(
+            r#"
+            DELETE FROM import_rules
+            WHERE
+                learned = TRUE
+                AND pattern = $1
+                AND source_account_id IS NOT DISTINCT FROM $2
+            "#
+        )
+        .bind(&self.pattern)
+        .bind(source_account_id)
+        .execute(&db.conn)
+        .await?;
+
+        ImportRule { learned: true, ..self.clone() }.insert(db).await
+    }
+
+}
@@ -0,0 +1,54 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum MatchType {
+    Substring,  // 部分一致
+    Regex,  // 正規表現
+}
+
+impl MatchType {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            MatchType::Substring => "部分一致".to_string(),
+            MatchType::Regex => "正規表現".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "部分一致" => Some(MatchType::Substring),
+            "正規表現" => Some(MatchType::Regex),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        MatchType::from_str(value).ok()
+            .or_else(|| MatchType::from_japanese(value))
+    }
+
+}
+
+impl From<&String> for MatchType {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        MatchType::from_str(value)
+        .unwrap_or_else(|_| {
+            MatchType::from_japanese(value)
+            .unwrap_or(MatchType::Substring)
+        })
+    }
+
+}
@@ -0,0 +1,95 @@
+use std::convert::From;
+
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::ImportRule;
+
+#[derive(Debug, sqlx::FromRow)]
+struct ImportRuleSelectResult {
+    rule_id: i32,
+    rule_name: String,
+    priority: i32,
+    match_type: String,
+    pattern: Option<String>,
+    min_amount: Option<Decimal>,
+    max_amount: Option<Decimal>,
+    source_account_name: Option<String>,
+    partner_name: Option<String>,
+    counter_account_name: String,
+    tax_code: Option<String>,
+    description: Option<String>,
+    learned: bool,
+}
+
+impl ImportRule {
+
+    // ordered by priority, the first matching rule fires
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ImportRuleSelectResult>(
+            r#"
+            SELECT
+                r.rule_id,
+                r.rule_name,
+                r.priority,
+                r.match_type,
+                r.pattern,
+                r.min_amount,
+                r.max_amount,
+                s.account_name AS source_account_name,
+                p.partner_name,
+                c.account_name AS counter_account_name,
+                r.tax_code,
+                r.description,
+                r.learned
+            FROM import_rules r
+                INNER JOIN accounts c
+                ON r.counter_account_id = c.account_id
+                LEFT OUTER JOIN accounts s
+                ON r.source_account_id = s.account_id
+                LEFT OUTER JOIN partners p
+                ON r.partner_id = p.partner_id
+            ORDER BY
+                r.priority ASC,
+                r.rule_id DESC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<ImportRule>>())
+    }
+
+}
+
+impl From<&ImportRuleSelectResult> for ImportRule {
+
+    fn from(
+        value: &ImportRuleSelectResult,
+    ) -> Self {
+        ImportRule {
+            rule_id: value.rule_id,
+            rule_name: value.rule_name.clone(),
+            priority: value.priority,
+            match_type: (&value.match_type).into(),
+            pattern: value.pattern.clone(),
+            min_amount: value.min_amount.and_then(|a| a.to_f32()),
+            max_amount: value.max_amount.and_then(|a| a.to_f32()),
+            source_account_name: value.source_account_name.clone(),
+            partner_name: value.partner_name.clone(),
+            counter_account_name: value.counter_account_name.clone(),
+            tax_code: value.tax_code.as_ref().map(|t| t.into()),
+            description: value.description.clone(),
+            learned: value.learned,
+        }
+    }
+
+}
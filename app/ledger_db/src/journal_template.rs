@@ -0,0 +1,36 @@
+mod journal_template_type;
+mod insert;
+mod select;
+mod delete;
+
+use crate::{
+    account::AmountSide,
+    transaction::{
+        TaxCode,
+        TransactionType,
+    },
+};
+
+pub use journal_template_type::*;
+
+// a journal with the amounts left open, posted by name.
+// tax_code splits the tax off the total when the tax is not given.
+#[derive(Debug)]
+pub struct JournalTemplate {
+    pub template_id: i32,
+    pub template_name: String,
+    pub transaction_type: TransactionType,
+    pub description: String,
+    pub tax_code: Option<TaxCode>,
+    pub lines: Vec<JournalTemplateLine>,
+}
+
+// a line of a template, either of the fixed account_name or of the
+// account given as the input account_param
+#[derive(Debug)]
+pub struct JournalTemplateLine {
+    pub side: AmountSide,
+    pub account_name: Option<String>,
+    pub account_param: Option<String>,
+    pub amount_source: AmountSource,
+}
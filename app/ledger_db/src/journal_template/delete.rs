@@ -0,0 +1,30 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::JournalTemplate;
+
+impl JournalTemplate {
+
+    pub async fn delete(
+        db: &Db,
+        template_name: &str,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM journal_templates
+            WHERE template_name = $1
+            "#
+        )
+        .bind(template_name)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
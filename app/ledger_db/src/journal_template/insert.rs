@@ -0,0 +1,84 @@
+use crate::{
+    Db,
+    Error,
+    staged_line::account_id_of,
+};
+
+use super::JournalTemplate;
+
+#[derive(Debug, sqlx::FromRow)]
+struct JournalTemplateInsertResult {
+    template_id: i32,
+}
+
+impl JournalTemplate {
+
+    // a template of the same name is replaced, lines and all
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let mut account_ids = Vec::new();
+        for line in &self.lines {
+            account_ids.push(match &line.account_name {
+                Some(name) => Some(account_id_of(db, name).await?),
+                None => None,
+            });
+        }
+        let mut tx = db.conn.begin().await?;
+
+        let template_id = sqlx::query_as::<_, JournalTemplateInsertResult>(
+            r#"
+            INSERT INTO journal_templates
+                (template_name, transaction_type, description, tax_code)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (template_name)
+            DO UPDATE SET
+                transaction_type = EXCLUDED.transaction_type,
+                description = EXCLUDED.description,
+                tax_code = EXCLUDED.tax_code
+            RETURNING
+                template_id
+            "#
+        )
+        .bind(&self.template_name)
+        .bind(self.transaction_type.to_string())
+        .bind(&self.description)
+        .bind(self.tax_code.as_ref().map(|t| t.to_string()))
+        .fetch_one(&mut *tx)
+        .await?.template_id;
+
+        sqlx::query(
+            r#"
+            DELETE FROM journal_template_lines
+            WHERE template_id = $1
+            "#
+        )
+        .bind(template_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for (line, account_id) in self.lines.iter().zip(account_ids) {
+            sqlx::query(
+                r#"
+                INSERT INTO journal_template_lines
+                    (template_id, side, account_id, account_param,
+                    amount_source)
+                VALUES ($1, $2, $3, $4, $5)
+                "#
+            )
+            .bind(template_id)
+            .bind(line.side.to_string())
+            .bind(account_id)
+            .bind(&line.account_param)
+            .bind(line.amount_source.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(template_id)
+    }
+
+}
@@ -0,0 +1,59 @@
+use std::convert::From;
+use std::str::FromStr;
+
+// the part of the tax-inclusive total a template line is booked with
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum AmountSource {
+    Total,  // 総額
+    TotalExTax,  // 税抜額
+    Tax,  // 消費税額
+}
+
+impl AmountSource {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            AmountSource::Total => "総額".to_string(),
+            AmountSource::TotalExTax => "税抜額".to_string(),
+            AmountSource::Tax => "消費税額".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "総額" | "税込額" => Some(AmountSource::Total),
+            "税抜額" => Some(AmountSource::TotalExTax),
+            "消費税額" | "消費税" => Some(AmountSource::Tax),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        AmountSource::from_str(value).ok()
+            .or_else(|| AmountSource::from_japanese(value))
+    }
+
+}
+
+impl From<&String> for AmountSource {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        AmountSource::from_str(value)
+        .unwrap_or_else(|_| {
+            AmountSource::from_japanese(value)
+            .unwrap_or(AmountSource::Total)
+        })
+    }
+
+}
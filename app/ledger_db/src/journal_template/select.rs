@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    JournalTemplate,
+    JournalTemplateLine,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct JournalTemplateSelectResult {
+    template_id: i32,
+    template_name: String,
+    transaction_type: String,
+    description: Option<String>,
+    tax_code: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct JournalTemplateLineSelectResult {
+    template_id: i32,
+    side: String,
+    account_name: Option<String>,
+    account_param: Option<String>,
+    amount_source: String,
+}
+
+impl JournalTemplate {
+
+    fn with_lines(
+        templates: Vec<JournalTemplateSelectResult>,
+        lines: Vec<JournalTemplateLineSelectResult>,
+    ) -> Vec<JournalTemplate> {
+        let mut id_map = HashMap::<i32, Vec<JournalTemplateLine>>::new();
+        for line in &lines {
+            id_map.entry(line.template_id).or_default().push(line.into());
+        }
+        templates.iter().map(|t| {
+            let mut template: JournalTemplate = t.into();
+            template.lines = id_map.remove(&t.template_id).unwrap_or_default();
+            template
+        }).collect()
+    }
+
+    async fn lines_of(
+        db: &Db,
+        template_id: Option<i32>,
+    ) -> Result<Vec<JournalTemplateLineSelectResult>, Error> {
+        let query = sqlx::query_as::<_, JournalTemplateLineSelectResult>(
+            r#"
+            SELECT
+                l.template_id,
+                l.side,
+                a.account_name,
+                l.account_param,
+                l.amount_source
+            FROM journal_template_lines l
+                LEFT JOIN accounts a
+                ON l.account_id = a.account_id
+            WHERE $1::INT IS NULL OR l.template_id = $1
+            ORDER BY
+                l.template_id ASC,
+                l.template_line_id ASC
+            "#
+        )
+        .bind(template_id);
+
+        Ok(query.fetch_all(&db.conn).await?)
+    }
+
+    pub async fn by_name(
+        db: &Db,
+        template_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, JournalTemplateSelectResult>(
+            r#"
+            SELECT
+                template_id,
+                template_name,
+                transaction_type,
+                description,
+                tax_code
+            FROM journal_templates
+            WHERE template_name = $1
+            "#
+        )
+        .bind(template_name);
+
+        let template = query.fetch_one(&db.conn).await?;
+        let lines = JournalTemplate::lines_of(
+            db, Some(template.template_id),
+        ).await?;
+        JournalTemplate::with_lines(vec![template], lines).pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, JournalTemplateSelectResult>(
+            r#"
+            SELECT
+                template_id,
+                template_name,
+                transaction_type,
+                description,
+                tax_code
+            FROM journal_templates
+            ORDER BY
+                template_id ASC
+            "#
+        );
+
+        let templates = query.fetch_all(&db.conn).await?;
+        let lines = JournalTemplate::lines_of(db, None).await?;
+        Ok(JournalTemplate::with_lines(templates, lines))
+    }
+
+}
+
+impl From<&JournalTemplateSelectResult> for JournalTemplate {
+
+    fn from(
+        value: &JournalTemplateSelectResult,
+    ) -> Self {
+        JournalTemplate {
+            template_id: value.template_id,
+            template_name: value.template_name.clone(),
+            transaction_type: (&value.transaction_type).into(),
+            description: value.description.clone().unwrap_or_default(),
+            tax_code: value.tax_code.as_ref().map(|t| t.into()),
+            lines: Vec::new(),
+        }
+    }
+
+}
+
+impl From<&JournalTemplateLineSelectResult> for JournalTemplateLine {
+
+    fn from(
+        value: &JournalTemplateLineSelectResult,
+    ) -> Self {
+        JournalTemplateLine {
+            side: (&value.side).into(),
+            account_name: value.account_name.clone(),
+            account_param: value.account_param.clone(),
+            amount_source: (&value.amount_source).into(),
+        }
+    }
+
+}
@@ -2,6 +2,26 @@ mod db;
 mod account;
 mod transaction;
 mod summary;
+mod budget;
+mod cash_flow;
+mod csv_mapping;
+mod staged_line;
+mod credit_card;
+mod partner;
+mod import_rule;
+mod reconciliation;
+mod transfer_source;
+mod payment;
+mod recurring;
+mod holiday;
+mod journal_template;
+mod account_mapping;
+mod backup;
+mod migration;
+mod user;
+mod share_link;
+mod review;
+mod exchange_rate;
 
 use std::convert::From;
 use thiserror::Error;
@@ -10,6 +30,26 @@ pub use db::*;
 pub use account::*;
 pub use transaction::*;
 pub use summary::*;
+pub use budget::*;
+pub use cash_flow::*;
+pub use csv_mapping::*;
+pub use staged_line::*;
+pub use credit_card::*;
+pub use partner::*;
+pub use import_rule::*;
+pub use reconciliation::*;
+pub use transfer_source::*;
+pub use payment::*;
+pub use recurring::*;
+pub use holiday::*;
+pub use journal_template::*;
+pub use account_mapping::*;
+pub use backup::*;
+pub use migration::*;
+pub use user::*;
+pub use share_link::*;
+pub use review::*;
+pub use exchange_rate::*;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -23,6 +63,18 @@ pub enum Error {
     DateTimeError,
     #[error("decimal conversion failed '{0}'")]
     DecimalConvError(f32),
+    #[error("invalid backup: {0}")]
+    InvalidBackup(String),
+    #[error("schema version {0} does not match version {1} of this build")]
+    SchemaVersionError(i64, i64),
+    #[error("the cursor is not of this sort")]
+    InvalidCursor,
+    #[error("'{0}' is left to the owner")]
+    ClosingForbidden(String),
+    #[error("{0} journals need review and {1} comments are open")]
+    ReviewOpen(i64, i64),
+    #[error("payable '{0}' is already in a payment")]
+    PayableTaken(i32),
 }
 
 impl From<sqlx::Error> for Error {
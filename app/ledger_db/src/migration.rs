@@ -0,0 +1,138 @@
+mod select;
+mod apply;
+
+use chrono::NaiveDateTime;
+
+// A step of the schema, embedded in the binary. Steps are applied in
+// the order of their versions and never changed once released; a new
+// schema change is a new step at the end.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_table",
+        sql: include_str!("../migrations/0001_create_table.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "insert_account",
+        sql: include_str!("../migrations/0002_insert_account.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_budget",
+        sql: include_str!("../migrations/0003_create_budget.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "create_cash_flow",
+        sql: include_str!("../migrations/0004_create_cash_flow.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "create_staged_line",
+        sql: include_str!("../migrations/0005_create_staged_line.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "create_credit_card",
+        sql: include_str!("../migrations/0006_create_credit_card.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "insert_csv_mapping",
+        sql: include_str!("../migrations/0007_insert_csv_mapping.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "create_import_rule",
+        sql: include_str!("../migrations/0008_create_import_rule.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "create_reconciliation",
+        sql: include_str!("../migrations/0009_create_reconciliation.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "create_payment",
+        sql: include_str!("../migrations/0010_create_payment.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "create_recurring",
+        sql: include_str!("../migrations/0011_create_recurring.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "create_journal_template",
+        sql: include_str!("../migrations/0012_create_journal_template.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "insert_journal_template",
+        sql: include_str!("../migrations/0013_insert_journal_template.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "create_account_mapping",
+        sql: include_str!("../migrations/0014_create_account_mapping.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "create_user",
+        sql: include_str!("../migrations/0015_create_user.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "create_share_link",
+        sql: include_str!("../migrations/0016_create_share_link.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "create_review",
+        sql: include_str!("../migrations/0017_create_review.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "create_exchange_rate",
+        sql: include_str!("../migrations/0018_create_exchange_rate.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "create_journal_search",
+        sql: include_str!("../migrations/0019_create_journal_search.sql"),
+    },
+];
+
+// the table the applied steps are recorded in. it is not a part of
+// the books and is left out of backups.
+pub const MIGRATION_TABLE: &str = "schema_migrations";
+
+// the version of the last step applied to databases created by the
+// db/init scripts before the steps were embedded: the tables of the
+// first release. their accounts are skipped by the step after it.
+const BASELINE_VERSION: i64 = 1;
+
+#[derive(Debug)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub applied_at: NaiveDateTime,
+    // recorded as found in the database, not applied by us
+    pub baseline: bool,
+}
+
+impl Migration {
+
+    pub fn latest_version() -> i64 {
+        MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+    }
+
+}
@@ -0,0 +1,112 @@
+use sqlx::PgConnection;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    Migration,
+    BASELINE_VERSION,
+    MIGRATIONS,
+};
+
+async fn create_migration_table(
+    conn: &mut PgConnection,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS public.schema_migrations (
+            version BIGINT PRIMARY KEY,
+            migration_name VARCHAR(255) NOT NULL,
+            applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            baseline BOOLEAN NOT NULL DEFAULT FALSE
+        )
+        "#
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn record(
+    conn: &mut PgConnection,
+    migration: &Migration,
+    baseline: bool,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO schema_migrations
+            (version, migration_name, baseline)
+        VALUES ($1, $2, $3)
+        "#
+    )
+    .bind(migration.version)
+    .bind(migration.name)
+    .bind(baseline)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+impl Migration {
+
+    // Applies the steps the database has not had yet in one transaction
+    // and returns their versions, so a failing step leaves the database
+    // as it was. A database with the tables but no record of the steps
+    // was created by the db/init scripts and is taken as being at the
+    // baseline.
+    pub async fn run_pending(
+        db: &Db,
+    ) -> Result<Vec<i64>, Error> {
+        let mut tx = db.conn.begin().await?;
+        // one instance migrates at a time
+        sqlx::query(
+            r#"
+            SELECT PG_ADVISORY_XACT_LOCK(HASHTEXT('schema_migrations'))
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        let untracked = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT
+                TO_REGCLASS('public.schema_migrations') IS NULL
+                AND TO_REGCLASS('public.accounts') IS NOT NULL
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        create_migration_table(&mut tx).await?;
+        if untracked {
+            for migration in MIGRATIONS.iter()
+                .filter(|m| m.version <= BASELINE_VERSION)
+            {
+                record(&mut tx, migration, true).await?;
+            }
+        }
+        let current = sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+            SELECT MAX(version) FROM schema_migrations
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .unwrap_or(0);
+
+        let mut applied = Vec::new();
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            sqlx::raw_sql(migration.sql)
+                .execute(&mut *tx)
+                .await?;
+            record(&mut tx, migration, false).await?;
+            applied.push(migration.version);
+        }
+        tx.commit().await?;
+
+        Ok(applied)
+    }
+
+}
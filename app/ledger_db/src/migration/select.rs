@@ -0,0 +1,84 @@
+use chrono::NaiveDateTime;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    AppliedMigration,
+    Migration,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct AppliedMigrationSelectResult {
+    version: i64,
+    migration_name: String,
+    applied_at: NaiveDateTime,
+    baseline: bool,
+}
+
+impl From<&AppliedMigrationSelectResult> for AppliedMigration {
+
+    fn from(
+        value: &AppliedMigrationSelectResult,
+    ) -> Self {
+        AppliedMigration {
+            version: value.version,
+            name: value.migration_name.clone(),
+            applied_at: value.applied_at,
+            baseline: value.baseline,
+        }
+    }
+
+}
+
+impl Migration {
+
+    // nothing when the database has never been migrated
+    pub async fn applied(
+        db: &Db,
+    ) -> Result<Vec<AppliedMigration>, Error> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT TO_REGCLASS('public.schema_migrations') IS NOT NULL
+            "#
+        )
+        .fetch_one(&db.conn)
+        .await?;
+        if !exists { return Ok(Vec::new()); }
+
+        let applied = sqlx::query_as::<_, AppliedMigrationSelectResult>(
+            r#"
+            SELECT
+                version, migration_name, applied_at, baseline
+            FROM schema_migrations
+            ORDER BY version ASC
+            "#
+        )
+        .fetch_all(&db.conn)
+        .await?;
+
+        Ok(applied.iter().map(|a| a.into()).collect())
+    }
+
+    pub async fn current_version(
+        db: &Db,
+    ) -> Result<i64, Error> {
+        Ok(Self::applied(db).await?.last().map(|a| a.version).unwrap_or(0))
+    }
+
+    // The database must be at the version of this build. An older one
+    // needs the pending steps, a newer one a newer build.
+    pub async fn check(
+        db: &Db,
+    ) -> Result<i64, Error> {
+        let current = Self::current_version(db).await?;
+        let latest = Self::latest_version();
+        match current == latest {
+            true => Ok(current),
+            false => Err(Error::SchemaVersionError(current, latest)),
+        }
+    }
+
+}
@@ -0,0 +1,29 @@
+mod partner_type;
+mod insert;
+mod select;
+
+pub use partner_type::*;
+pub(crate) use insert::partner_id_of;
+
+// a bank account as the Zengin records describe it,
+// names in half-width kana
+#[derive(Debug, Clone)]
+pub struct BankAccount {
+    pub bank_code: String,
+    pub bank_name: String,
+    pub branch_code: String,
+    pub branch_name: String,
+    pub deposit_kind: DepositKind,
+    pub account_number: String,
+    pub account_holder: String,
+}
+
+// a customer or supplier a transaction is made with.
+// bank_account is where transfers to the partner are paid into.
+#[derive(Debug)]
+pub struct Partner {
+    pub partner_id: i32,
+    pub partner_name: String,
+    pub bank_account: Option<BankAccount>,
+    pub fee_bearer: FeeBearer,
+}
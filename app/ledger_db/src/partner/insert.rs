@@ -0,0 +1,88 @@
+use sqlx::PgExecutor;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Partner;
+
+#[derive(Debug, sqlx::FromRow)]
+struct PartnerInsertResult {
+    partner_id: i32,
+}
+
+// partners are registered on their first use, within the transaction
+// of the journal when there is one so that a failed post leaves none
+pub(crate) async fn partner_id_of<'e>(
+    conn: impl PgExecutor<'e>,
+    partner_name: &str,
+) -> Result<i32, Error> {
+    let query = sqlx::query_as::<_, PartnerInsertResult>(
+        r#"
+        INSERT INTO partners
+            (partner_name)
+        VALUES ($1)
+        ON CONFLICT (partner_name)
+        DO UPDATE SET
+            partner_name = EXCLUDED.partner_name
+        RETURNING
+            partner_id
+        "#
+    )
+    .bind(partner_name);
+
+    Ok(query.fetch_one(conn).await?.partner_id)
+}
+
+impl Partner {
+
+    // the bank details are replaced, a partner without them keeps
+    // the ones registered before
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let bank = self.bank_account.as_ref();
+        let query = sqlx::query_as::<_, PartnerInsertResult>(
+            r#"
+            INSERT INTO partners
+                (partner_name, bank_code, bank_name, branch_code,
+                branch_name, deposit_kind, account_number, account_holder,
+                fee_bearer)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (partner_name)
+            DO UPDATE SET
+                bank_code = COALESCE(EXCLUDED.bank_code, partners.bank_code),
+                bank_name = COALESCE(EXCLUDED.bank_name, partners.bank_name),
+                branch_code
+                    = COALESCE(EXCLUDED.branch_code, partners.branch_code),
+                branch_name
+                    = COALESCE(EXCLUDED.branch_name, partners.branch_name),
+                deposit_kind
+                    = COALESCE(EXCLUDED.deposit_kind, partners.deposit_kind),
+                account_number = COALESCE(
+                    EXCLUDED.account_number, partners.account_number
+                ),
+                account_holder = COALESCE(
+                    EXCLUDED.account_holder, partners.account_holder
+                ),
+                fee_bearer = EXCLUDED.fee_bearer
+            RETURNING
+                partner_id
+            "#
+        )
+        .bind(&self.partner_name)
+        .bind(bank.map(|b| &b.bank_code))
+        .bind(bank.map(|b| &b.bank_name))
+        .bind(bank.map(|b| &b.branch_code))
+        .bind(bank.map(|b| &b.branch_name))
+        .bind(bank.map(|b| b.deposit_kind.to_string()))
+        .bind(bank.map(|b| &b.account_number))
+        .bind(bank.map(|b| &b.account_holder))
+        .bind(self.fee_bearer.to_string());
+
+        Ok(query.fetch_one(&db.conn).await?.partner_id)
+    }
+
+}
@@ -0,0 +1,110 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum DepositKind {
+    Ordinary,  // 普通
+    Current,  // 当座
+    Savings,  // 貯蓄
+    Other,  // その他
+}
+
+impl DepositKind {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            DepositKind::Ordinary => "普通".to_string(),
+            DepositKind::Current => "当座".to_string(),
+            DepositKind::Savings => "貯蓄".to_string(),
+            DepositKind::Other => "その他".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "普通" => Some(DepositKind::Ordinary),
+            "当座" => Some(DepositKind::Current),
+            "貯蓄" => Some(DepositKind::Savings),
+            "その他" => Some(DepositKind::Other),
+            _ => None,
+        }
+    }
+
+    // 預金種目 of the Zengin records
+    pub fn code(&self) -> &'static str {
+        match self {
+            DepositKind::Ordinary => "1",
+            DepositKind::Current => "2",
+            DepositKind::Savings => "4",
+            DepositKind::Other => "9",
+        }
+    }
+
+}
+
+impl From<&String> for DepositKind {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        DepositKind::from_str(value)
+        .unwrap_or_else(|_| {
+            DepositKind::from_japanese(value)
+            .unwrap_or(DepositKind::Ordinary)
+        })
+    }
+
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum FeeBearer {
+    Payer,  // 当方負担
+    Payee,  // 先方負担
+}
+
+impl FeeBearer {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            FeeBearer::Payer => "当方負担".to_string(),
+            FeeBearer::Payee => "先方負担".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "当方負担" => Some(FeeBearer::Payer),
+            "先方負担" => Some(FeeBearer::Payee),
+            _ => None,
+        }
+    }
+
+}
+
+impl From<&String> for FeeBearer {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        FeeBearer::from_str(value)
+        .unwrap_or_else(|_| {
+            FeeBearer::from_japanese(value)
+            .unwrap_or(FeeBearer::Payer)
+        })
+    }
+
+}
@@ -0,0 +1,102 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    BankAccount,
+    DepositKind,
+    Partner,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct PartnerSelectResult {
+    partner_id: i32,
+    partner_name: String,
+    bank_code: Option<String>,
+    bank_name: Option<String>,
+    branch_code: Option<String>,
+    branch_name: Option<String>,
+    deposit_kind: Option<String>,
+    account_number: Option<String>,
+    account_holder: Option<String>,
+    fee_bearer: String,
+}
+
+impl Partner {
+
+    pub async fn by_name(
+        db: &Db,
+        partner_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, PartnerSelectResult>(
+            r#"
+            SELECT
+                partner_id, partner_name,
+                bank_code, bank_name, branch_code, branch_name,
+                deposit_kind, account_number, account_holder,
+                fee_bearer
+            FROM partners
+            WHERE partner_name = $1
+            "#
+        )
+        .bind(partner_name);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, PartnerSelectResult>(
+            r#"
+            SELECT
+                partner_id, partner_name,
+                bank_code, bank_name, branch_code, branch_name,
+                deposit_kind, account_number, account_holder,
+                fee_bearer
+            FROM partners
+            ORDER BY partner_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<Partner>>())
+    }
+
+}
+
+impl From<&PartnerSelectResult> for Partner {
+
+    fn from(
+        value: &PartnerSelectResult,
+    ) -> Self {
+        let bank_account = match (
+            &value.bank_code, &value.branch_code, &value.account_number,
+        ) {
+            (Some(bank_code), Some(branch_code), Some(account_number))
+                => Some(BankAccount {
+                    bank_code: bank_code.clone(),
+                    bank_name: value.bank_name.clone().unwrap_or_default(),
+                    branch_code: branch_code.clone(),
+                    branch_name: value.branch_name.clone().unwrap_or_default(),
+                    deposit_kind: value.deposit_kind.as_ref()
+                        .map(|d| d.into())
+                        .unwrap_or(DepositKind::Ordinary),
+                    account_number: account_number.clone(),
+                    account_holder
+                        : value.account_holder.clone().unwrap_or_default(),
+                }),
+            _ => None,
+        };
+        Partner {
+            partner_id: value.partner_id,
+            partner_name: value.partner_name.clone(),
+            bank_account,
+            fee_bearer: (&value.fee_bearer).into(),
+        }
+    }
+
+}
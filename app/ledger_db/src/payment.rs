@@ -0,0 +1,45 @@
+mod payment_type;
+mod insert;
+mod select;
+mod update;
+
+use chrono::NaiveDate;
+
+pub use payment_type::*;
+
+// a batch of transfers paid from the transfer source of
+// source_account_name, one per partner of the items
+#[derive(Debug)]
+pub struct Payment {
+    pub payment_id: i32,
+    pub source_account_name: String,
+    pub transfer_date: NaiveDate,
+    pub fee_amount: f32,
+    pub payment_status: PaymentStatus,
+    pub items: Vec<PaymentItem>,
+}
+
+// a payable paid by the payment. transaction_id is the payment
+// journal, set when the payment is executed.
+#[derive(Debug)]
+pub struct PaymentItem {
+    pub payment_item_id: i32,
+    pub transaction_detail_id: i32,
+    pub partner_name: String,
+    pub account_name: String,
+    pub amount: f32,
+    pub transaction_id: Option<i32>,
+}
+
+// a payable credited with a partner, not in any payment yet and not
+// settled by hand, amount is what is left of it
+#[derive(Debug)]
+pub struct OpenPayable {
+    pub transaction_detail_id: i32,
+    pub transaction_id: i32,
+    pub transaction_date: NaiveDate,
+    pub description: String,
+    pub partner_name: String,
+    pub account_name: String,
+    pub amount: f32,
+}
@@ -0,0 +1,74 @@
+use crate::{
+    Db,
+    Error,
+    partner::partner_id_of,
+    staged_line::decimal_of,
+    transfer_source::TransferSource,
+};
+
+use super::Payment;
+
+#[derive(Debug, sqlx::FromRow)]
+struct PaymentInsertResult {
+    payment_id: i32,
+}
+
+impl Payment {
+
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let source_id = TransferSource::by_account(
+            db, &self.source_account_name,
+        ).await?.source_id;
+        let mut tx = db.conn.begin().await?;
+        let mut partner_ids = Vec::new();
+        for item in &self.items {
+            partner_ids.push(partner_id_of(&mut *tx, &item.partner_name).await?);
+        }
+
+        let payment_id = sqlx::query_as::<_, PaymentInsertResult>(
+            r#"
+            INSERT INTO payments
+                (source_id, transfer_date, fee_amount, payment_status)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                payment_id
+            "#
+        )
+        .bind(source_id)
+        .bind(self.transfer_date)
+        .bind(decimal_of(self.fee_amount)?)
+        .bind(self.payment_status.to_string())
+        .fetch_one(&mut *tx)
+        .await?.payment_id;
+
+        // a payable another payment took meanwhile fails the whole one
+        for (item, partner_id) in self.items.iter().zip(partner_ids) {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO payment_items
+                    (payment_id, transaction_detail_id, partner_id, amount)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (transaction_detail_id) WHERE NOT cancelled
+                DO NOTHING
+                "#
+            )
+            .bind(payment_id)
+            .bind(item.transaction_detail_id)
+            .bind(partner_id)
+            .bind(decimal_of(item.amount)?)
+            .execute(&mut *tx)
+            .await?;
+            if result.rows_affected() == 0 {
+                return Err(Error::PayableTaken(item.transaction_detail_id));
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(payment_id)
+    }
+
+}
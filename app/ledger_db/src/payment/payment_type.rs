@@ -0,0 +1,38 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum PaymentStatus {
+    Pending,  // 振込待ち
+    Executed,  // 振込済
+    Cancelled,  // 取消
+}
+
+impl PaymentStatus {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            PaymentStatus::Pending => "振込待ち".to_string(),
+            PaymentStatus::Executed => "振込済".to_string(),
+            PaymentStatus::Cancelled => "取消".to_string(),
+        }
+    }
+
+}
+
+impl From<&String> for PaymentStatus {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        PaymentStatus::from_str(value).unwrap_or(PaymentStatus::Pending)
+    }
+
+}
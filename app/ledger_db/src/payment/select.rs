@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::convert::From;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    OpenPayable,
+    Payment,
+    PaymentItem,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct PaymentSelectResult {
+    payment_id: i32,
+    source_account_name: String,
+    transfer_date: NaiveDate,
+    fee_amount: Decimal,
+    payment_status: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PaymentItemSelectResult {
+    payment_id: i32,
+    payment_item_id: i32,
+    transaction_detail_id: i32,
+    partner_name: String,
+    account_name: String,
+    amount: Decimal,
+    transaction_id: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct OpenPayableSelectResult {
+    transaction_detail_id: i32,
+    transaction_id: i32,
+    transaction_date: NaiveDate,
+    description: Option<String>,
+    partner_name: String,
+    account_name: String,
+    amount: Decimal,
+}
+
+impl Payment {
+
+    fn with_items(
+        payments: Vec<PaymentSelectResult>,
+        items: Vec<PaymentItemSelectResult>,
+    ) -> Vec<Payment> {
+        let mut id_map = HashMap::<i32, Vec<PaymentItem>>::new();
+        for item in &items {
+            id_map.entry(item.payment_id).or_default().push(item.into());
+        }
+        payments.iter().map(|p| {
+            let mut payment: Payment = p.into();
+            payment.items = id_map.remove(&p.payment_id).unwrap_or_default();
+            payment
+        }).collect()
+    }
+
+    async fn items_of(
+        db: &Db,
+        payment_id: Option<i32>,
+    ) -> Result<Vec<PaymentItemSelectResult>, Error> {
+        let query = sqlx::query_as::<_, PaymentItemSelectResult>(
+            r#"
+            SELECT
+                i.payment_id,
+                i.payment_item_id,
+                i.transaction_detail_id,
+                p.partner_name,
+                a.account_name,
+                i.amount,
+                i.transaction_id
+            FROM payment_items i
+                INNER JOIN partners p
+                ON i.partner_id = p.partner_id
+                INNER JOIN transaction_details td
+                ON i.transaction_detail_id = td.transaction_detail_id
+                INNER JOIN accounts a
+                ON td.account_id = a.account_id
+            WHERE $1::INT IS NULL OR i.payment_id = $1
+            ORDER BY
+                i.payment_id ASC,
+                p.partner_name ASC,
+                i.payment_item_id ASC
+            "#
+        )
+        .bind(payment_id);
+
+        Ok(query.fetch_all(&db.conn).await?)
+    }
+
+    pub async fn by_id(
+        db: &Db,
+        payment_id: i32,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, PaymentSelectResult>(
+            r#"
+            SELECT
+                p.payment_id,
+                a.account_name AS source_account_name,
+                p.transfer_date,
+                p.fee_amount,
+                p.payment_status
+            FROM payments p
+                INNER JOIN transfer_sources s
+                ON p.source_id = s.source_id
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+            WHERE p.payment_id = $1
+            "#
+        )
+        .bind(payment_id);
+
+        let payment = query.fetch_one(&db.conn).await?;
+        let items = Payment::items_of(db, Some(payment_id)).await?;
+        Payment::with_items(vec![payment], items).pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, PaymentSelectResult>(
+            r#"
+            SELECT
+                p.payment_id,
+                a.account_name AS source_account_name,
+                p.transfer_date,
+                p.fee_amount,
+                p.payment_status
+            FROM payments p
+                INNER JOIN transfer_sources s
+                ON p.source_id = s.source_id
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+            ORDER BY
+                p.transfer_date ASC,
+                p.payment_id ASC
+            "#
+        );
+
+        let payments = query.fetch_all(&db.conn).await?;
+        let items = Payment::items_of(db, None).await?;
+        Ok(Payment::with_items(payments, items))
+    }
+
+}
+
+impl OpenPayable {
+
+    // Credits of the accounts matching one of the LIKE patterns, booked
+    // with a partner and not in a payment other than cancelled. A payment
+    // settles the credits of its items, the debits booked by hand against
+    // the account and partner settle what is left from the oldest credit
+    // on; what is left after both is the open amount.
+    pub async fn by_accounts(
+        db: &Db,
+        account_patterns: &[String],
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, OpenPayableSelectResult>(
+            r#"
+            WITH credits AS (
+                SELECT
+                    td.transaction_detail_id,
+                    t.transaction_id,
+                    t.transaction_date,
+                    t.description,
+                    t.partner_id,
+                    td.account_id,
+                    paid.amount IS NOT NULL AS in_payment,
+                    td.credit_amount - COALESCE(paid.amount, 0)
+                        AS unpaid_amount,
+                    SUM(td.credit_amount - COALESCE(paid.amount, 0)) OVER (
+                        PARTITION BY td.account_id, t.partner_id
+                        ORDER BY t.transaction_date, td.transaction_detail_id
+                    ) AS running_amount
+                FROM transaction_details td
+                    INNER JOIN transactions t
+                    ON td.transaction_id = t.transaction_id
+                    INNER JOIN accounts a
+                    ON td.account_id = a.account_id
+                    LEFT JOIN (
+                        SELECT
+                            transaction_detail_id,
+                            SUM(amount) AS amount
+                        FROM payment_items
+                        WHERE NOT cancelled
+                        GROUP BY transaction_detail_id
+                    ) paid
+                    ON td.transaction_detail_id = paid.transaction_detail_id
+                WHERE
+                    a.account_name LIKE ANY($1)
+                    AND t.partner_id IS NOT NULL
+                    AND td.credit_amount > 0
+            ),
+            settled AS (
+                SELECT
+                    td.account_id,
+                    t.partner_id,
+                    SUM(td.debit_amount) AS settled_amount
+                FROM transaction_details td
+                    INNER JOIN transactions t
+                    ON td.transaction_id = t.transaction_id
+                WHERE
+                    t.partner_id IS NOT NULL
+                    AND td.debit_amount > 0
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM payment_items i
+                        WHERE i.transaction_id = t.transaction_id
+                    )
+                GROUP BY
+                    td.account_id,
+                    t.partner_id
+            )
+            SELECT
+                c.transaction_detail_id,
+                c.transaction_id,
+                c.transaction_date,
+                c.description,
+                p.partner_name,
+                a.account_name,
+                LEAST(
+                    c.unpaid_amount,
+                    c.running_amount - COALESCE(s.settled_amount, 0)
+                ) AS amount
+            FROM credits c
+                INNER JOIN accounts a
+                ON c.account_id = a.account_id
+                INNER JOIN partners p
+                ON c.partner_id = p.partner_id
+                LEFT JOIN settled s
+                ON c.account_id = s.account_id
+                AND c.partner_id = s.partner_id
+            WHERE
+                NOT c.in_payment
+                AND c.running_amount - COALESCE(s.settled_amount, 0) > 0
+            ORDER BY
+                p.partner_name ASC,
+                c.transaction_date ASC,
+                c.transaction_detail_id ASC
+            "#
+        )
+        .bind(account_patterns);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<OpenPayable>>())
+    }
+
+}
+
+impl From<&PaymentSelectResult> for Payment {
+
+    fn from(
+        value: &PaymentSelectResult,
+    ) -> Self {
+        Payment {
+            payment_id: value.payment_id,
+            source_account_name: value.source_account_name.clone(),
+            transfer_date: value.transfer_date,
+            fee_amount: value.fee_amount.to_f32().unwrap_or(0_f32),
+            payment_status: (&value.payment_status).into(),
+            items: Vec::new(),
+        }
+    }
+
+}
+
+impl From<&PaymentItemSelectResult> for PaymentItem {
+
+    fn from(
+        value: &PaymentItemSelectResult,
+    ) -> Self {
+        PaymentItem {
+            payment_item_id: value.payment_item_id,
+            transaction_detail_id: value.transaction_detail_id,
+            partner_name: value.partner_name.clone(),
+            account_name: value.account_name.clone(),
+            amount: value.amount.to_f32().unwrap_or(0_f32),
+            transaction_id: value.transaction_id,
+        }
+    }
+
+}
+
+impl From<&OpenPayableSelectResult> for OpenPayable {
+
+    fn from(
+        value: &OpenPayableSelectResult,
+    ) -> Self {
+        OpenPayable {
+            transaction_detail_id: value.transaction_detail_id,
+            transaction_id: value.transaction_id,
+            transaction_date: value.transaction_date,
+            description: value.description.clone().unwrap_or_default(),
+            partner_name: value.partner_name.clone(),
+            account_name: value.account_name.clone(),
+            amount: value.amount.to_f32().unwrap_or(0_f32),
+        }
+    }
+
+}
@@ -0,0 +1,111 @@
+use crate::{
+    Db,
+    Error,
+    transaction::Transaction,
+};
+
+use super::{
+    Payment,
+    PaymentStatus,
+};
+
+impl Payment {
+
+    // moves the payment on from the status it is expected to be in, the
+    // items of a cancelled one leave their payables open again
+    pub async fn change_status(
+        db: &Db,
+        payment_id: i32,
+        from: &PaymentStatus,
+        to: &PaymentStatus,
+    ) -> Result<(), Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE payments
+            SET payment_status = $3
+            WHERE
+                payment_id = $1
+                AND payment_status = $2
+            "#
+        )
+        .bind(payment_id)
+        .bind(from.to_string())
+        .bind(to.to_string())
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 { return Err(Error::RowNotFound); }
+
+        sqlx::query(
+            r#"
+            UPDATE payment_items
+            SET cancelled = $2
+            WHERE payment_id = $1
+            "#
+        )
+        .bind(payment_id)
+        .bind(*to == PaymentStatus::Cancelled)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Posts the journal of each partner and links the items to it. The
+    // payment is moved from pending to executed first, in the same
+    // transaction, so that it is executed once or not at all.
+    // RowNotFound when it is no longer pending.
+    pub async fn execute(
+        db: &Db,
+        payment_id: i32,
+        journals: &[(String, Transaction)],
+    ) -> Result<Vec<i32>, Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE payments
+            SET payment_status = $3
+            WHERE
+                payment_id = $1
+                AND payment_status = $2
+            "#
+        )
+        .bind(payment_id)
+        .bind(PaymentStatus::Pending.to_string())
+        .bind(PaymentStatus::Executed.to_string())
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 { return Err(Error::RowNotFound); }
+
+        let mut posted = Vec::new();
+        for (partner_name, transaction) in journals {
+            let transaction_id = transaction.insert_on(&mut tx).await?;
+            sqlx::query(
+                r#"
+                UPDATE payment_items
+                SET transaction_id = $3
+                WHERE
+                    payment_id = $1
+                    AND partner_id = (
+                        SELECT partner_id FROM partners WHERE partner_name = $2
+                    )
+                "#
+            )
+            .bind(payment_id)
+            .bind(partner_name)
+            .bind(transaction_id)
+            .execute(&mut *tx)
+            .await?;
+            posted.push(transaction_id);
+        }
+
+        tx.commit().await?;
+
+        Ok(posted)
+    }
+
+}
@@ -0,0 +1,33 @@
+mod reconcile_status;
+mod insert;
+mod select;
+mod update;
+
+use chrono::NaiveDate;
+
+pub use reconcile_status::*;
+
+// the bank statement of account_name for a period,
+// against which the book lines are ticked off
+#[derive(Debug)]
+pub struct Reconciliation {
+    pub reconciliation_id: i32,
+    pub account_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub statement_balance: f32,
+    pub reconcile_status: ReconcileStatus,
+}
+
+// a line of transaction_details on the reconciled account
+#[derive(Debug)]
+pub struct BookItem {
+    pub transaction_detail_id: i32,
+    pub transaction_id: i32,
+    pub transaction_date: NaiveDate,
+    pub description: String,
+    pub debit_amount: f32,
+    pub credit_amount: f32,
+    pub reconciled: bool,
+    pub reconciliation_id: Option<i32>,
+}
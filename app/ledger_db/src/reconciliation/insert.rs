@@ -0,0 +1,50 @@
+use crate::{
+    Db,
+    Error,
+    staged_line::{
+        account_id_of,
+        decimal_of,
+    },
+};
+
+use super::Reconciliation;
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReconciliationInsertResult {
+    reconciliation_id: i32,
+}
+
+impl Reconciliation {
+
+    // an open reconciliation of the same period end is restarted
+    // with the new statement
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let account_id = account_id_of(db, &self.account_name).await?;
+        let query = sqlx::query_as::<_, ReconciliationInsertResult>(
+            r#"
+            INSERT INTO reconciliations
+                (account_id, period_start, period_end,
+                statement_balance, reconcile_status)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (account_id, period_end)
+            DO UPDATE SET
+                period_start = EXCLUDED.period_start,
+                statement_balance = EXCLUDED.statement_balance
+            WHERE reconciliations.reconcile_status = EXCLUDED.reconcile_status
+            RETURNING
+                reconciliation_id
+            "#
+        )
+        .bind(account_id)
+        .bind(self.period_start)
+        .bind(self.period_end)
+        .bind(decimal_of(self.statement_balance)?)
+        .bind(self.reconcile_status.to_string());
+
+        Ok(query.fetch_one(&db.conn).await?.reconciliation_id)
+    }
+
+}
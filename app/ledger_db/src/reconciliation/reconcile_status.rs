@@ -0,0 +1,36 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum ReconcileStatus {
+    Open,  // 照合中
+    Closed,  // 照合済
+}
+
+impl ReconcileStatus {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            ReconcileStatus::Open => "照合中".to_string(),
+            ReconcileStatus::Closed => "照合済".to_string(),
+        }
+    }
+
+}
+
+impl From<&String> for ReconcileStatus {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        ReconcileStatus::from_str(value).unwrap_or(ReconcileStatus::Open)
+    }
+
+}
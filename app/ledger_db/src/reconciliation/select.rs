@@ -0,0 +1,221 @@
+use std::convert::From;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+    transaction::TransactionType,
+};
+
+use super::{
+    BookItem,
+    Reconciliation,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReconciliationSelectResult {
+    reconciliation_id: i32,
+    account_name: String,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    statement_balance: Decimal,
+    reconcile_status: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BookItemSelectResult {
+    transaction_detail_id: i32,
+    transaction_id: i32,
+    transaction_date: NaiveDate,
+    description: Option<String>,
+    debit_amount: Decimal,
+    credit_amount: Decimal,
+    reconciled: bool,
+    reconciliation_id: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BalanceSelectResult {
+    balance: Option<Decimal>,
+}
+
+impl Reconciliation {
+
+    pub async fn by_id(
+        db: &Db,
+        reconciliation_id: i32,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, ReconciliationSelectResult>(
+            r#"
+            SELECT
+                r.reconciliation_id,
+                a.account_name,
+                r.period_start,
+                r.period_end,
+                r.statement_balance,
+                r.reconcile_status
+            FROM reconciliations r
+                INNER JOIN accounts a
+                ON r.account_id = a.account_id
+            WHERE r.reconciliation_id = $1
+            "#
+        )
+        .bind(reconciliation_id);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ReconciliationSelectResult>(
+            r#"
+            SELECT
+                r.reconciliation_id,
+                a.account_name,
+                r.period_start,
+                r.period_end,
+                r.statement_balance,
+                r.reconcile_status
+            FROM reconciliations r
+                INNER JOIN accounts a
+                ON r.account_id = a.account_id
+            ORDER BY
+                r.period_end ASC,
+                r.reconciliation_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<Reconciliation>>())
+    }
+
+}
+
+impl BookItem {
+
+    // The lines of the account booked in the period, without the
+    // opening and carry-forward entries which no bank line stands for.
+    // The lines before the period still outstanding, or reconciled in
+    // this reconciliation, are taken as well.
+    pub async fn by_period(
+        db: &Db,
+        account_name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        reconciliation_id: i32,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, BookItemSelectResult>(
+            r#"
+            SELECT
+                td.transaction_detail_id,
+                t.transaction_id,
+                t.transaction_date,
+                t.description,
+                td.debit_amount,
+                td.credit_amount,
+                td.reconciled,
+                td.reconciliation_id
+            FROM transaction_details td
+                INNER JOIN transactions t
+                ON td.transaction_id = t.transaction_id
+                INNER JOIN accounts a
+                ON td.account_id = a.account_id
+            WHERE
+                a.account_name = $1
+                AND (
+                    t.transaction_date >= $2
+                    OR NOT td.reconciled
+                    OR td.reconciliation_id = $6
+                )
+                AND t.transaction_date <= $3
+                AND t.transaction_type NOT IN ($4, $5)
+            ORDER BY
+                t.transaction_date ASC,
+                td.transaction_detail_id ASC
+            "#
+        )
+        .bind(account_name)
+        .bind(start)
+        .bind(end)
+        .bind(TransactionType::FromPrev.to_string())
+        .bind(TransactionType::ToNext.to_string())
+        .bind(reconciliation_id);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<BookItem>>())
+    }
+
+    // debit minus credit of the account up to and including the date,
+    // the carry-forward entries left out
+    pub async fn balance_until(
+        db: &Db,
+        account_name: &str,
+        end: NaiveDate,
+    ) -> Result<f32, Error> {
+        let query = sqlx::query_as::<_, BalanceSelectResult>(
+            r#"
+            SELECT
+                SUM(td.debit_amount - td.credit_amount) AS balance
+            FROM transaction_details td
+                INNER JOIN transactions t
+                ON td.transaction_id = t.transaction_id
+                INNER JOIN accounts a
+                ON td.account_id = a.account_id
+            WHERE
+                a.account_name = $1
+                AND t.transaction_date <= $2
+                AND t.transaction_type <> $3
+            "#
+        )
+        .bind(account_name)
+        .bind(end)
+        .bind(TransactionType::ToNext.to_string());
+
+        Ok(query.fetch_one(&db.conn).await?.balance
+            .and_then(|b| b.to_f32()).unwrap_or(0_f32))
+    }
+
+}
+
+impl From<&ReconciliationSelectResult> for Reconciliation {
+
+    fn from(
+        value: &ReconciliationSelectResult,
+    ) -> Self {
+        Reconciliation {
+            reconciliation_id: value.reconciliation_id,
+            account_name: value.account_name.clone(),
+            period_start: value.period_start,
+            period_end: value.period_end,
+            statement_balance
+                : value.statement_balance.to_f32().unwrap_or(0_f32),
+            reconcile_status: (&value.reconcile_status).into(),
+        }
+    }
+
+}
+
+impl From<&BookItemSelectResult> for BookItem {
+
+    fn from(
+        value: &BookItemSelectResult,
+    ) -> Self {
+        BookItem {
+            transaction_detail_id: value.transaction_detail_id,
+            transaction_id: value.transaction_id,
+            transaction_date: value.transaction_date,
+            description: value.description.clone().unwrap_or_default(),
+            debit_amount: value.debit_amount.to_f32().unwrap_or(0_f32),
+            credit_amount: value.credit_amount.to_f32().unwrap_or(0_f32),
+            reconciled: value.reconciled,
+            reconciliation_id: value.reconciliation_id,
+        }
+    }
+
+}
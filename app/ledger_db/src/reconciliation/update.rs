@@ -0,0 +1,142 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use crate::staged_line::LineStatus;
+
+use super::{
+    BookItem,
+    Reconciliation,
+    ReconcileStatus,
+};
+
+impl Reconciliation {
+
+    pub async fn close(
+        db: &Db,
+        reconciliation_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE reconciliations
+            SET reconcile_status = $2
+            WHERE
+                reconciliation_id = $1
+                AND reconcile_status = $3
+            "#
+        )
+        .bind(reconciliation_id)
+        .bind(ReconcileStatus::Closed.to_string())
+        .bind(ReconcileStatus::Open.to_string())
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
+
+impl BookItem {
+
+    // Ticks off the book line, together with the statement line it was
+    // matched with if any. A draft matched with a line already in the
+    // books is discarded so that it is not posted a second time.
+    pub async fn reconcile(
+        db: &Db,
+        transaction_detail_id: i32,
+        reconciliation_id: i32,
+        staged_line_id: Option<i32>,
+    ) -> Result<(), Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE transaction_details
+            SET
+                reconciled = TRUE,
+                reconciliation_id = $2
+            WHERE
+                transaction_detail_id = $1
+                AND reconciled = FALSE
+            "#
+        )
+        .bind(transaction_detail_id)
+        .bind(reconciliation_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 { return Err(Error::RowNotFound); }
+
+        if let Some(id) = staged_line_id {
+            let result = sqlx::query(
+                r#"
+                UPDATE staged_lines
+                SET
+                    transaction_detail_id = $1,
+                    line_status = CASE
+                        WHEN line_status = $3 THEN $4
+                        ELSE line_status
+                    END
+                WHERE
+                    staged_line_id = $2
+                    AND transaction_detail_id IS NULL
+                "#
+            )
+            .bind(transaction_detail_id)
+            .bind(id)
+            .bind(LineStatus::Draft.to_string())
+            .bind(LineStatus::Discarded.to_string())
+            .execute(&mut *tx)
+            .await?;
+            if result.rows_affected() == 0 { return Err(Error::RowNotFound); }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn unreconcile(
+        db: &Db,
+        transaction_detail_id: i32,
+        reconciliation_id: i32,
+    ) -> Result<(), Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE transaction_details
+            SET
+                reconciled = FALSE,
+                reconciliation_id = NULL
+            WHERE
+                transaction_detail_id = $1
+                AND reconciliation_id = $2
+            "#
+        )
+        .bind(transaction_detail_id)
+        .bind(reconciliation_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 { return Err(Error::RowNotFound); }
+
+        sqlx::query(
+            r#"
+            UPDATE staged_lines
+            SET transaction_detail_id = NULL
+            WHERE transaction_detail_id = $1
+            "#
+        )
+        .bind(transaction_detail_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+}
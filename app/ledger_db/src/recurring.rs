@@ -0,0 +1,43 @@
+mod recurring_type;
+mod insert;
+mod select;
+mod delete;
+
+use chrono::NaiveDate;
+
+pub use recurring_type::*;
+
+// a journal posted again and again: on day_of_month of every
+// frequency months from start_date until end_date, moved onto a
+// business day as business_day says. day_of_month past the end of a
+// month is the last day of it.
+#[derive(Debug)]
+pub struct RecurringEntry {
+    pub recurring_id: i32,
+    pub recurring_name: String,
+    pub description: String,
+    pub partner_name: Option<String>,
+    pub frequency: Frequency,
+    pub day_of_month: u32,
+    pub business_day: BusinessDay,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub active: bool,
+    pub lines: Vec<RecurringLine>,
+}
+
+#[derive(Debug)]
+pub struct RecurringLine {
+    pub account_name: String,
+    pub debit_amount: f32,
+    pub credit_amount: f32,
+}
+
+// an occurrence already posted, by the date it was scheduled on
+// before the business day adjustment
+#[derive(Debug)]
+pub struct RecurringPosting {
+    pub recurring_id: i32,
+    pub scheduled_date: NaiveDate,
+    pub transaction_id: Option<i32>,
+}
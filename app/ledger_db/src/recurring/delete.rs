@@ -0,0 +1,31 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::RecurringEntry;
+
+impl RecurringEntry {
+
+    // the journals posted so far are kept
+    pub async fn delete(
+        db: &Db,
+        recurring_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM recurring_entries
+            WHERE recurring_id = $1
+            "#
+        )
+        .bind(recurring_id)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+
+use crate::{
+    Db,
+    Error,
+    partner::partner_id_of,
+    staged_line::{
+        account_id_of,
+        decimal_of,
+    },
+    transaction::Transaction,
+};
+
+use super::RecurringEntry;
+
+#[derive(Debug, sqlx::FromRow)]
+struct RecurringInsertResult {
+    recurring_id: i32,
+}
+
+impl RecurringEntry {
+
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let mut account_ids = Vec::new();
+        for line in &self.lines {
+            account_ids.push(account_id_of(db, &line.account_name).await?);
+        }
+        let mut tx = db.conn.begin().await?;
+        let partner_id = match &self.partner_name {
+            Some(name) => Some(partner_id_of(&mut *tx, name).await?),
+            None => None,
+        };
+
+        let recurring_id = sqlx::query_as::<_, RecurringInsertResult>(
+            r#"
+            INSERT INTO recurring_entries
+                (recurring_name, description, partner_id, frequency,
+                day_of_month, business_day, start_date, end_date, active)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                recurring_id
+            "#
+        )
+        .bind(&self.recurring_name)
+        .bind(&self.description)
+        .bind(partner_id)
+        .bind(self.frequency.to_string())
+        .bind(self.day_of_month as i32)
+        .bind(self.business_day.to_string())
+        .bind(self.start_date)
+        .bind(self.end_date)
+        .bind(self.active)
+        .fetch_one(&mut *tx)
+        .await?.recurring_id;
+
+        for (line, account_id) in self.lines.iter().zip(account_ids) {
+            sqlx::query(
+                r#"
+                INSERT INTO recurring_lines
+                    (recurring_id, account_id, debit_amount, credit_amount)
+                VALUES ($1, $2, $3, $4)
+                "#
+            )
+            .bind(recurring_id)
+            .bind(account_id)
+            .bind(decimal_of(line.debit_amount)?)
+            .bind(decimal_of(line.credit_amount)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(recurring_id)
+    }
+
+    // The occurrence is claimed by its posting row before the journal
+    // is inserted, in the same transaction, so that it is posted once
+    // however many schedulers run. None when it was posted already.
+    pub async fn post(
+        db: &Db,
+        recurring_id: i32,
+        scheduled_date: NaiveDate,
+        transaction: &Transaction,
+    ) -> Result<Option<i32>, Error> {
+        let mut tx = db.conn.begin().await?;
+
+        let claimed = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO recurring_postings
+                (recurring_id, scheduled_date)
+            VALUES ($1, $2)
+            ON CONFLICT (recurring_id, scheduled_date)
+            DO NOTHING
+            RETURNING
+                recurring_posting_id
+            "#
+        )
+        .bind(recurring_id)
+        .bind(scheduled_date)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let recurring_posting_id = match claimed {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let transaction_id = transaction.insert_on(&mut tx).await?;
+        sqlx::query(
+            r#"
+            UPDATE recurring_postings
+            SET transaction_id = $2
+            WHERE recurring_posting_id = $1
+            "#
+        )
+        .bind(recurring_posting_id)
+        .bind(transaction_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(transaction_id))
+    }
+
+}
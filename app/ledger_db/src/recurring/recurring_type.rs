@@ -0,0 +1,131 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum Frequency {
+    Monthly,  // 毎月
+    Quarterly,  // 四半期毎
+    HalfYearly,  // 半年毎
+    Yearly,  // 毎年
+}
+
+impl Frequency {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            Frequency::Monthly => "毎月".to_string(),
+            Frequency::Quarterly => "四半期毎".to_string(),
+            Frequency::HalfYearly => "半年毎".to_string(),
+            Frequency::Yearly => "毎年".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "毎月" => Some(Frequency::Monthly),
+            "四半期毎" => Some(Frequency::Quarterly),
+            "半年毎" => Some(Frequency::HalfYearly),
+            "毎年" => Some(Frequency::Yearly),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        Frequency::from_str(value).ok()
+            .or_else(|| Frequency::from_japanese(value))
+    }
+
+    pub fn months(&self) -> u32 {
+        match self {
+            Frequency::Monthly => 1,
+            Frequency::Quarterly => 3,
+            Frequency::HalfYearly => 6,
+            Frequency::Yearly => 12,
+        }
+    }
+
+}
+
+impl From<&String> for Frequency {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        Frequency::from_str(value)
+        .unwrap_or_else(|_| {
+            Frequency::from_japanese(value)
+            .unwrap_or(Frequency::Monthly)
+        })
+    }
+
+}
+
+// where an occurrence falling on a holiday is moved to
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum BusinessDay {
+    AsIs,  // 調整なし
+    Following,  // 翌営業日
+    Preceding,  // 前営業日
+    ModifiedFollowing,  // 翌営業日(月末は前営業日)
+}
+
+impl BusinessDay {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            BusinessDay::AsIs => "調整なし".to_string(),
+            BusinessDay::Following => "翌営業日".to_string(),
+            BusinessDay::Preceding => "前営業日".to_string(),
+            BusinessDay::ModifiedFollowing
+                => "翌営業日(月末は前営業日)".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "調整なし" => Some(BusinessDay::AsIs),
+            "翌営業日" => Some(BusinessDay::Following),
+            "前営業日" => Some(BusinessDay::Preceding),
+            "翌営業日(月末は前営業日)" => Some(BusinessDay::ModifiedFollowing),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        BusinessDay::from_str(value).ok()
+            .or_else(|| BusinessDay::from_japanese(value))
+    }
+
+}
+
+impl From<&String> for BusinessDay {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        BusinessDay::from_str(value)
+        .unwrap_or_else(|_| {
+            BusinessDay::from_japanese(value)
+            .unwrap_or(BusinessDay::Following)
+        })
+    }
+
+}
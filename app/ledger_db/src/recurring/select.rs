@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::convert::From;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    RecurringEntry,
+    RecurringLine,
+    RecurringPosting,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct RecurringSelectResult {
+    recurring_id: i32,
+    recurring_name: String,
+    description: Option<String>,
+    partner_name: Option<String>,
+    frequency: String,
+    day_of_month: i32,
+    business_day: String,
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    active: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RecurringLineSelectResult {
+    recurring_id: i32,
+    account_name: String,
+    debit_amount: Decimal,
+    credit_amount: Decimal,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RecurringPostingSelectResult {
+    recurring_id: i32,
+    scheduled_date: NaiveDate,
+    transaction_id: Option<i32>,
+}
+
+impl RecurringEntry {
+
+    fn with_lines(
+        entries: Vec<RecurringSelectResult>,
+        lines: Vec<RecurringLineSelectResult>,
+    ) -> Vec<RecurringEntry> {
+        let mut id_map = HashMap::<i32, Vec<RecurringLine>>::new();
+        for line in &lines {
+            id_map.entry(line.recurring_id).or_default().push(line.into());
+        }
+        entries.iter().map(|e| {
+            let mut entry: RecurringEntry = e.into();
+            entry.lines = id_map.remove(&e.recurring_id).unwrap_or_default();
+            entry
+        }).collect()
+    }
+
+    async fn lines_of(
+        db: &Db,
+        recurring_id: Option<i32>,
+    ) -> Result<Vec<RecurringLineSelectResult>, Error> {
+        let query = sqlx::query_as::<_, RecurringLineSelectResult>(
+            r#"
+            SELECT
+                l.recurring_id,
+                a.account_name,
+                l.debit_amount,
+                l.credit_amount
+            FROM recurring_lines l
+                INNER JOIN accounts a
+                ON l.account_id = a.account_id
+            WHERE $1::INT IS NULL OR l.recurring_id = $1
+            ORDER BY
+                l.recurring_id ASC,
+                l.recurring_line_id ASC
+            "#
+        )
+        .bind(recurring_id);
+
+        Ok(query.fetch_all(&db.conn).await?)
+    }
+
+    pub async fn by_id(
+        db: &Db,
+        recurring_id: i32,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, RecurringSelectResult>(
+            r#"
+            SELECT
+                r.recurring_id,
+                r.recurring_name,
+                r.description,
+                p.partner_name,
+                r.frequency,
+                r.day_of_month,
+                r.business_day,
+                r.start_date,
+                r.end_date,
+                r.active
+            FROM recurring_entries r
+                LEFT JOIN partners p
+                ON r.partner_id = p.partner_id
+            WHERE r.recurring_id = $1
+            "#
+        )
+        .bind(recurring_id);
+
+        let entry = query.fetch_one(&db.conn).await?;
+        let lines = RecurringEntry::lines_of(db, Some(recurring_id)).await?;
+        RecurringEntry::with_lines(vec![entry], lines).pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, RecurringSelectResult>(
+            r#"
+            SELECT
+                r.recurring_id,
+                r.recurring_name,
+                r.description,
+                p.partner_name,
+                r.frequency,
+                r.day_of_month,
+                r.business_day,
+                r.start_date,
+                r.end_date,
+                r.active
+            FROM recurring_entries r
+                LEFT JOIN partners p
+                ON r.partner_id = p.partner_id
+            ORDER BY
+                r.recurring_id ASC
+            "#
+        );
+
+        let entries = query.fetch_all(&db.conn).await?;
+        let lines = RecurringEntry::lines_of(db, None).await?;
+        Ok(RecurringEntry::with_lines(entries, lines))
+    }
+
+}
+
+impl RecurringPosting {
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, RecurringPostingSelectResult>(
+            r#"
+            SELECT
+                recurring_id,
+                scheduled_date,
+                transaction_id
+            FROM recurring_postings
+            ORDER BY
+                recurring_id ASC,
+                scheduled_date ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<RecurringPosting>>())
+    }
+
+}
+
+impl From<&RecurringSelectResult> for RecurringEntry {
+
+    fn from(
+        value: &RecurringSelectResult,
+    ) -> Self {
+        RecurringEntry {
+            recurring_id: value.recurring_id,
+            recurring_name: value.recurring_name.clone(),
+            description: value.description.clone().unwrap_or_default(),
+            partner_name: value.partner_name.clone(),
+            frequency: (&value.frequency).into(),
+            day_of_month: value.day_of_month as u32,
+            business_day: (&value.business_day).into(),
+            start_date: value.start_date,
+            end_date: value.end_date,
+            active: value.active,
+            lines: Vec::new(),
+        }
+    }
+
+}
+
+impl From<&RecurringLineSelectResult> for RecurringLine {
+
+    fn from(
+        value: &RecurringLineSelectResult,
+    ) -> Self {
+        RecurringLine {
+            account_name: value.account_name.clone(),
+            debit_amount: value.debit_amount.to_f32().unwrap_or(0_f32),
+            credit_amount: value.credit_amount.to_f32().unwrap_or(0_f32),
+        }
+    }
+
+}
+
+impl From<&RecurringPostingSelectResult> for RecurringPosting {
+
+    fn from(
+        value: &RecurringPostingSelectResult,
+    ) -> Self {
+        RecurringPosting {
+            recurring_id: value.recurring_id,
+            scheduled_date: value.scheduled_date,
+            transaction_id: value.transaction_id,
+        }
+    }
+
+}
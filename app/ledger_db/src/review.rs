@@ -0,0 +1,27 @@
+mod review_status;
+mod insert;
+mod select;
+mod update;
+
+use chrono::NaiveDateTime;
+
+pub use review_status::*;
+
+// a question or an answer on a transaction, left until resolved
+#[derive(Debug)]
+pub struct TransactionComment {
+    pub transaction_comment_id: i32,
+    pub transaction_id: i32,
+    pub comment_text: String,
+    pub resolved: bool,
+    // the name of the user who wrote it
+    pub created_by: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+// what is left open in a period before it can be closed
+#[derive(Debug)]
+pub struct ReviewCount {
+    pub needs_review: i64,
+    pub open_comments: i64,
+}
@@ -0,0 +1,50 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::TransactionComment;
+
+#[derive(Debug, sqlx::FromRow)]
+struct CommentInsertResult {
+    transaction_comment_id: i32,
+}
+
+impl TransactionComment {
+
+    // A comment puts the transaction back to needs_review. RowNotFound
+    // when there is no such transaction.
+    pub async fn insert(
+        db: &Db,
+        transaction_id: i32,
+        user_name: Option<&str>,
+        comment_text: &str,
+    ) -> Result<i32, Error> {
+        let result = sqlx::query_as::<_, CommentInsertResult>(
+            r#"
+            WITH flagged AS (
+                UPDATE transactions
+                SET review_status = 'needs_review'
+                WHERE transaction_id = $1
+                RETURNING transaction_id
+            )
+            INSERT INTO transaction_comments
+                (transaction_id, comment_text, created_by)
+            SELECT
+                f.transaction_id, $3,
+                (SELECT user_id FROM users WHERE user_name = $2)
+            FROM flagged f
+            RETURNING
+                transaction_comment_id
+            "#
+        )
+        .bind(transaction_id)
+        .bind(user_name)
+        .bind(comment_text)
+        .fetch_one(&db.conn)
+        .await?;
+
+        Ok(result.transaction_comment_id)
+    }
+
+}
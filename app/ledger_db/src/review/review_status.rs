@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReviewStatus {
+    NeedsReview,  // 要確認
+    Reviewed,  // 確認済
+}
+
+impl ReviewStatus {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            ReviewStatus::NeedsReview => "要確認".to_string(),
+            ReviewStatus::Reviewed => "確認済".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "要確認" => Some(ReviewStatus::NeedsReview),
+            "確認済" => Some(ReviewStatus::Reviewed),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        ReviewStatus::from_str(value).ok()
+            .or_else(|| ReviewStatus::from_japanese(value))
+    }
+
+}
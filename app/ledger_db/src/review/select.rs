@@ -0,0 +1,120 @@
+use std::convert::From;
+
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ReviewCount,
+    TransactionComment,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct CommentSelectResult {
+    transaction_comment_id: i32,
+    transaction_id: i32,
+    comment_text: String,
+    resolved: bool,
+    created_by: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReviewCountSelectResult {
+    needs_review: i64,
+    open_comments: i64,
+}
+
+impl TransactionComment {
+
+    pub async fn by_transaction(
+        db: &Db,
+        transaction_id: i32,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, CommentSelectResult>(
+            r#"
+            SELECT
+                c.transaction_comment_id, c.transaction_id,
+                c.comment_text, c.resolved,
+                u.user_name AS created_by, c.created_at
+            FROM transaction_comments c
+                LEFT OUTER JOIN users u
+                ON c.created_by = u.user_id
+            WHERE c.transaction_id = $1
+            ORDER BY c.transaction_comment_id ASC
+            "#
+        )
+        .bind(transaction_id);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<TransactionComment>>())
+    }
+
+}
+
+impl ReviewCount {
+
+    // the transactions from start_date to end_date still to be reviewed,
+    // and their comments not yet resolved
+    pub async fn by_period(
+        db: &Db,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, ReviewCountSelectResult>(
+            r#"
+            SELECT
+                (
+                    SELECT COUNT(*) FROM transactions t
+                    WHERE t.review_status = 'needs_review'
+                        AND t.transaction_date >= $1
+                        AND t.transaction_date <= $2
+                ) AS needs_review,
+                (
+                    SELECT COUNT(*) FROM transaction_comments c
+                        INNER JOIN transactions t
+                        ON c.transaction_id = t.transaction_id
+                    WHERE NOT c.resolved
+                        AND t.transaction_date >= $1
+                        AND t.transaction_date <= $2
+                ) AS open_comments
+            "#
+        )
+        .bind(start_date)
+        .bind(end_date);
+
+        let result = query.fetch_one(&db.conn).await?;
+        Ok(ReviewCount {
+            needs_review: result.needs_review,
+            open_comments: result.open_comments,
+        })
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.needs_review == 0 && self.open_comments == 0
+    }
+
+}
+
+impl From<&CommentSelectResult> for TransactionComment {
+
+    fn from(
+        value: &CommentSelectResult,
+    ) -> Self {
+        TransactionComment {
+            transaction_comment_id: value.transaction_comment_id,
+            transaction_id: value.transaction_id,
+            comment_text: value.comment_text.clone(),
+            resolved: value.resolved,
+            created_by: value.created_by.clone(),
+            created_at: value.created_at,
+        }
+    }
+
+}
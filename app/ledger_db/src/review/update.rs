@@ -0,0 +1,65 @@
+use crate::{
+    Db,
+    Error,
+    transaction::Transaction,
+};
+
+use super::{
+    ReviewStatus,
+    TransactionComment,
+};
+
+impl TransactionComment {
+
+    // RowNotFound unless the comment is there and still open
+    pub async fn resolve(
+        db: &Db,
+        transaction_comment_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE transaction_comments
+            SET resolved = TRUE
+            WHERE transaction_comment_id = $1
+                AND NOT resolved
+            "#
+        )
+        .bind(transaction_comment_id)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
+
+impl Transaction {
+
+    // None takes the flag off
+    pub async fn set_review_status(
+        db: &Db,
+        transaction_id: i32,
+        review_status: Option<ReviewStatus>,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE transactions
+            SET review_status = $2
+            WHERE transaction_id = $1
+            "#
+        )
+        .bind(transaction_id)
+        .bind(review_status.map(|s| s.to_string()))
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
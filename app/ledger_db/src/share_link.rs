@@ -0,0 +1,27 @@
+mod share_scope;
+mod insert;
+mod select;
+mod update;
+
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+};
+
+pub use share_scope::*;
+
+// A read-only view of the books of a period, for someone without a
+// user such as the 税理士. The link itself is signed by the app; the
+// row is what lets it be revoked.
+#[derive(Debug)]
+pub struct ShareLink {
+    pub share_link_id: i32,
+    pub share_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub scopes: Vec<ShareScope>,
+    pub expires_on: NaiveDate,
+    pub revoked_at: Option<NaiveDateTime>,
+    // the name of the user who made it
+    pub created_by: Option<String>,
+}
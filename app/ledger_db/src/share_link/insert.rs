@@ -0,0 +1,45 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ShareLink,
+    ShareScope,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct ShareLinkInsertResult {
+    share_link_id: i32,
+}
+
+impl ShareLink {
+
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let result = sqlx::query_as::<_, ShareLinkInsertResult>(
+            r#"
+            INSERT INTO share_links
+                (share_name, period_start, period_end, scopes, expires_on,
+                created_by)
+            VALUES ($1, $2, $3, $4, $5,
+                (SELECT user_id FROM users WHERE user_name = $6))
+            RETURNING
+                share_link_id
+            "#
+        )
+        .bind(&self.share_name)
+        .bind(self.period_start)
+        .bind(self.period_end)
+        .bind(ShareScope::join(&self.scopes))
+        .bind(self.expires_on)
+        .bind(&self.created_by)
+        .fetch_one(&db.conn)
+        .await?;
+
+        Ok(result.share_link_id)
+    }
+
+}
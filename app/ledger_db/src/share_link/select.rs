@@ -0,0 +1,94 @@
+use std::convert::From;
+
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ShareLink,
+    ShareScope,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct ShareLinkSelectResult {
+    share_link_id: i32,
+    share_name: String,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    scopes: String,
+    expires_on: NaiveDate,
+    revoked_at: Option<NaiveDateTime>,
+    created_by: Option<String>,
+}
+
+impl ShareLink {
+
+    pub async fn by_id(
+        db: &Db,
+        share_link_id: i32,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, ShareLinkSelectResult>(
+            r#"
+            SELECT
+                s.share_link_id, s.share_name,
+                s.period_start, s.period_end,
+                s.scopes, s.expires_on, s.revoked_at,
+                u.user_name AS created_by
+            FROM share_links s
+                LEFT OUTER JOIN users u
+                ON s.created_by = u.user_id
+            WHERE s.share_link_id = $1
+            "#
+        )
+        .bind(share_link_id);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ShareLinkSelectResult>(
+            r#"
+            SELECT
+                s.share_link_id, s.share_name,
+                s.period_start, s.period_end,
+                s.scopes, s.expires_on, s.revoked_at,
+                u.user_name AS created_by
+            FROM share_links s
+                LEFT OUTER JOIN users u
+                ON s.created_by = u.user_id
+            ORDER BY s.share_link_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<ShareLink>>())
+    }
+
+}
+
+impl From<&ShareLinkSelectResult> for ShareLink {
+
+    fn from(
+        value: &ShareLinkSelectResult,
+    ) -> Self {
+        ShareLink {
+            share_link_id: value.share_link_id,
+            share_name: value.share_name.clone(),
+            period_start: value.period_start,
+            period_end: value.period_end,
+            scopes: ShareScope::split(&value.scopes),
+            expires_on: value.expires_on,
+            revoked_at: value.revoked_at,
+            created_by: value.created_by.clone(),
+        }
+    }
+
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+// the reports a share link opens
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ShareScope {
+    Journals,  // 仕訳帳
+    GeneralLedger,  // 総勘定元帳
+    TrialBalance,  // 試算表
+    FinancialStatements,  // 決算書
+}
+
+impl ShareScope {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            ShareScope::Journals => "仕訳帳".to_string(),
+            ShareScope::GeneralLedger => "総勘定元帳".to_string(),
+            ShareScope::TrialBalance => "試算表".to_string(),
+            ShareScope::FinancialStatements => "決算書".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "仕訳帳" => Some(ShareScope::Journals),
+            "総勘定元帳" | "元帳" => Some(ShareScope::GeneralLedger),
+            "試算表" => Some(ShareScope::TrialBalance),
+            "決算書" => Some(ShareScope::FinancialStatements),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        ShareScope::from_str(value).ok()
+            .or_else(|| ShareScope::from_japanese(value))
+    }
+
+    // kept in one column as 'journals,trial_balance'
+    pub(crate) fn join(scopes: &[ShareScope]) -> String {
+        scopes.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    pub(crate) fn split(value: &str) -> Vec<ShareScope> {
+        value.split(',')
+            .filter_map(|s| ShareScope::parse(s.trim()))
+            .collect()
+    }
+
+}
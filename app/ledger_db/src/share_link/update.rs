@@ -0,0 +1,33 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::ShareLink;
+
+impl ShareLink {
+
+    // RowNotFound unless the link is there and not yet revoked
+    pub async fn revoke(
+        db: &Db,
+        share_link_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE share_links
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE share_link_id = $1
+                AND revoked_at IS NULL
+            "#
+        )
+        .bind(share_link_id)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
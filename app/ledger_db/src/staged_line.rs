@@ -0,0 +1,42 @@
+mod staged_line_type;
+mod insert;
+mod select;
+mod update;
+
+use chrono::NaiveDate;
+
+use crate::transaction::TaxCode;
+
+pub use staged_line_type::*;
+pub(crate) use insert::{
+    account_id_of,
+    decimal_of,
+};
+
+// a statement line waiting to be posted as a journal.
+// account_name is the account the statement belongs to,
+// counter_account_name is assigned before posting, either by hand or
+// proposed by the import rule rule_id.
+// transaction_detail_id is the book line it was reconciled with.
+// external_id identifies the line within the statements of the
+// account so that overlapping statements are staged only once.
+#[derive(Debug)]
+pub struct StagedLine {
+    pub staged_line_id: i32,
+    pub import_source: ImportSource,
+    pub account_name: String,
+    pub line_date: NaiveDate,
+    pub description: String,
+    pub withdrawal_amount: f32,
+    pub deposit_amount: f32,
+    pub balance_amount: Option<f32>,
+    pub counter_account_name: Option<String>,
+    pub memo: Option<String>,
+    pub line_status: LineStatus,
+    pub transaction_id: Option<i32>,
+    pub external_id: Option<String>,
+    pub tax_code: Option<TaxCode>,
+    pub partner_name: Option<String>,
+    pub rule_id: Option<i32>,
+    pub transaction_detail_id: Option<i32>,
+}
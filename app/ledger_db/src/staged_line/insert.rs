@@ -0,0 +1,84 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    Db,
+    Error,
+    account::Account,
+    partner::partner_id_of,
+};
+
+use super::StagedLine;
+
+#[derive(Debug, sqlx::FromRow)]
+struct StagedLineInsertResult {
+    staged_line_id: i32,
+}
+
+pub(crate) async fn account_id_of(
+    db: &Db,
+    account_name: &str,
+) -> Result<i32, Error> {
+    match Account::by_name(db, account_name).await {
+        Ok(acc) => Ok(acc.account_id),
+        Err(Error::RowNotFound) => Err(Error::AccountNotFound),
+        Err(err) => Err(err),
+    }
+}
+
+pub(crate) fn decimal_of(amount: f32) -> Result<Decimal, Error> {
+    Decimal::from_f32_retain(amount).ok_or(Error::DecimalConvError(amount))
+}
+
+impl StagedLine {
+
+    // returns None when a line with the same external_id
+    // is already staged for the account.
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<Option<i32>, Error> {
+        let account_id = account_id_of(db, &self.account_name).await?;
+        let counter_account_id = match &self.counter_account_name {
+            Some(name) => Some(account_id_of(db, name).await?),
+            None => None,
+        };
+        let partner_id = match &self.partner_name {
+            Some(name) => Some(partner_id_of(&db.conn, name).await?),
+            None => None,
+        };
+        let balance = match self.balance_amount {
+            Some(b) => Some(decimal_of(b)?),
+            None => None,
+        };
+        let query = sqlx::query_as::<_, StagedLineInsertResult>(
+            r#"
+            INSERT INTO staged_lines
+                (import_source, account_id, line_date, description,
+                withdrawal_amount, deposit_amount, balance_amount,
+                counter_account_id, memo, line_status, external_id,
+                tax_code, partner_id, rule_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (account_id, external_id) DO NOTHING
+            RETURNING
+                staged_line_id
+            "#
+        )
+        .bind(self.import_source.to_string())
+        .bind(account_id)
+        .bind(self.line_date)
+        .bind(&self.description)
+        .bind(decimal_of(self.withdrawal_amount)?)
+        .bind(decimal_of(self.deposit_amount)?)
+        .bind(balance)
+        .bind(counter_account_id)
+        .bind(&self.memo)
+        .bind(self.line_status.to_string())
+        .bind(&self.external_id)
+        .bind(self.tax_code.as_ref().map(|t| t.to_string()))
+        .bind(partner_id)
+        .bind(self.rule_id);
+
+        Ok(query.fetch_optional(&db.conn).await?.map(|r| r.staged_line_id))
+    }
+
+}
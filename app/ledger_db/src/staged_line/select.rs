@@ -0,0 +1,206 @@
+use std::convert::From;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::ToPrimitive,
+    Decimal,
+};
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    LineStatus,
+    StagedLine,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct StagedLineSelectResult {
+    staged_line_id: i32,
+    import_source: String,
+    account_name: String,
+    line_date: NaiveDate,
+    description: Option<String>,
+    withdrawal_amount: Decimal,
+    deposit_amount: Decimal,
+    balance_amount: Option<Decimal>,
+    counter_account_name: Option<String>,
+    memo: Option<String>,
+    line_status: String,
+    transaction_id: Option<i32>,
+    external_id: Option<String>,
+    tax_code: Option<String>,
+    partner_name: Option<String>,
+    rule_id: Option<i32>,
+    transaction_detail_id: Option<i32>,
+}
+
+impl StagedLine {
+
+    pub async fn by_id(
+        db: &Db,
+        staged_line_id: i32,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, StagedLineSelectResult>(
+            r#"
+            SELECT
+                s.staged_line_id,
+                s.import_source,
+                a.account_name,
+                s.line_date,
+                s.description,
+                s.withdrawal_amount,
+                s.deposit_amount,
+                s.balance_amount,
+                c.account_name AS counter_account_name,
+                s.memo,
+                s.line_status,
+                s.transaction_id,
+                s.external_id,
+                s.tax_code,
+                p.partner_name,
+                s.rule_id,
+                s.transaction_detail_id
+            FROM staged_lines s
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+                LEFT OUTER JOIN accounts c
+                ON s.counter_account_id = c.account_id
+                LEFT OUTER JOIN partners p
+                ON s.partner_id = p.partner_id
+            WHERE s.staged_line_id = $1
+            "#
+        )
+        .bind(staged_line_id);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn by_status(
+        db: &Db,
+        line_status: &LineStatus,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, StagedLineSelectResult>(
+            r#"
+            SELECT
+                s.staged_line_id,
+                s.import_source,
+                a.account_name,
+                s.line_date,
+                s.description,
+                s.withdrawal_amount,
+                s.deposit_amount,
+                s.balance_amount,
+                c.account_name AS counter_account_name,
+                s.memo,
+                s.line_status,
+                s.transaction_id,
+                s.external_id,
+                s.tax_code,
+                p.partner_name,
+                s.rule_id,
+                s.transaction_detail_id
+            FROM staged_lines s
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+                LEFT OUTER JOIN accounts c
+                ON s.counter_account_id = c.account_id
+                LEFT OUTER JOIN partners p
+                ON s.partner_id = p.partner_id
+            WHERE s.line_status = $1
+            ORDER BY
+                s.line_date ASC,
+                s.staged_line_id ASC
+            "#
+        )
+        .bind(line_status.to_string());
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<StagedLine>>())
+    }
+
+
+    // the statement lines of the account in the period, in any status
+    pub async fn by_account(
+        db: &Db,
+        account_name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, StagedLineSelectResult>(
+            r#"
+            SELECT
+                s.staged_line_id,
+                s.import_source,
+                a.account_name,
+                s.line_date,
+                s.description,
+                s.withdrawal_amount,
+                s.deposit_amount,
+                s.balance_amount,
+                c.account_name AS counter_account_name,
+                s.memo,
+                s.line_status,
+                s.transaction_id,
+                s.external_id,
+                s.tax_code,
+                p.partner_name,
+                s.rule_id,
+                s.transaction_detail_id
+            FROM staged_lines s
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+                LEFT OUTER JOIN accounts c
+                ON s.counter_account_id = c.account_id
+                LEFT OUTER JOIN partners p
+                ON s.partner_id = p.partner_id
+            WHERE
+                a.account_name = $1
+                AND s.line_date >= $2
+                AND s.line_date <= $3
+            ORDER BY
+                s.line_date ASC,
+                s.staged_line_id ASC
+            "#
+        )
+        .bind(account_name)
+        .bind(start)
+        .bind(end);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<StagedLine>>())
+    }
+
+}
+
+impl From<&StagedLineSelectResult> for StagedLine {
+
+    fn from(
+        value: &StagedLineSelectResult,
+    ) -> Self {
+        StagedLine {
+            staged_line_id: value.staged_line_id,
+            import_source: (&value.import_source).into(),
+            account_name: value.account_name.clone(),
+            line_date: value.line_date,
+            description: value.description.clone().unwrap_or_default(),
+            withdrawal_amount
+                : value.withdrawal_amount.to_f32().unwrap_or(0_f32),
+            deposit_amount: value.deposit_amount.to_f32().unwrap_or(0_f32),
+            balance_amount: value.balance_amount
+                .and_then(|b| b.to_f32()),
+            counter_account_name: value.counter_account_name.clone(),
+            memo: value.memo.clone(),
+            line_status: (&value.line_status).into(),
+            transaction_id: value.transaction_id,
+            external_id: value.external_id.clone(),
+            tax_code: value.tax_code.as_ref().map(|t| t.into()),
+            partner_name: value.partner_name.clone(),
+            rule_id: value.rule_id,
+            transaction_detail_id: value.transaction_detail_id,
+        }
+    }
+
+}
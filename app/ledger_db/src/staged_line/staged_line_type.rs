@@ -0,0 +1,76 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum ImportSource {
+    BankCsv,  // 銀行CSV
+    CardCsv,  // カードCSV
+    Ofx,  // OFX
+    Zengin,  // 全銀協
+}
+
+impl ImportSource {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            ImportSource::BankCsv => "銀行CSV".to_string(),
+            ImportSource::CardCsv => "カードCSV".to_string(),
+            ImportSource::Ofx => "OFX".to_string(),
+            ImportSource::Zengin => "全銀協".to_string(),
+        }
+    }
+
+}
+
+impl From<&String> for ImportSource {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        ImportSource::from_str(value).unwrap_or(ImportSource::BankCsv)
+    }
+
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum LineStatus {
+    Draft,  // 未処理
+    Posted,  // 仕訳済
+    Discarded,  // 破棄
+}
+
+impl LineStatus {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            LineStatus::Draft => "未処理".to_string(),
+            LineStatus::Posted => "仕訳済".to_string(),
+            LineStatus::Discarded => "破棄".to_string(),
+        }
+    }
+
+}
+
+impl From<&String> for LineStatus {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        LineStatus::from_str(value).unwrap_or(LineStatus::Draft)
+    }
+
+}
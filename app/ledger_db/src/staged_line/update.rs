@@ -0,0 +1,130 @@
+use crate::{
+    Db,
+    Error,
+    partner::partner_id_of,
+    transaction::Transaction,
+};
+
+use super::{
+    LineStatus,
+    StagedLine,
+    insert::account_id_of,
+};
+
+impl StagedLine {
+
+    // stores counter account, memo, tax code, partner and rule of a draft
+    pub async fn update_assignment(
+        &self,
+        db: &Db,
+    ) -> Result<(), Error> {
+        let counter_account_id = match &self.counter_account_name {
+            Some(name) => Some(account_id_of(db, name).await?),
+            None => None,
+        };
+        let partner_id = match &self.partner_name {
+            Some(name) => Some(partner_id_of(&db.conn, name).await?),
+            None => None,
+        };
+        let result = sqlx::query(
+            r#"
+            UPDATE staged_lines
+            SET
+                counter_account_id = $2,
+                memo = $3,
+                tax_code = $4,
+                partner_id = $5,
+                rule_id = $6
+            WHERE
+                staged_line_id = $1
+                AND line_status = $7
+            "#
+        )
+        .bind(self.staged_line_id)
+        .bind(counter_account_id)
+        .bind(&self.memo)
+        .bind(self.tax_code.as_ref().map(|t| t.to_string()))
+        .bind(partner_id)
+        .bind(self.rule_id)
+        .bind(LineStatus::Draft.to_string())
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    // The line is claimed before the journal is inserted, in the same
+    // transaction, so that a line is posted once however often it is
+    // asked to be. RowNotFound when it is no longer a draft.
+    pub async fn post(
+        db: &Db,
+        staged_line_id: i32,
+        transaction: &Transaction,
+    ) -> Result<i32, Error> {
+        let mut tx = db.conn.begin().await?;
+
+        sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE staged_lines
+            SET line_status = $2
+            WHERE
+                staged_line_id = $1
+                AND line_status = $3
+            RETURNING
+                staged_line_id
+            "#
+        )
+        .bind(staged_line_id)
+        .bind(LineStatus::Posted.to_string())
+        .bind(LineStatus::Draft.to_string())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(Error::RowNotFound)?;
+
+        let transaction_id = transaction.insert_on(&mut tx).await?;
+        sqlx::query(
+            r#"
+            UPDATE staged_lines
+            SET transaction_id = $2
+            WHERE staged_line_id = $1
+            "#
+        )
+        .bind(staged_line_id)
+        .bind(transaction_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(transaction_id)
+    }
+
+    pub async fn discard(
+        db: &Db,
+        staged_line_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE staged_lines
+            SET line_status = $2
+            WHERE
+                staged_line_id = $1
+                AND line_status = $3
+            "#
+        )
+        .bind(staged_line_id)
+        .bind(LineStatus::Discarded.to_string())
+        .bind(LineStatus::Draft.to_string())
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -63,7 +63,7 @@ impl Summary {
         .bind(TransactionType::FromPrev.to_string());
 
         let mut summary = query.fetch_all(&db.conn).await?
-            .iter().map(|lqr| Summary::from(lqr))
+            .iter().map(Summary::from)
             .collect::<Vec<Summary>>();
         summary.sort_by(|s1, s2| {
             s1.account_type.cmp(&s2.account_type)
@@ -109,7 +109,7 @@ impl Summary {
         .bind(TransactionType::InTerm.to_string());
 
         let mut summary = query.fetch_all(&db.conn).await?
-            .iter().map(|lqr| Summary::from(lqr))
+            .iter().map(Summary::from)
             .collect::<Vec<Summary>>();
         summary.sort_by(|s1, s2| {
             s1.account_type.cmp(&s2.account_type)
@@ -159,7 +159,7 @@ impl Summary {
         .bind(TransactionType::Kessan.to_string());
 
         let mut summary = query.fetch_all(&db.conn).await?
-            .iter().map(|lqr| Summary::from(lqr))
+            .iter().map(Summary::from)
             .collect::<Vec<Summary>>();
         summary.sort_by(|s1, s2| {
             s1.account_type.cmp(&s2.account_type)
@@ -210,7 +210,7 @@ impl Summary {
         .bind(TransactionType::Soneki.to_string());
 
         let mut summary = query.fetch_all(&db.conn).await?
-            .iter().map(|lqr| Summary::from(lqr))
+            .iter().map(Summary::from)
             .collect::<Vec<Summary>>();
         summary.sort_by(|s1, s2| {
             s1.account_type.cmp(&s2.account_type)
@@ -262,7 +262,7 @@ impl Summary {
         .bind(TransactionType::ToNext.to_string());
 
         let mut summary = query.fetch_all(&db.conn).await?
-            .iter().map(|lqr| Summary::from(lqr))
+            .iter().map(Summary::from)
             .collect::<Vec<Summary>>();
         summary.sort_by(|s1, s2| {
             s1.account_type.cmp(&s2.account_type)
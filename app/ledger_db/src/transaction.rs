@@ -1,12 +1,28 @@
 mod transaction_type;
+mod tax_code;
 mod insert;
 mod select;
+mod update;
+mod search;
+mod post;
 
 use chrono::NaiveDate;
 
 use crate::account::AccountType;
+use crate::review::ReviewStatus;
 
 pub use transaction_type::*;
+pub use tax_code::*;
+pub use search::*;
+
+// the amount of a line in another currency, on the side of the yen amount
+#[derive(Debug, Clone)]
+pub struct ForeignAmount {
+    pub currency: String,
+    pub amount: f32,
+    // yen per unit the line was converted at
+    pub rate: f32,
+}
 
 #[derive(Debug)]
 pub struct TransactionDetail {
@@ -14,6 +30,7 @@ pub struct TransactionDetail {
     pub account_type: AccountType,
     pub debit_amount: f32,
     pub credit_amount: f32,
+    pub foreign: Option<ForeignAmount>,
 }
 
 #[derive(Debug)]
@@ -22,6 +39,13 @@ pub struct Transaction {
     pub transaction_date: NaiveDate,
     pub transaction_type: TransactionType,
     pub description: String,
+    pub partner_name: Option<String>,
+    // the name of the user who posted it
+    pub created_by: Option<String>,
+    pub tags: Vec<String>,
+    // read from the database, not written by insert
+    pub review_status: Option<ReviewStatus>,
+    pub open_comments: i64,
     pub details: Vec<TransactionDetail>,
 }
 
@@ -1,9 +1,13 @@
-use rust_decimal::Decimal;
+use rust_decimal::{
+    prelude::FromPrimitive,
+    Decimal,
+};
+use sqlx::PgConnection;
 
 use crate::{
     Db,
     Error,
-    account::Account,
+    partner::partner_id_of,
 };
 
 use super::Transaction;
@@ -20,12 +24,47 @@ impl Transaction {
         db: &Db,
     ) -> Result<i32, Error> {
         let mut tx = db.conn.begin().await?;
+        let transaction_id = self.insert_on(&mut tx).await?;
+        tx.commit().await?;
+
+        Ok(transaction_id)
+    }
+
+    // all or none of the journals are inserted
+    pub async fn insert_all(
+        db: &Db,
+        transactions: &[Transaction],
+    ) -> Result<Vec<i32>, Error> {
+        let mut tx = db.conn.begin().await?;
+        let mut ids = Vec::new();
+        for transaction in transactions {
+            ids.push(transaction.insert_on(&mut tx).await?);
+        }
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    // the statements of insert, within a transaction of the caller
+    // that posts the journal together with what it stands for. every
+    // query is on that connection, a caller waiting on a lock holds
+    // no second one from the pool.
+    pub(crate) async fn insert_on(
+        &self,
+        tx: &mut PgConnection,
+    ) -> Result<i32, Error> {
+        let partner_id = match &self.partner_name {
+            Some(name) => Some(partner_id_of(&mut *tx, name).await?),
+            None => None,
+        };
 
         let transaction_id = sqlx::query_as::<_, TransactionInsertResult>(
             r#"
             INSERT INTO transactions
-                (transaction_date, transaction_type, description)
-            VALUES ($1, $2, $3)
+                (transaction_date, transaction_type, description, partner_id,
+                created_by, tags)
+            VALUES ($1, $2, $3, $4,
+                (SELECT user_id FROM users WHERE user_name = $5), $6)
             RETURNING
                 transaction_id
             "#
@@ -33,16 +72,22 @@ impl Transaction {
         .bind(self.transaction_date)
         .bind(self.transaction_type.to_string())
         .bind(&self.description)
+        .bind(partner_id)
+        .bind(&self.created_by)
+        .bind(&self.tags)
         .fetch_one(&mut *tx)
         .await?.transaction_id;
 
         for d in &self.details {
-            let acc = match Account::by_name(db, &d.account_name).await {
-                Ok(acc) => acc,
-                Err(Error::RowNotFound)
-                    => return Err(Error::AccountNotFound),
-                Err(err) => return Err(err),
-            };
+            let account_id = sqlx::query_scalar::<_, i32>(
+                r#"
+                SELECT account_id FROM accounts WHERE account_name = $1
+                "#
+            )
+            .bind(&d.account_name)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(Error::AccountNotFound)?;
 
             let debit = match Decimal::from_f32_retain(d.debit_amount) {
                 Some(a) => a,
@@ -52,23 +97,36 @@ impl Transaction {
                 Some(a) => a,
                 None => return Err(Error::DecimalConvError(d.credit_amount)),
             };
+            // the shortest decimal of the rate, not every bit of the f32
+            let foreign = match &d.foreign {
+                Some(f) => Some((
+                    f.currency.clone(),
+                    Decimal::from_f32_retain(f.amount)
+                        .ok_or(Error::DecimalConvError(f.amount))?,
+                    Decimal::from_f32(f.rate)
+                        .ok_or(Error::DecimalConvError(f.rate))?,
+                )),
+                None => None,
+            };
             sqlx::query(
                 r#"
                 INSERT INTO transaction_details
-                    (transaction_id, account_id, debit_amount, credit_amount)
-                VALUES ($1, $2, $3, $4)
+                    (transaction_id, account_id, debit_amount, credit_amount,
+                    currency, foreign_amount, exchange_rate)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
                 "#
             )
             .bind(transaction_id)
-            .bind(acc.account_id)
+            .bind(account_id)
             .bind(debit)
             .bind(credit)
+            .bind(foreign.as_ref().map(|f| f.0.clone()))
+            .bind(foreign.as_ref().map(|f| f.1))
+            .bind(foreign.as_ref().map(|f| f.2))
             .execute(&mut *tx)
             .await?;
         }
 
-        tx.commit().await?;
-
         Ok(transaction_id)
     }
 
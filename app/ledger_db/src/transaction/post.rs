@@ -0,0 +1,82 @@
+use chrono::{
+    Datelike,
+    Days,
+    Months,
+    NaiveDate,
+};
+
+use crate::{
+    Db,
+    Error,
+    ReviewCount,
+    User,
+    UserRole,
+};
+
+use super::{
+    Transaction,
+    TransactionType,
+};
+
+impl Transaction {
+
+    // 決算, 損益 and 次期繰越, which close the year
+    pub fn is_closing(&self) -> bool {
+        matches!(
+            self.transaction_type,
+            TransactionType::Kessan | TransactionType::Soneki | TransactionType::ToNext,
+        )
+    }
+
+    // The transaction as posted by the user, None when no one signs in.
+    // The journals closing the year are left to the owner.
+    pub fn stamp(
+        mut self,
+        user: Option<&User>,
+    ) -> Result<Self, Error> {
+        let role = user.map(|u| u.user_role).unwrap_or(UserRole::Owner);
+        if self.is_closing() && role < UserRole::Owner {
+            return Err(Error::ClosingForbidden(
+                self.transaction_type.into_japanese()
+            ));
+        }
+        self.created_by = user.map(|u| u.user_name.clone());
+        Ok(self)
+    }
+
+    // The closing journals of a fiscal year, which begins on the first
+    // day of start_month, wait until its questions are answered.
+    pub async fn check_closable(
+        &self,
+        db: &Db,
+        start_month: u32,
+    ) -> Result<(), Error> {
+        if !self.is_closing() { return Ok(()); }
+        let date = self.transaction_date;
+        let y = match date.month() >= start_month {
+            true => date.year(),
+            false => date.year() - 1,
+        };
+        let start = NaiveDate::from_ymd_opt(y, start_month, 1)
+            .ok_or(Error::DateTimeError)?;
+        let end = start + Months::new(12) - Days::new(1);
+        let count = ReviewCount::by_period(db, start, end).await?;
+        match count.is_clear() {
+            true => Ok(()),
+            false => Err(Error::ReviewOpen(count.needs_review, count.open_comments)),
+        }
+    }
+
+    // both of the above, for whatever posts a journal
+    pub async fn posted_by(
+        self,
+        db: &Db,
+        user: Option<&User>,
+        start_month: u32,
+    ) -> Result<Self, Error> {
+        let transaction = self.stamp(user)?;
+        transaction.check_closable(db, start_month).await?;
+        Ok(transaction)
+    }
+
+}
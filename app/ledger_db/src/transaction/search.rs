@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::{
+    prelude::FromPrimitive,
+    Decimal,
+};
+use sqlx::{
+    Postgres,
+    QueryBuilder,
+};
+
+use crate::{
+    Db,
+    Error,
+    account::AmountSide,
+};
+
+use super::{
+    Transaction,
+    TransactionType,
+};
+
+// the total is the sum of the debit lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchSort {
+    DateAsc,
+    #[default]
+    DateDesc,
+    AmountAsc,
+    AmountDesc,
+}
+
+// the sort key and the id of the last transaction of a page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchCursor {
+    Date(NaiveDate, i32),
+    Amount(Decimal, i32),
+}
+
+// Every filter left None takes every transaction. The amounts, the side
+// and the account are of one and the same line.
+#[derive(Debug, Default)]
+pub struct TransactionSearch {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub transaction_type: Option<TransactionType>,
+    pub account_name: Option<String>,
+    // None is either side
+    pub side: Option<AmountSide>,
+    pub min_amount: Option<f32>,
+    pub max_amount: Option<f32>,
+    // a part of the description
+    pub description: Option<String>,
+    pub partner_name: Option<String>,
+    // all of them
+    pub tags: Vec<String>,
+    pub sort: SearchSort,
+    pub after: Option<SearchCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug)]
+pub struct SearchPage {
+    pub transactions: Vec<Transaction>,
+    // None on the last page
+    pub next: Option<SearchCursor>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SearchSelectResult {
+    transaction_id: i32,
+    transaction_date: NaiveDate,
+    total_amount: Decimal,
+}
+
+impl SearchSelectResult {
+
+    fn cursor(&self, sort: SearchSort) -> SearchCursor {
+        match sort {
+            SearchSort::DateAsc | SearchSort::DateDesc
+                => SearchCursor::Date(self.transaction_date, self.transaction_id),
+            SearchSort::AmountAsc | SearchSort::AmountDesc
+                => SearchCursor::Amount(self.total_amount, self.transaction_id),
+        }
+    }
+
+}
+
+// % and _ of the input are searched for as they are
+fn like_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn decimal_of(amount: f32) -> Result<Decimal, Error> {
+    Decimal::from_f32(amount).ok_or(Error::DecimalConvError(amount))
+}
+
+impl TransactionSearch {
+
+    fn push_line_filter(
+        &self,
+        qb: &mut QueryBuilder<'_, Postgres>,
+    ) -> Result<(), Error> {
+        let any = self.account_name.is_some() || self.side.is_some()
+            || self.min_amount.is_some() || self.max_amount.is_some();
+        if !any { return Ok(()); }
+        qb.push(
+            r#"
+                AND EXISTS (
+                    SELECT 1 FROM transaction_details td
+                        INNER JOIN accounts a
+                        ON td.account_id = a.account_id
+                    WHERE td.transaction_id = t.transaction_id"#
+        );
+        if let Some(account_name) = &self.account_name {
+            qb.push(" AND a.account_name = ").push_bind(account_name.clone());
+        }
+        match self.side {
+            Some(AmountSide::Debit) => { qb.push(" AND td.debit_amount <> 0"); },
+            Some(AmountSide::Credit) => { qb.push(" AND td.credit_amount <> 0"); },
+            None => (),
+        }
+        if let Some(min) = self.min_amount {
+            qb.push(" AND td.debit_amount + td.credit_amount >= ")
+                .push_bind(decimal_of(min)?);
+        }
+        if let Some(max) = self.max_amount {
+            qb.push(" AND td.debit_amount + td.credit_amount <= ")
+                .push_bind(decimal_of(max)?);
+        }
+        qb.push(")");
+        Ok(())
+    }
+
+    fn query(&self) -> Result<QueryBuilder<'_, Postgres>, Error> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            r#"
+            WITH matched AS (
+                SELECT
+                    t.transaction_id,
+                    t.transaction_date,
+                    (
+                        SELECT COALESCE(SUM(td.debit_amount), 0)
+                        FROM transaction_details td
+                        WHERE td.transaction_id = t.transaction_id
+                    ) AS total_amount
+                FROM transactions t
+                    LEFT OUTER JOIN partners p
+                    ON t.partner_id = p.partner_id
+                WHERE TRUE"#
+        );
+        if let Some(from) = self.from {
+            qb.push(" AND t.transaction_date >= ").push_bind(from);
+        }
+        if let Some(to) = self.to {
+            qb.push(" AND t.transaction_date <= ").push_bind(to);
+        }
+        if let Some(transaction_type) = &self.transaction_type {
+            qb.push(" AND t.transaction_type = ").push_bind(transaction_type.to_string());
+        }
+        if let Some(description) = &self.description {
+            qb.push(" AND t.description ILIKE ").push_bind(like_pattern(description));
+        }
+        if let Some(partner_name) = &self.partner_name {
+            qb.push(" AND p.partner_name = ").push_bind(partner_name.clone());
+        }
+        if !self.tags.is_empty() {
+            qb.push(" AND t.tags @> ").push_bind(self.tags.clone())
+                .push("::VARCHAR(50)[]");
+        }
+        self.push_line_filter(&mut qb)?;
+        qb.push(
+            r#"
+            )
+            SELECT
+                transaction_id, transaction_date, total_amount
+            FROM matched"#
+        );
+        let (column, order) = match self.sort {
+            SearchSort::DateAsc => ("transaction_date", "ASC"),
+            SearchSort::DateDesc => ("transaction_date", "DESC"),
+            SearchSort::AmountAsc => ("total_amount", "ASC"),
+            SearchSort::AmountDesc => ("total_amount", "DESC"),
+        };
+        let after = if order == "ASC" { ">" } else { "<" };
+        match (&self.after, self.sort) {
+            (None, _) => (),
+            (Some(SearchCursor::Date(date, id)), SearchSort::DateAsc | SearchSort::DateDesc) => {
+                qb.push(format!(" WHERE ({}, transaction_id) {} (", column, after))
+                    .push_bind(*date).push(", ").push_bind(*id).push(")");
+            },
+            (Some(SearchCursor::Amount(amount, id)), SearchSort::AmountAsc | SearchSort::AmountDesc) => {
+                qb.push(format!(" WHERE ({}, transaction_id) {} (", column, after))
+                    .push_bind(*amount).push(", ").push_bind(*id).push(")");
+            },
+            // a cursor of another sort
+            _ => return Err(Error::InvalidCursor),
+        }
+        qb.push(format!(
+            " ORDER BY {} {}, transaction_id {} LIMIT ", column, order, order,
+        ));
+        qb.push_bind(self.limit + 1);
+        Ok(qb)
+    }
+
+}
+
+impl Transaction {
+
+    // a page of the transactions found, in the order of the sort
+    pub async fn search(
+        db: &Db,
+        search: &TransactionSearch,
+    ) -> Result<SearchPage, Error> {
+        let mut qb = search.query()?;
+        let mut hits = qb.build_query_as::<SearchSelectResult>()
+            .fetch_all(&db.conn).await?;
+        let next = match hits.len() as i64 > search.limit {
+            true => {
+                hits.truncate(search.limit.max(0) as usize);
+                hits.last().map(|h| h.cursor(search.sort))
+            },
+            false => None,
+        };
+
+        let ids = hits.iter().map(|h| h.transaction_id).collect::<Vec<i32>>();
+        let mut by_id = Transaction::by_ids(db, &ids).await?.into_iter()
+            .map(|t| (t.transaction_id, t))
+            .collect::<HashMap<i32, Transaction>>();
+        let transactions = ids.iter()
+            .filter_map(|id| by_id.remove(id))
+            .collect::<Vec<Transaction>>();
+        Ok(SearchPage { transactions, next })
+    }
+
+}
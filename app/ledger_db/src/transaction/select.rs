@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::convert::From;
 
 use chrono::{
+    Days,
     Months,
     NaiveDate,
 };
@@ -13,9 +14,11 @@ use rust_decimal::{
 use crate::{
     Db,
     Error,
+    review::ReviewStatus,
 };
 
 use super::{
+    ForeignAmount,
     Transaction,
     TransactionDetail,
 };
@@ -26,10 +29,18 @@ struct TransactionSelectResult {
     transaction_date: NaiveDate,
     transaction_type: String,
     description: String,
+    partner_name: Option<String>,
+    created_by: Option<String>,
+    tags: Vec<String>,
+    review_status: Option<String>,
+    open_comments: i64,
     account_name: String,
     account_type: String,
     debit_amount: Decimal,
     credit_amount: Decimal,
+    currency: Option<String>,
+    foreign_amount: Option<Decimal>,
+    exchange_rate: Option<Decimal>,
 }
 
 impl From<&TransactionSelectResult> for TransactionDetail {
@@ -42,6 +53,14 @@ impl From<&TransactionSelectResult> for TransactionDetail {
             account_type: (&value.account_type).into(),
             debit_amount: value.debit_amount.to_f32().unwrap_or(0_f32),
             credit_amount: value.credit_amount.to_f32().unwrap_or(0_f32),
+            foreign: match (&value.currency, value.foreign_amount, value.exchange_rate) {
+                (Some(currency), Some(amount), Some(rate)) => Some(ForeignAmount {
+                    currency: currency.clone(),
+                    amount: amount.to_f32().unwrap_or(0_f32),
+                    rate: rate.to_f32().unwrap_or(0_f32),
+                }),
+                _ => None,
+            },
         }
     }
 
@@ -57,6 +76,12 @@ impl Transaction {
             transaction_date: tsr.transaction_date,
             transaction_type: (&tsr.transaction_type).into(),
             description: tsr.description.clone(),
+            partner_name: tsr.partner_name.clone(),
+            created_by: tsr.created_by.clone(),
+            tags: tsr.tags.clone(),
+            review_status: tsr.review_status.as_deref()
+                .and_then(ReviewStatus::parse),
+            open_comments: tsr.open_comments,
             details: Vec::new(),
         }
     }
@@ -68,12 +93,7 @@ impl Transaction {
 
         let mut id_map = HashMap::new();
         for tsr in &value {
-            if !id_map.contains_key(&tsr.transaction_id) {
-                id_map.insert(
-                    tsr.transaction_id,
-                    Transaction::init_from(tsr),
-                );
-            }
+            id_map.entry(tsr.transaction_id).or_insert_with(|| Transaction::init_from(tsr));
             id_map.get_mut(&tsr.transaction_id).unwrap()
             .details.push(tsr.into());
         }
@@ -97,7 +117,16 @@ impl Transaction {
         let start_date
             = NaiveDate::from_ymd_opt(year, month, 1)
                 .ok_or(Error::DateTimeError)?;
-        let end_date = start_date + Months::new(1_u32);
+        let end_date = start_date + Months::new(1_u32) - Days::new(1_u64);
+        Transaction::by_period(db, start_date, end_date).await
+    }
+
+    // the transactions from start_date to end_date, both inclusive
+    pub async fn by_period(
+        db: &Db,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<Transaction>, Error> {
         let query = sqlx::query_as::<_, TransactionSelectResult>(
             r#"
             SELECT
@@ -105,18 +134,34 @@ impl Transaction {
                 t.transaction_date,
                 t.transaction_type,
                 t.description,
+                p.partner_name,
+                u.user_name AS created_by,
+                t.tags::TEXT[] AS tags,
+                t.review_status,
+                (
+                    SELECT COUNT(*) FROM transaction_comments c
+                    WHERE c.transaction_id = t.transaction_id
+                        AND NOT c.resolved
+                ) AS open_comments,
                 a.account_name,
                 a.account_type,
                 td.debit_amount,
-                td.credit_amount
+                td.credit_amount,
+                td.currency,
+                td.foreign_amount,
+                td.exchange_rate
             FROM transactions t
+                LEFT OUTER JOIN partners p
+                ON t.partner_id = p.partner_id
+                LEFT OUTER JOIN users u
+                ON t.created_by = u.user_id
                 LEFT OUTER JOIN transaction_details td
                 ON t.transaction_id = td.transaction_id
                 LEFT OUTER JOIN accounts a
                 ON td.account_id = a.account_id
             WHERE
                 t.transaction_date >= $1
-                AND t.transaction_date < $2
+                AND t.transaction_date <= $2
             ORDER BY
                 t.transaction_date ASC,
                 t.transaction_type ASC,
@@ -132,5 +177,57 @@ impl Transaction {
         Ok(Transaction::from(query.fetch_all(&db.conn).await?))
     }
 
-}
+    // in the order of the dates, not of the ids
+    pub async fn by_ids(
+        db: &Db,
+        transaction_ids: &[i32],
+    ) -> Result<Vec<Transaction>, Error> {
+        let query = sqlx::query_as::<_, TransactionSelectResult>(
+            r#"
+            SELECT
+                t.transaction_id,
+                t.transaction_date,
+                t.transaction_type,
+                t.description,
+                p.partner_name,
+                u.user_name AS created_by,
+                t.tags::TEXT[] AS tags,
+                t.review_status,
+                (
+                    SELECT COUNT(*) FROM transaction_comments c
+                    WHERE c.transaction_id = t.transaction_id
+                        AND NOT c.resolved
+                ) AS open_comments,
+                a.account_name,
+                a.account_type,
+                td.debit_amount,
+                td.credit_amount,
+                td.currency,
+                td.foreign_amount,
+                td.exchange_rate
+            FROM transactions t
+                LEFT OUTER JOIN partners p
+                ON t.partner_id = p.partner_id
+                LEFT OUTER JOIN users u
+                ON t.created_by = u.user_id
+                LEFT OUTER JOIN transaction_details td
+                ON t.transaction_id = td.transaction_id
+                LEFT OUTER JOIN accounts a
+                ON td.account_id = a.account_id
+            WHERE
+                t.transaction_id = ANY($1)
+            ORDER BY
+                t.transaction_date ASC,
+                t.transaction_type ASC,
+                t.transaction_id ASC,
+                td.debit_amount DESC,
+                td.credit_amount DESC,
+                td.transaction_detail_id ASC
+            "#
+        )
+        .bind(transaction_ids);
 
+        Ok(Transaction::from(query.fetch_all(&db.conn).await?))
+    }
+
+}
@@ -0,0 +1,75 @@
+use std::convert::From;
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+pub enum TaxCode {
+    Taxable10,  // 課税10%
+    Taxable8,  // 軽減8%
+    NonTaxable,  // 非課税
+    OutOfScope,  // 対象外
+}
+
+impl TaxCode {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            TaxCode::Taxable10 => "課税10%".to_string(),
+            TaxCode::Taxable8 => "軽減8%".to_string(),
+            TaxCode::NonTaxable => "非課税".to_string(),
+            TaxCode::OutOfScope => "対象外".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "課税10%" | "課税" | "10%" => Some(TaxCode::Taxable10),
+            "軽減8%" | "軽減" | "8%" => Some(TaxCode::Taxable8),
+            "非課税" => Some(TaxCode::NonTaxable),
+            "対象外" | "不課税" => Some(TaxCode::OutOfScope),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        TaxCode::from_str(value).ok()
+            .or_else(|| TaxCode::from_japanese(value))
+    }
+
+    pub fn rate(&self) -> u32 {
+        match self {
+            TaxCode::Taxable10 => 10,
+            TaxCode::Taxable8 => 8,
+            TaxCode::NonTaxable => 0,
+            TaxCode::OutOfScope => 0,
+        }
+    }
+
+    // consumption tax included in a tax-inclusive total, rounded down
+    pub fn tax_of(&self, total: f32) -> f32 {
+        let rate = self.rate() as f32;
+        (total * rate / (100_f32 + rate)).floor()
+    }
+
+}
+
+impl From<&String> for TaxCode {
+
+    fn from(
+        value: &String,
+    ) -> Self {
+        TaxCode::from_str(value)
+        .unwrap_or_else(|_| {
+            TaxCode::from_japanese(value)
+            .unwrap_or(TaxCode::OutOfScope)
+        })
+    }
+
+}
@@ -0,0 +1,34 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::Transaction;
+
+impl Transaction {
+
+    // the tags are replaced as a whole
+    pub async fn set_tags(
+        db: &Db,
+        transaction_id: i32,
+        tags: &[String],
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE transactions
+            SET tags = $2
+            WHERE transaction_id = $1
+            "#
+        )
+        .bind(transaction_id)
+        .bind(tags)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
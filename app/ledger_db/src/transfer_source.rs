@@ -0,0 +1,15 @@
+mod insert;
+mod select;
+
+use crate::partner::BankAccount;
+
+// our bank account transfers are paid from. account_name is the ledger
+// account of it, requester_code the 委託者コード agreed with the bank,
+// and the account holder of bank_account the 委託者名.
+#[derive(Debug)]
+pub struct TransferSource {
+    pub source_id: i32,
+    pub account_name: String,
+    pub requester_code: String,
+    pub bank_account: BankAccount,
+}
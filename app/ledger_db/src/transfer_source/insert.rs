@@ -0,0 +1,56 @@
+use crate::{
+    Db,
+    Error,
+    staged_line::account_id_of,
+};
+
+use super::TransferSource;
+
+#[derive(Debug, sqlx::FromRow)]
+struct TransferSourceInsertResult {
+    source_id: i32,
+}
+
+impl TransferSource {
+
+    pub async fn upsert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let account_id = account_id_of(db, &self.account_name).await?;
+        let bank = &self.bank_account;
+        let query = sqlx::query_as::<_, TransferSourceInsertResult>(
+            r#"
+            INSERT INTO transfer_sources
+                (account_id, requester_code, requester_name, bank_code,
+                bank_name, branch_code, branch_name, deposit_kind,
+                account_number)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (account_id)
+            DO UPDATE SET
+                requester_code = EXCLUDED.requester_code,
+                requester_name = EXCLUDED.requester_name,
+                bank_code = EXCLUDED.bank_code,
+                bank_name = EXCLUDED.bank_name,
+                branch_code = EXCLUDED.branch_code,
+                branch_name = EXCLUDED.branch_name,
+                deposit_kind = EXCLUDED.deposit_kind,
+                account_number = EXCLUDED.account_number
+            RETURNING
+                source_id
+            "#
+        )
+        .bind(account_id)
+        .bind(&self.requester_code)
+        .bind(&bank.account_holder)
+        .bind(&bank.bank_code)
+        .bind(&bank.bank_name)
+        .bind(&bank.branch_code)
+        .bind(&bank.branch_name)
+        .bind(bank.deposit_kind.to_string())
+        .bind(&bank.account_number);
+
+        Ok(query.fetch_one(&db.conn).await?.source_id)
+    }
+
+}
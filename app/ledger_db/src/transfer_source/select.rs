@@ -0,0 +1,105 @@
+use std::convert::From;
+
+use crate::{
+    Db,
+    Error,
+    partner::BankAccount,
+};
+
+use super::TransferSource;
+
+#[derive(Debug, sqlx::FromRow)]
+struct TransferSourceSelectResult {
+    source_id: i32,
+    account_name: String,
+    requester_code: String,
+    requester_name: String,
+    bank_code: String,
+    bank_name: String,
+    branch_code: String,
+    branch_name: String,
+    deposit_kind: String,
+    account_number: String,
+}
+
+impl TransferSource {
+
+    pub async fn by_account(
+        db: &Db,
+        account_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, TransferSourceSelectResult>(
+            r#"
+            SELECT
+                s.source_id,
+                a.account_name,
+                s.requester_code,
+                s.requester_name,
+                s.bank_code,
+                s.bank_name,
+                s.branch_code,
+                s.branch_name,
+                s.deposit_kind,
+                s.account_number
+            FROM transfer_sources s
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+            WHERE a.account_name = $1
+            "#
+        )
+        .bind(account_name);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, TransferSourceSelectResult>(
+            r#"
+            SELECT
+                s.source_id,
+                a.account_name,
+                s.requester_code,
+                s.requester_name,
+                s.bank_code,
+                s.bank_name,
+                s.branch_code,
+                s.branch_name,
+                s.deposit_kind,
+                s.account_number
+            FROM transfer_sources s
+                INNER JOIN accounts a
+                ON s.account_id = a.account_id
+            ORDER BY s.source_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<TransferSource>>())
+    }
+
+}
+
+impl From<&TransferSourceSelectResult> for TransferSource {
+
+    fn from(
+        value: &TransferSourceSelectResult,
+    ) -> Self {
+        TransferSource {
+            source_id: value.source_id,
+            account_name: value.account_name.clone(),
+            requester_code: value.requester_code.clone(),
+            bank_account: BankAccount {
+                bank_code: value.bank_code.clone(),
+                bank_name: value.bank_name.clone(),
+                branch_code: value.branch_code.clone(),
+                branch_name: value.branch_name.clone(),
+                deposit_kind: (&value.deposit_kind).into(),
+                account_number: value.account_number.clone(),
+                account_holder: value.requester_name.clone(),
+            },
+        }
+    }
+
+}
@@ -0,0 +1,28 @@
+mod user_role;
+mod insert;
+mod select;
+mod delete;
+
+use chrono::NaiveDateTime;
+
+pub use user_role::*;
+
+// someone signing in to the app, with the password hashed by the app
+#[derive(Debug, Clone)]
+pub struct User {
+    pub user_id: i32,
+    pub user_name: String,
+    pub password_hash: String,
+    pub user_role: UserRole,
+}
+
+// a token of a user for the cli and scripts. only the hash of the
+// token is kept, the token is shown once when it is made.
+#[derive(Debug)]
+pub struct ApiToken {
+    pub api_token_id: i32,
+    pub user_name: String,
+    pub token_name: String,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
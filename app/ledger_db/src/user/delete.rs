@@ -0,0 +1,64 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ApiToken,
+    User,
+};
+
+impl User {
+
+    // the tokens of the user go with it, and the journals it posted
+    // are kept without the user
+    pub async fn delete(
+        db: &Db,
+        user_name: &str,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM users
+            WHERE user_name = $1
+            "#
+        )
+        .bind(user_name)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
+
+impl ApiToken {
+
+    pub async fn delete(
+        db: &Db,
+        user_name: &str,
+        api_token_id: i32,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM api_tokens t
+            USING users u
+            WHERE t.user_id = u.user_id
+                AND u.user_name = $1
+                AND t.api_token_id = $2
+            "#
+        )
+        .bind(user_name)
+        .bind(api_token_id)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
@@ -0,0 +1,98 @@
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ApiToken,
+    User,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct UserInsertResult {
+    user_id: i32,
+}
+
+impl User {
+
+    pub async fn insert(
+        &self,
+        db: &Db,
+    ) -> Result<i32, Error> {
+        let result = sqlx::query_as::<_, UserInsertResult>(
+            r#"
+            INSERT INTO users
+                (user_name, password_hash, user_role)
+            VALUES ($1, $2, $3)
+            RETURNING
+                user_id
+            "#
+        )
+        .bind(&self.user_name)
+        .bind(&self.password_hash)
+        .bind(self.user_role.to_string())
+        .fetch_one(&db.conn)
+        .await?;
+
+        Ok(result.user_id)
+    }
+
+    pub async fn set_password(
+        db: &Db,
+        user_name: &str,
+        password_hash: &str,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET password_hash = $2
+            WHERE user_name = $1
+            "#
+        )
+        .bind(user_name)
+        .bind(password_hash)
+        .execute(&db.conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(Error::RowNotFound),
+            _ => Ok(()),
+        }
+    }
+
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ApiTokenInsertResult {
+    api_token_id: i32,
+}
+
+impl ApiToken {
+
+    pub async fn insert(
+        db: &Db,
+        user_name: &str,
+        token_name: &str,
+        token_hash: &str,
+    ) -> Result<i32, Error> {
+        let result = sqlx::query_as::<_, ApiTokenInsertResult>(
+            r#"
+            INSERT INTO api_tokens
+                (user_id, token_name, token_hash)
+            SELECT user_id, $2, $3
+            FROM users
+            WHERE user_name = $1
+            RETURNING
+                api_token_id
+            "#
+        )
+        .bind(user_name)
+        .bind(token_name)
+        .bind(token_hash)
+        .fetch_one(&db.conn)
+        .await?;
+
+        Ok(result.api_token_id)
+    }
+
+}
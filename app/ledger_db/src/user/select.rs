@@ -0,0 +1,153 @@
+use std::convert::From;
+
+use chrono::NaiveDateTime;
+
+use crate::{
+    Db,
+    Error,
+};
+
+use super::{
+    ApiToken,
+    User,
+    UserRole,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct UserSelectResult {
+    user_id: i32,
+    user_name: String,
+    password_hash: String,
+    user_role: String,
+}
+
+impl User {
+
+    pub async fn by_name(
+        db: &Db,
+        user_name: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, UserSelectResult>(
+            r#"
+            SELECT
+                user_id, user_name, password_hash, user_role
+            FROM users
+            WHERE user_name = $1
+            "#
+        )
+        .bind(user_name);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    // the user of the token, which is marked as used
+    pub async fn by_token(
+        db: &Db,
+        token_hash: &str,
+    ) -> Result<Self, Error> {
+        let query = sqlx::query_as::<_, UserSelectResult>(
+            r#"
+            WITH used AS (
+                UPDATE api_tokens
+                SET last_used_at = CURRENT_TIMESTAMP
+                WHERE token_hash = $1
+                RETURNING user_id
+            )
+            SELECT
+                u.user_id, u.user_name, u.password_hash, u.user_role
+            FROM users u
+            JOIN used ON used.user_id = u.user_id
+            "#
+        )
+        .bind(token_hash);
+
+        Ok((&query.fetch_one(&db.conn).await?).into())
+    }
+
+    pub async fn all(
+        db: &Db,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, UserSelectResult>(
+            r#"
+            SELECT
+                user_id, user_name, password_hash, user_role
+            FROM users
+            ORDER BY user_id ASC
+            "#
+        );
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<User>>())
+    }
+
+}
+
+impl From<&UserSelectResult> for User {
+
+    fn from(
+        value: &UserSelectResult,
+    ) -> Self {
+        User {
+            user_id: value.user_id,
+            user_name: value.user_name.clone(),
+            password_hash: value.password_hash.clone(),
+            // an unknown role may do the least
+            user_role: UserRole::parse(&value.user_role)
+                .unwrap_or(UserRole::Accountant),
+        }
+    }
+
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ApiTokenSelectResult {
+    api_token_id: i32,
+    user_name: String,
+    token_name: String,
+    last_used_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+impl ApiToken {
+
+    pub async fn by_user(
+        db: &Db,
+        user_name: &str,
+    ) -> Result<Vec<Self>, Error> {
+        let query = sqlx::query_as::<_, ApiTokenSelectResult>(
+            r#"
+            SELECT
+                t.api_token_id,
+                u.user_name,
+                t.token_name,
+                t.last_used_at,
+                t.created_at
+            FROM api_tokens t
+            JOIN users u ON u.user_id = t.user_id
+            WHERE u.user_name = $1
+            ORDER BY t.api_token_id ASC
+            "#
+        )
+        .bind(user_name);
+
+        Ok(query.fetch_all(&db.conn).await?
+            .iter().map(|q| q.into()).collect::<Vec<ApiToken>>())
+    }
+
+}
+
+impl From<&ApiTokenSelectResult> for ApiToken {
+
+    fn from(
+        value: &ApiTokenSelectResult,
+    ) -> Self {
+        ApiToken {
+            api_token_id: value.api_token_id,
+            user_name: value.user_name.clone(),
+            token_name: value.token_name.clone(),
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+
+}
@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+// in the order of what they may do, so that a role covers the roles
+// before it
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum UserRole {
+    Accountant,  // reads the books and the reports
+    Bookkeeper,  // posts journals, but does not close the year
+    Owner,  // does everything, users and backups included
+}
+
+impl UserRole {
+
+    pub fn into_japanese(&self) -> String {
+        match self {
+            UserRole::Accountant => "会計士".to_string(),
+            UserRole::Bookkeeper => "記帳担当".to_string(),
+            UserRole::Owner => "事業主".to_string(),
+        }
+    }
+
+    pub fn from_japanese(ja: impl Into<String>) -> Option<Self> {
+        match &ja.into() as &str {
+            "会計士" => Some(UserRole::Accountant),
+            "記帳担当" => Some(UserRole::Bookkeeper),
+            "事業主" => Some(UserRole::Owner),
+            _ => None,
+        }
+    }
+
+    // the english name or the japanese one
+    pub fn parse(value: &str) -> Option<Self> {
+        UserRole::from_str(value).ok()
+            .or_else(|| UserRole::from_japanese(value))
+    }
+
+}